@@ -0,0 +1,106 @@
+use axum::http::header;
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::AppState;
+use crate::server::IncomingRequest;
+
+pub const TOKEN_QUERY_PARAM: &str = "access_token";
+pub const TOKEN_HEADER: &str = "X-Access-Token";
+/// Set after a valid `?access_token=` so browsers keep access while navigating.
+pub const TOKEN_COOKIE: &str = "fakeapi_access";
+
+pub type AccessTokens = Arc<RwLock<Vec<AccessToken>>>;
+
+/// A token granting access to the mock server until `expires_at`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// The token a request presents, from the query string, header or cookie.
+pub fn presented_token(incoming: &IncomingRequest) -> Option<String> {
+    if let Some((_, token)) = incoming.query.iter().find(|(name, _)| name == TOKEN_QUERY_PARAM) {
+        return Some(token.clone());
+    }
+    if let Some(token) = incoming.headers.get(TOKEN_HEADER).and_then(|v| v.to_str().ok()) {
+        return Some(token.to_string());
+    }
+    incoming
+        .headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == TOKEN_COOKIE)
+        .map(|(_, token)| token.to_string())
+}
+
+pub async fn is_valid(tokens: &AccessTokens, token: &str) -> bool {
+    let now = Utc::now();
+    tokens
+        .read()
+        .await
+        .iter()
+        .any(|t| t.token == token && t.expires_at > now)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccessTokenParams {
+    ttl_minutes: u32,
+}
+
+/// Issues a new expiring token; only enforced while `requireAccessToken` is on.
+#[tauri::command]
+pub async fn create_access_token(
+    state: tauri::State<'_, AppState>,
+    params: CreateAccessTokenParams,
+) -> Result<AccessToken, String> {
+    if params.ttl_minutes == 0 {
+        return Err("Token lifetime must be at least one minute".to_string());
+    }
+    let token = AccessToken {
+        token: rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect(),
+        expires_at: Utc::now() + Duration::minutes(i64::from(params.ttl_minutes)),
+    };
+
+    let mut tokens = state.access_tokens.write().await;
+    let now = Utc::now();
+    tokens.retain(|t| t.expires_at > now);
+    tokens.push(token.clone());
+    Ok(token)
+}
+
+#[tauri::command]
+pub async fn list_access_tokens(state: tauri::State<'_, AppState>) -> Result<Vec<AccessToken>, String> {
+    let now = Utc::now();
+    Ok(state
+        .access_tokens
+        .read()
+        .await
+        .iter()
+        .filter(|t| t.expires_at > now)
+        .cloned()
+        .collect())
+}
+
+/// Revokes every token, returning how many were still valid.
+#[tauri::command]
+pub async fn revoke_access_tokens(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let mut tokens = state.access_tokens.write().await;
+    let now = Utc::now();
+    let active = tokens.iter().filter(|t| t.expires_at > now).count();
+    tokens.clear();
+    Ok(active)
+}
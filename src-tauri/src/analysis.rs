@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dependencies::worst_case_delay;
+use crate::endpoints::{AppState, Endpoint, EndpointKind, ResponseSource};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeLatencyParams {
+    /// Overrides the project's `latency_budget_ms` for this run.
+    budget_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyWarning {
+    pub endpoint_id: String,
+    pub method: String,
+    pub path: String,
+    pub delay: u64,
+    pub budget: u64,
+}
+
+/// Flags endpoints whose delay, including upstream dependencies, exceeds the
+/// latency budget. Jittered endpoints count with their slowest likely delay.
+#[tauri::command]
+pub async fn analyze_latency(
+    state: tauri::State<'_, AppState>,
+    params: AnalyzeLatencyParams,
+) -> Result<Vec<LatencyWarning>, String> {
+    let budget = match params.budget_ms {
+        Some(budget) => budget,
+        None => state
+            .server_settings
+            .read()
+            .await
+            .latency_budget_ms
+            .ok_or_else(|| "No latency budget configured".to_string())?,
+    };
+
+    Ok(latency_warnings(&state.endpoints.read().await, budget))
+}
+
+fn latency_warnings(endpoints: &[Endpoint], budget: u64) -> Vec<LatencyWarning> {
+    endpoints
+        .iter()
+        .map(|e| (e, worst_case_delay(endpoints, e)))
+        .filter(|(_, delay)| *delay > budget)
+        .map(|(e, delay)| LatencyWarning {
+            endpoint_id: e.id.clone(),
+            method: e.method.clone(),
+            path: e.path.clone(),
+            delay,
+            budget,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadWarning {
+    pub endpoint_id: String,
+    pub method: String,
+    pub path: String,
+    pub message: String,
+}
+
+/// Headers that legitimately differ between a HEAD and a GET response.
+fn comparable_header(name: &str) -> bool {
+    !name.eq_ignore_ascii_case("Content-Length") && !name.eq_ignore_ascii_case("Date")
+}
+
+fn header_differences(head: &Endpoint, get: &Endpoint) -> Vec<String> {
+    let value = |endpoint: &Endpoint, name: &str| {
+        endpoint
+            .headers
+            .iter()
+            .filter(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone())
+            .collect::<Vec<_>>()
+    };
+    let mut names: Vec<String> = head
+        .headers
+        .iter()
+        .chain(&get.headers)
+        .map(|(name, _)| name.to_ascii_lowercase())
+        .filter(|name| comparable_header(name))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| value(head, name) != value(get, name))
+        .collect()
+}
+
+fn explicit_head_warnings(head: &Endpoint, get: &Endpoint) -> Vec<String> {
+    let mut messages = Vec::new();
+    if head.status != get.status {
+        messages.push(format!("Status {} differs from GET's {}", head.status, get.status));
+    }
+    if head.content_type != get.content_type {
+        messages.push(format!("Content type {} differs from GET's {}", head.content_type, get.content_type));
+    }
+    for name in header_differences(head, get) {
+        messages.push(format!("Header '{}' differs from GET", name));
+    }
+    if !head.response.is_empty() {
+        messages.push("Response body is never sent for HEAD".to_string());
+    }
+    messages
+}
+
+/// Reasons a GET endpoint's automatic HEAD answer may not match its GET answer.
+fn auto_head_warnings(get: &Endpoint) -> Vec<String> {
+    let mut messages = Vec::new();
+    if !get.sequence.is_empty() {
+        messages.push("HEAD requests advance the sequence, so the next GET gets a different step".to_string());
+    }
+    if get.sequence.is_empty() && !get.variants.is_empty() {
+        messages.push("Variants are drawn per request, so HEAD and GET can disagree on status and headers".to_string());
+    }
+    match get.response_source {
+        ResponseSource::Script => {
+            messages.push("Script responses may branch on the method; check HEAD gets GET's status and headers".to_string())
+        }
+        ResponseSource::Events => messages.push("HEAD on an event stream stays open like the stream".to_string()),
+        _ => {}
+    }
+    messages
+}
+
+/// Flags HEAD endpoints whose status or headers drift from the GET endpoint
+/// on the same path, and GET endpoints whose automatic HEAD answer can drift.
+#[tauri::command]
+pub async fn check_head_consistency(state: tauri::State<'_, AppState>) -> Result<Vec<HeadWarning>, String> {
+    let endpoints = state.endpoints.read().await;
+    let mocks: Vec<&Endpoint> = endpoints
+        .iter()
+        .filter(|e| e.enabled && e.kind == EndpointKind::Mock)
+        .collect();
+    let same_route = |a: &Endpoint, b: &Endpoint| a.path == b.path && a.server == b.server;
+
+    let mut warnings = Vec::new();
+    for endpoint in &mocks {
+        let messages = if endpoint.method.eq_ignore_ascii_case("HEAD") {
+            match mocks
+                .iter()
+                .find(|get| get.method.eq_ignore_ascii_case("GET") && same_route(get, endpoint))
+            {
+                Some(get) => explicit_head_warnings(endpoint, get),
+                None => Vec::new(),
+            }
+        } else if endpoint.method.eq_ignore_ascii_case("GET")
+            && !mocks
+                .iter()
+                .any(|head| head.method.eq_ignore_ascii_case("HEAD") && same_route(head, endpoint))
+        {
+            auto_head_warnings(endpoint)
+        } else {
+            Vec::new()
+        };
+        warnings.extend(messages.into_iter().map(|message| HeadWarning {
+            endpoint_id: endpoint.id.clone(),
+            method: endpoint.method.clone(),
+            path: endpoint.path.clone(),
+            message,
+        }));
+    }
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jitter::DelayJitter;
+
+    #[test]
+    fn jittered_endpoints_are_judged_by_their_upper_bound() {
+        let mut upstream = Endpoint::new("GET".to_string(), "/upstream".to_string(), 200, 0, String::new());
+        upstream.delay_jitter = Some(DelayJitter::Uniform { min_ms: 0, max_ms: 500 });
+        let mut endpoint = Endpoint::new("GET".to_string(), "/orders".to_string(), 200, 100, String::new());
+        endpoint.depends_on = vec![upstream.id.clone()];
+        let endpoints = [upstream, endpoint];
+
+        for _ in 0..2 {
+            let warnings = latency_warnings(&endpoints, 550);
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].path, "/orders");
+            assert_eq!(warnings[0].delay, 600);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Requires a header carrying one of a set of keys, as many third-party
+/// APIs do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyAuth {
+    #[serde(default = "default_header")]
+    pub header: String,
+    /// Accepted keys; with none configured every request is refused.
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// JSON body for 401s; a generic error when unset.
+    #[serde(default, alias = "unauthorizedBody")]
+    pub unauthorized_body: Option<String>,
+}
+
+fn default_header() -> String {
+    "X-Api-Key".to_string()
+}
+
+/// `Err` with the 401 to send when the key is missing or unknown.
+pub fn authorize(config: &ApiKeyAuth, incoming: &IncomingRequest) -> Result<(), MockResponse> {
+    let reason = match incoming.headers.get(config.header.as_str()).and_then(|v| v.to_str().ok()) {
+        Some(key) if config.keys.iter().any(|accepted| accepted == key) => return Ok(()),
+        Some(_) => format!("Invalid API key in {}", config.header),
+        None => format!("Missing API key in {}", config.header),
+    };
+    let body = config
+        .unauthorized_body
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({ "error": reason }).to_string());
+    Err(MockResponse::json(StatusCode::UNAUTHORIZED, body))
+}
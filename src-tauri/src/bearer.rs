@@ -0,0 +1,192 @@
+use axum::http::{header, StatusCode};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Requires `Authorization: Bearer <token>`, answering 401 when the token is
+/// missing or invalid and 403 when it lacks the required claims.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BearerAuth {
+    /// HS256 key; when set the token must be a JWT signed with it. Any
+    /// non-empty token is accepted otherwise.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Accept JWTs signed with the OIDC provider's generated ES256 key.
+    #[serde(default)]
+    pub oidc: bool,
+    /// Reject JWTs whose `exp` has passed or whose `nbf` is still ahead.
+    #[serde(default = "default_check_expiry", alias = "checkExpiry")]
+    pub check_expiry: bool,
+    /// Claims the JWT payload must carry with exactly these values, e.g.
+    /// `{"role": "admin"}`.
+    #[serde(default, alias = "requiredClaims")]
+    pub required_claims: HashMap<String, Value>,
+    /// JSON body for 401s; a generic error when unset.
+    #[serde(default, alias = "unauthorizedBody")]
+    pub unauthorized_body: Option<String>,
+    /// JSON body for 403s; a generic error when unset.
+    #[serde(default, alias = "forbiddenBody")]
+    pub forbidden_body: Option<String>,
+}
+
+fn default_check_expiry() -> bool {
+    true
+}
+
+/// Why a request was turned away.
+enum Denial {
+    Unauthorized(String),
+    Forbidden(String),
+}
+
+fn bearer_token(incoming: &IncomingRequest) -> Option<&str> {
+    let value = incoming.headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("Bearer") && !token.is_empty()).then_some(token)
+}
+
+fn decode_segment(segment: &str) -> Option<Value> {
+    let bytes = URL_SAFE_NO_PAD.decode(segment.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// The JWT's payload, or `None` when the token isn't a JWT.
+pub(crate) fn payload(token: &str) -> Option<Value> {
+    let mut segments = token.split('.');
+    let (Some(_), Some(payload), Some(_), None) = (segments.next(), segments.next(), segments.next(), segments.next())
+    else {
+        return None;
+    };
+    decode_segment(payload)
+}
+
+/// Verifies an HS256 signature against `secret`, or an ES256 one against the
+/// generated OIDC key when `oidc` is set.
+pub(crate) fn verify(token: &str, secret: Option<&str>, oidc: bool) -> Result<(), String> {
+    let (signed, signature) = token.rsplit_once('.').ok_or("Token is not a JWT")?;
+    let header = signed.split('.').next().and_then(decode_segment).ok_or("Token is not a JWT")?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature.trim_end_matches('='))
+        .map_err(|_| "Token signature is not base64url")?;
+    match (header.get("alg").and_then(Value::as_str), secret) {
+        (Some("HS256"), Some(secret)) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+            mac.update(signed.as_bytes());
+            mac.verify_slice(&signature).map_err(|_| "Token signature is invalid".to_string())
+        }
+        (Some("ES256"), _) if oidc => crate::oidc::verify_es256(signed, &signature),
+        _ if oidc && secret.is_none() => Err("Token is not signed with ES256".to_string()),
+        _ => Err("Token is not signed with HS256".to_string()),
+    }
+}
+
+/// A compact HS256 JWT carrying `claims`.
+pub(crate) fn sign_hs256(claims: &Value, secret: &str) -> Result<String, String> {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+    let signed = format!("{}.{}", header, payload);
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+    mac.update(signed.as_bytes());
+    Ok(format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())))
+}
+
+fn check(config: &BearerAuth, incoming: &IncomingRequest) -> Result<(), Denial> {
+    let token = bearer_token(incoming).ok_or_else(|| Denial::Unauthorized("A bearer token is required".to_string()))?;
+    if config.secret.is_some() || config.oidc {
+        verify(token, config.secret.as_deref(), config.oidc).map_err(Denial::Unauthorized)?;
+    }
+    let claims = payload(token);
+    // Refresh tokens from the OAuth provider are only good at its token endpoint
+    let token_use = claims.as_ref().and_then(|c| c.get("token_use")).and_then(Value::as_str);
+    if token_use == Some(crate::oauth::REFRESH_TOKEN_USE) {
+        return Err(Denial::Unauthorized("Refresh tokens can't be used as access tokens".to_string()));
+    }
+
+    if config.check_expiry {
+        let now = chrono::Utc::now().timestamp();
+        let claim = |name: &str| claims.as_ref().and_then(|c| c.get(name)).and_then(Value::as_i64);
+        if claim("exp").is_some_and(|exp| exp <= now) {
+            return Err(Denial::Unauthorized("Token has expired".to_string()));
+        }
+        if claim("nbf").is_some_and(|nbf| nbf > now) {
+            return Err(Denial::Unauthorized("Token is not valid yet".to_string()));
+        }
+    }
+
+    for (name, expected) in &config.required_claims {
+        if claims.as_ref().and_then(|c| c.get(name)) != Some(expected) {
+            return Err(Denial::Forbidden(format!("Token lacks the required '{}' claim", name)));
+        }
+    }
+    Ok(())
+}
+
+/// `Err` with the 401/403 to send when the request doesn't pass.
+pub fn authorize(config: &BearerAuth, incoming: &IncomingRequest) -> Result<(), MockResponse> {
+    let (status, custom_body, reason) = match check(config, incoming) {
+        Ok(()) => return Ok(()),
+        Err(Denial::Unauthorized(reason)) => (StatusCode::UNAUTHORIZED, &config.unauthorized_body, reason),
+        Err(Denial::Forbidden(reason)) => (StatusCode::FORBIDDEN, &config.forbidden_body, reason),
+    };
+    let body = custom_body
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({ "error": reason }).to_string());
+    let mut response = MockResponse::json(status, body);
+    let error = if status == StatusCode::FORBIDDEN { "insufficient_scope" } else { "invalid_token" };
+    response.headers.push((
+        "WWW-Authenticate".to_string(),
+        format!("Bearer error=\"{}\", error_description=\"{}\"", error, reason.replace('"', "'")),
+    ));
+    Err(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+
+    fn config(secret: &str) -> BearerAuth {
+        serde_json::from_value(serde_json::json!({ "secret": secret })).unwrap()
+    }
+
+    fn request(token: &str) -> IncomingRequest {
+        let (parts, ()) = axum::http::Request::builder()
+            .header("Authorization", format!("Bearer {}", token))
+            .body(())
+            .unwrap()
+            .into_parts();
+        IncomingRequest::from_parts(&parts, Bytes::new())
+    }
+
+    #[test]
+    fn verifies_hs256_signatures() {
+        let token = sign_hs256(&serde_json::json!({ "sub": "ann" }), "secret").unwrap();
+        assert!(verify(&token, Some("secret"), false).is_ok());
+        assert!(verify(&token, Some("other"), false).is_err());
+        for malformed in ["", ".", "a.b", "a.b.c", "..", "a.b.!!!"] {
+            assert!(verify(malformed, Some("secret"), false).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_expired_and_refresh_tokens() {
+        let now = chrono::Utc::now().timestamp();
+        let sign = |claims: Value| sign_hs256(&claims, "secret").unwrap();
+        let valid = sign(serde_json::json!({ "exp": now + 60 }));
+        assert!(authorize(&config("secret"), &request(&valid)).is_ok());
+
+        let expired = sign(serde_json::json!({ "exp": now - 60 }));
+        let refresh = sign(serde_json::json!({ "exp": now + 60, "token_use": "refresh" }));
+        for token in [expired, refresh] {
+            let denied = authorize(&config("secret"), &request(&token)).unwrap_err();
+            assert_eq!(denied.status, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::server::MockResponse;
+
+/// Worker slots per endpoint, keyed by endpoint id and size so a changed
+/// limit starts a fresh pool.
+pub type ConcurrencyLimits = Arc<Mutex<HashMap<String, Arc<Semaphore>>>>;
+
+/// Caps how many requests an endpoint handles at once, like the worker pool
+/// of the real service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyLimit {
+    #[serde(alias = "maxInFlight")]
+    pub max_in_flight: u32,
+    #[serde(default)]
+    pub overflow: Overflow,
+}
+
+/// What happens to a request that arrives while every slot is taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overflow {
+    /// Wait for a slot, then take the endpoint's delay as usual.
+    #[default]
+    Queue,
+    /// Answer 503 straight away.
+    Reject,
+}
+
+pub enum Admission {
+    /// Holds a slot until dropped.
+    Admitted(OwnedSemaphorePermit),
+    /// Must wait on the pool for a slot.
+    Queued(Arc<Semaphore>),
+    Rejected(MockResponse),
+}
+
+impl Admission {
+    /// Waits for a queued request's slot; the slot is held until the result is dropped.
+    pub async fn wait(self) -> Option<OwnedSemaphorePermit> {
+        match self {
+            Admission::Admitted(permit) => Some(permit),
+            Admission::Queued(pool) => pool.acquire_owned().await.ok(),
+            Admission::Rejected(_) => None,
+        }
+    }
+}
+
+pub fn admit(limits: &ConcurrencyLimits, endpoint_id: &str, limit: &ConcurrencyLimit) -> Admission {
+    let size = limit.max_in_flight.max(1);
+    let pool = limits
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(format!("{}:{}", endpoint_id, size))
+        .or_insert_with(|| Arc::new(Semaphore::new(size as usize)))
+        .clone();
+    match pool.clone().try_acquire_owned() {
+        Ok(permit) => Admission::Admitted(permit),
+        Err(_) if limit.overflow == Overflow::Queue => Admission::Queued(pool),
+        Err(_) => Admission::Rejected(MockResponse::json(
+            StatusCode::SERVICE_UNAVAILABLE,
+            serde_json::json!({ "error": "Too many concurrent requests" }).to_string(),
+        )),
+    }
+}
@@ -0,0 +1,42 @@
+use axum_server::accept::Accept;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Holds each accepted connection for a while before handing it on. The TCP
+/// connect itself completes at once, since the OS accepts it; what the client
+/// waits on is the TLS handshake under TLS, and the first response byte for
+/// plain HTTP, where it can't tell the delay from a slow server.
+#[derive(Debug, Clone, Copy)]
+pub struct DelayAcceptor {
+    delay: Duration,
+}
+
+impl DelayAcceptor {
+    pub fn new(delay_ms: u64) -> Self {
+        Self {
+            delay: Duration::from_millis(delay_ms),
+        }
+    }
+}
+
+impl<I, S> Accept<I, S> for DelayAcceptor
+where
+    I: Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = I;
+    type Service = S;
+    type Future = Pin<Box<dyn Future<Output = io::Result<(I, S)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let delay = self.delay;
+        Box::pin(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            Ok((stream, service))
+        })
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::{AppState, Endpoint, EndpointKind, ServerSettings};
+
+/// Request headers a browser sends without a preflight (`Content-Type` is
+/// checked separately, as only some values are safe).
+const SAFELISTED_HEADERS: [&str; 4] = ["accept", "accept-language", "content-language", "range"];
+const SIMPLE_CONTENT_TYPES: [&str; 3] = ["application/x-www-form-urlencoded", "multipart/form-data", "text/plain"];
+/// Response headers readable from JavaScript without `Access-Control-Expose-Headers`.
+const SAFELISTED_RESPONSE_HEADERS: [&str; 7] = [
+    "cache-control",
+    "content-language",
+    "content-length",
+    "content-type",
+    "expires",
+    "last-modified",
+    "pragma",
+];
+
+/// A cross-origin call to simulate against every endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateCorsParams {
+    /// Origin of the calling page, e.g. `http://localhost:5173`.
+    origin: String,
+    /// Whether the client sends cookies (`credentials: "include"`).
+    #[serde(default)]
+    credentials: bool,
+    /// Extra request header names the client sends, e.g. `X-Request-Id`.
+    #[serde(default)]
+    request_headers: Vec<String>,
+    /// `Content-Type` of request bodies; `application/json` when unset.
+    content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorsFinding {
+    pub endpoint_id: String,
+    pub method: String,
+    pub path: String,
+    /// Whether the browser sends an `OPTIONS` preflight first.
+    pub preflight: bool,
+    /// Why the browser would block the call; empty when it succeeds.
+    pub failures: Vec<String>,
+    /// Problems that don't block the call, such as unreadable headers.
+    pub warnings: Vec<String>,
+}
+
+/// The method a browser would use to call the endpoint.
+fn call_method(endpoint: &Endpoint) -> String {
+    match endpoint.kind {
+        EndpointKind::Inbox => "POST".to_string(),
+        EndpointKind::Resource | EndpointKind::Static => "GET".to_string(),
+        _ => endpoint.method.to_uppercase(),
+    }
+}
+
+/// Request headers the call carries, including the ones the endpoint's auth requires.
+fn request_headers(params: &ValidateCorsParams, settings: &ServerSettings, endpoint: &Endpoint) -> Vec<String> {
+    let mut headers: Vec<String> = params.request_headers.iter().map(|h| h.to_ascii_lowercase()).collect();
+    if endpoint.bearer_auth.is_some() || settings.bearer_auth.is_some() {
+        headers.push("authorization".to_string());
+    }
+    if let Some(auth) = endpoint.api_key_auth.as_ref().or(settings.api_key_auth.as_ref()) {
+        headers.push(auth.header.to_ascii_lowercase());
+    }
+    headers.sort();
+    headers.dedup();
+    headers
+}
+
+fn check(params: &ValidateCorsParams, settings: &ServerSettings, endpoint: &Endpoint) -> CorsFinding {
+    let method = call_method(endpoint);
+    let headers = request_headers(params, settings, endpoint);
+    let sends_body = matches!(method.as_str(), "POST" | "PUT" | "PATCH");
+    let content_type = params.content_type.as_deref().unwrap_or("application/json");
+    let simple_content_type = SIMPLE_CONTENT_TYPES.iter().any(|simple| content_type.starts_with(simple));
+
+    let unsafe_headers: Vec<&String> = headers
+        .iter()
+        .filter(|h| !SAFELISTED_HEADERS.contains(&h.as_str()))
+        .collect();
+    let preflight = !matches!(method.as_str(), "GET" | "HEAD" | "POST")
+        || !unsafe_headers.is_empty()
+        || (sends_body && !simple_content_type);
+
+    // Every server answers with the permissive CORS policy: `*` for the
+    // origin, methods, request headers and exposed headers, no credentials.
+    let mut failures = Vec::new();
+    if params.credentials {
+        failures.push(format!(
+            "Credentialed requests from {} need the exact origin in Access-Control-Allow-Origin, but the server sends *",
+            params.origin
+        ));
+    }
+    if preflight {
+        if params.credentials && !matches!(method.as_str(), "GET" | "HEAD" | "POST") {
+            failures.push(format!("With credentials, Access-Control-Allow-Methods: * doesn't allow {}", method));
+        }
+        for header in &unsafe_headers {
+            if header.as_str() == "authorization" {
+                failures.push("Access-Control-Allow-Headers: * never covers Authorization".to_string());
+            } else if params.credentials {
+                failures.push(format!("With credentials, Access-Control-Allow-Headers: * doesn't allow {}", header));
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (name, _) in &endpoint.headers {
+        let lower = name.to_ascii_lowercase();
+        if lower.starts_with("access-control-") {
+            warnings.push(format!("{} is replaced by the server's CORS policy", name));
+        } else if params.credentials && !SAFELISTED_RESPONSE_HEADERS.contains(&lower.as_str()) {
+            warnings.push(format!(
+                "{} isn't readable from JavaScript: Access-Control-Expose-Headers: * doesn't apply with credentials",
+                name
+            ));
+        }
+    }
+
+    CorsFinding {
+        endpoint_id: endpoint.id.clone(),
+        method,
+        path: endpoint.path.clone(),
+        preflight,
+        failures,
+        warnings,
+    }
+}
+
+/// Simulates the browser's CORS checks, preflight included, for a call from
+/// `origin` to every enabled endpoint, and lists the endpoints with problems.
+#[tauri::command]
+pub async fn validate_cors(
+    state: tauri::State<'_, AppState>,
+    params: ValidateCorsParams,
+) -> Result<Vec<CorsFinding>, String> {
+    if params.origin.trim().is_empty() {
+        return Err("Origin is required".to_string());
+    }
+    let settings = state.server_settings.read().await;
+    let endpoints = state.endpoints.read().await;
+    Ok(endpoints
+        .iter()
+        .filter(|e| e.enabled && e.kind != EndpointKind::WebSocket)
+        .map(|endpoint| check(&params, &settings, endpoint))
+        .filter(|finding| !finding.failures.is_empty() || !finding.warnings.is_empty())
+        .collect())
+}
@@ -0,0 +1,102 @@
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, HeaderValue};
+use std::io::Read;
+
+/// Decodes a `Content-Encoding` of gzip, deflate, br or zstd (or a list of
+/// them) so matchers and the log see the plain body. The encoding headers
+/// are rewritten to describe the decoded body, and the encoded size is
+/// returned. Bodies with an unknown encoding are left untouched.
+pub fn decode(headers: &mut HeaderMap, body: Bytes, limit: usize) -> Result<(Bytes, Option<usize>), String> {
+    let Some(encoding) = headers.get(header::CONTENT_ENCODING).and_then(|v| v.to_str().ok()) else {
+        return Ok((body, None));
+    };
+    let codings: Vec<String> = encoding
+        .split(',')
+        .map(|coding| coding.trim().to_ascii_lowercase())
+        .filter(|coding| !coding.is_empty() && coding != "identity")
+        .collect();
+    if codings.is_empty() || !codings.iter().all(|coding| is_supported(coding)) {
+        return Ok((body, None));
+    }
+
+    let encoded_size = body.len();
+    let mut decoded = body.to_vec();
+    // Codings are listed in the order they were applied
+    for coding in codings.iter().rev() {
+        decoded = decode_one(coding, &decoded, limit)?;
+    }
+    headers.remove(header::CONTENT_ENCODING);
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(decoded.len()));
+    Ok((Bytes::from(decoded), Some(encoded_size)))
+}
+
+fn is_supported(coding: &str) -> bool {
+    matches!(coding, "gzip" | "x-gzip" | "deflate" | "br" | "zstd")
+}
+
+fn decode_one(coding: &str, data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read + '_> = match coding {
+        "gzip" | "x-gzip" => Box::new(flate2::read::MultiGzDecoder::new(data)),
+        "deflate" => Box::new(flate2::read::ZlibDecoder::new(data)),
+        "br" => Box::new(brotli::Decompressor::new(data, 4096)),
+        "zstd" => Box::new(zstd::stream::read::Decoder::new(data).map_err(|e| format!("Failed to decode zstd body: {}", e))?),
+        _ => return Ok(data.to_vec()),
+    };
+    // Read one byte past the limit to tell a full body from an oversized one
+    let mut decoded = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("Failed to decode {} body: {}", coding, e))?;
+    if decoded.len() > limit {
+        return Err(format!("Decoded {} body exceeds {} bytes", coding, limit));
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn encoded(encoding: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_str(encoding).unwrap());
+        headers
+    }
+
+    #[test]
+    fn decodes_stacked_codings_in_reverse() {
+        let body = gzip(&zstd::encode_all(&b"{\"id\": 1}"[..], 0).unwrap());
+        let mut headers = encoded("zstd, gzip");
+        let (decoded, raw_size) = decode(&mut headers, Bytes::from(body.clone()), 1024).unwrap();
+        assert_eq!(&decoded[..], b"{\"id\": 1}");
+        assert_eq!(raw_size, Some(body.len()));
+        assert!(headers.get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(headers[header::CONTENT_LENGTH], "9");
+    }
+
+    #[test]
+    fn unknown_codings_are_left_alone() {
+        let mut headers = encoded("gzip, compress");
+        let (body, raw_size) = decode(&mut headers, Bytes::from_static(b"abc"), 1024).unwrap();
+        assert_eq!((&body[..], raw_size), (&b"abc"[..], None));
+        assert!(headers.get(header::CONTENT_ENCODING).is_some());
+    }
+
+    #[test]
+    fn rejects_corrupt_and_oversized_bodies() {
+        for coding in ["gzip", "deflate", "zstd"] {
+            assert!(decode(&mut encoded(coding), Bytes::from_static(b"not compressed"), 1024).is_err());
+        }
+        let bomb = gzip(&[0; 4096]);
+        assert!(decode(&mut encoded("gzip"), Bytes::from(bomb.clone()), 4095).is_err());
+        assert!(decode(&mut encoded("gzip"), Bytes::from(bomb), 4096).is_ok());
+    }
+}
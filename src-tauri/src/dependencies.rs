@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use crate::endpoints::Endpoint;
+
+/// Total latency of an endpoint including every endpoint it (transitively)
+/// depends on, as if each upstream were called in sequence. Cycles are
+/// counted once. Endpoints with `delay_jitter` contribute a fresh sample.
+pub fn total_delay(endpoints: &[Endpoint], endpoint: &Endpoint) -> u64 {
+    let mut visited = HashSet::new();
+    accumulate_delay(endpoints, endpoint, &mut visited, &|e: &Endpoint| match &e.delay_jitter {
+        Some(jitter) => jitter.sample(),
+        None => e.delay,
+    })
+}
+
+/// `total_delay` taking the upper bound of each endpoint's jitter instead of
+/// a sample, so repeated runs agree.
+pub fn worst_case_delay(endpoints: &[Endpoint], endpoint: &Endpoint) -> u64 {
+    let mut visited = HashSet::new();
+    accumulate_delay(endpoints, endpoint, &mut visited, &|e: &Endpoint| match &e.delay_jitter {
+        Some(jitter) => jitter.upper_bound(),
+        None => e.delay,
+    })
+}
+
+/// `total_delay` counting only the fixed `delay` of each endpoint.
+pub fn fixed_delay(endpoints: &[Endpoint], endpoint: &Endpoint) -> u64 {
+    let mut visited = HashSet::new();
+    accumulate_delay(endpoints, endpoint, &mut visited, &|e: &Endpoint| e.delay)
+}
+
+fn accumulate_delay<'a>(
+    endpoints: &'a [Endpoint],
+    endpoint: &'a Endpoint,
+    visited: &mut HashSet<&'a str>,
+    own_delay: &dyn Fn(&Endpoint) -> u64,
+) -> u64 {
+    if !visited.insert(endpoint.id.as_str()) {
+        return 0;
+    }
+
+    own_delay(endpoint)
+        + endpoint
+            .depends_on
+            .iter()
+            .filter_map(|id| endpoints.iter().find(|e| &e.id == id))
+            .map(|dep| accumulate_delay(endpoints, dep, visited, own_delay))
+            .sum::<u64>()
+}
+
+/// Builds a body that embeds each dependency's response under its path,
+/// merged into the endpoint's own response when that is a JSON object.
+pub fn compose_body(endpoints: &[Endpoint], endpoint: &Endpoint) -> String {
+    let mut composed = match serde_json::from_str::<serde_json::Value>(&endpoint.response) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+
+    for dep in endpoint
+        .depends_on
+        .iter()
+        .filter_map(|id| endpoints.iter().find(|e| &e.id == id))
+    {
+        let body = serde_json::from_str(&dep.response)
+            .unwrap_or_else(|_| serde_json::Value::String(dep.response.clone()));
+        composed.insert(dep.path.clone(), body);
+    }
+
+    serde_json::Value::Object(composed).to_string()
+}
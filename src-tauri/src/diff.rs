@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::endpoints::Endpoint;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// How one endpoint's behavior differs between the old and new project.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointChange {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    pub kind: ChangeKind,
+    pub details: Vec<String>,
+}
+
+/// Compares endpoints by id, falling back to method + path for re-created entries.
+pub fn diff_endpoints(old: &[Endpoint], new: &[Endpoint]) -> Vec<EndpointChange> {
+    let mut changes = Vec::new();
+    let mut matched_old = vec![false; old.len()];
+
+    for endpoint in new {
+        match find_counterpart(endpoint, old).filter(|&i| !matched_old[i]) {
+            Some(index) => {
+                matched_old[index] = true;
+                let details = describe_changes(&old[index], endpoint);
+                if !details.is_empty() {
+                    changes.push(change(endpoint, ChangeKind::Changed, details));
+                }
+            }
+            None => changes.push(change(endpoint, ChangeKind::Added, Vec::new())),
+        }
+    }
+
+    for (endpoint, _) in old.iter().zip(matched_old).filter(|(_, matched)| !matched) {
+        changes.push(change(endpoint, ChangeKind::Removed, Vec::new()));
+    }
+
+    changes
+}
+
+fn find_counterpart(endpoint: &Endpoint, others: &[Endpoint]) -> Option<usize> {
+    others.iter().position(|o| o.id == endpoint.id).or_else(|| {
+        others
+            .iter()
+            .position(|o| o.method.eq_ignore_ascii_case(&endpoint.method) && o.path == endpoint.path)
+    })
+}
+
+fn change(endpoint: &Endpoint, kind: ChangeKind, details: Vec<String>) -> EndpointChange {
+    EndpointChange {
+        id: endpoint.id.clone(),
+        method: endpoint.method.clone(),
+        path: endpoint.path.clone(),
+        kind,
+        details,
+    }
+}
+
+fn describe_changes(old: &Endpoint, new: &Endpoint) -> Vec<String> {
+    let mut details = Vec::new();
+    if old.status != new.status {
+        details.push(format!("status {} -> {}", old.status, new.status));
+    }
+    if old.response != new.response {
+        details.push("response body changed".to_string());
+    }
+    if old.content_type != new.content_type {
+        details.push(format!("content type {} -> {}", old.content_type, new.content_type));
+    }
+    if old.delay != new.delay {
+        details.push(format!("delay {}ms -> {}ms", old.delay, new.delay));
+    }
+    if old.delay_jitter != new.delay_jitter {
+        details.push("delay jitter changed".to_string());
+    }
+    details
+}
@@ -0,0 +1,112 @@
+use crate::endpoints::Endpoint;
+
+/// Standalone HTML page describing every enabled endpoint. With `try_it` each
+/// endpoint gets a form that sends the request from the browser, so the page
+/// must be served from the mock server's own origin.
+pub fn render_page(title: &str, endpoints: &[Endpoint], try_it: bool) -> String {
+    let mut rows = String::new();
+    for endpoint in endpoints.iter().filter(|e| e.enabled) {
+        rows.push_str(&format!(
+            "<section class=\"endpoint\">\n<h2><span class=\"method {method_class}\">{method}</span> <code>{path}</code></h2>\n\
+             <p>Status <strong>{status}</strong> &middot; <code>{content_type}</code>{delay}</p>\n<pre>{body}</pre>\n{form}</section>\n",
+            method_class = html_escape(&endpoint.method.to_lowercase()),
+            method = html_escape(&endpoint.method),
+            path = html_escape(&endpoint.path),
+            status = endpoint.status,
+            content_type = html_escape(&endpoint.content_type),
+            delay = if let Some(jitter) = &endpoint.delay_jitter {
+                format!(" &middot; {}", html_escape(&jitter.to_string()))
+            } else if endpoint.delay > 0 {
+                format!(" &middot; {}ms delay", endpoint.delay)
+            } else {
+                String::new()
+            },
+            body = html_escape(&pretty_body(endpoint)),
+            form = if try_it { try_it_form(endpoint) } else { String::new() },
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n\
+         <body>\n<h1>{title}</h1>\n{rows}{script}</body>\n</html>\n",
+        title = html_escape(title),
+        script = if try_it { TRY_IT_SCRIPT } else { "" },
+    )
+}
+
+/// Wildcard segments are pre-filled with `1` for the user to edit.
+fn try_it_form(endpoint: &Endpoint) -> String {
+    let path = endpoint
+        .path
+        .split('/')
+        .map(|segment| if segment.contains('*') { "1" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/");
+    let method = if endpoint.kind == crate::endpoints::EndpointKind::Inbox {
+        "POST"
+    } else {
+        endpoint.method.as_str()
+    };
+    let body_input = if matches!(method, "POST" | "PUT" | "PATCH") {
+        "<textarea name=\"body\" rows=\"4\" placeholder=\"Request body\"></textarea>"
+    } else {
+        ""
+    };
+    format!(
+        "<form class=\"try-it\" data-method=\"{}\"><input name=\"path\" value=\"{}\">{}<button>Try it</button><pre hidden></pre></form>\n",
+        html_escape(method),
+        html_escape(&path),
+        body_input
+    )
+}
+
+pub(crate) fn pretty_body(endpoint: &Endpoint) -> String {
+    match endpoint.response_source {
+        crate::endpoints::ResponseSource::Script => return "(computed by script)".to_string(),
+        crate::endpoints::ResponseSource::Events => return "(server-sent event stream)".to_string(),
+        _ => {}
+    }
+    if let Some(path) = &endpoint.file_path {
+        if endpoint.response_source == crate::endpoints::ResponseSource::File {
+            let name = std::path::Path::new(path).file_name().map(|n| n.to_string_lossy());
+            return format!("(contents of {})", name.as_deref().unwrap_or("a file"));
+        }
+    }
+    serde_json::from_str::<serde_json::Value>(&endpoint.response)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| endpoint.response.clone())
+}
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#222}\
+.endpoint{border:1px solid #ddd;border-radius:6px;padding:0 1rem;margin:1rem 0}\
+.method{display:inline-block;min-width:4.5rem;text-align:center;border-radius:4px;color:#fff;background:#666;font-size:.9rem;padding:.1rem .4rem}\
+.get{background:#2b7de9}.post{background:#2a9d55}.put{background:#d98b00}.delete{background:#d33}.patch{background:#8a4fd3}\
+pre{background:#f6f8fa;padding:.75rem;overflow:auto;max-height:20rem}\
+.try-it{margin-bottom:1rem}.try-it input{width:60%;font-family:monospace}.try-it textarea{display:block;width:100%;margin:.5rem 0;font-family:monospace}";
+
+const TRY_IT_SCRIPT: &str = r#"<script>
+document.querySelectorAll("form.try-it").forEach(function (form) {
+  form.addEventListener("submit", async function (event) {
+    event.preventDefault();
+    var output = form.querySelector("pre");
+    var body = form.elements.body ? form.elements.body.value : undefined;
+    output.hidden = false;
+    output.textContent = "...";
+    try {
+      var response = await fetch(form.elements.path.value, { method: form.dataset.method, body: body || undefined });
+      output.textContent = response.status + " " + response.statusText + "\n\n" + await response.text();
+    } catch (error) {
+      output.textContent = String(error);
+    }
+  });
+});
+</script>
+"#;
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::endpoints::AppState;
+use crate::server::IncomingRequest;
+
+/// Recent fingerprints are pruned once the tracker holds this many.
+const MAX_TRACKED: usize = 10_000;
+const BODY_PREVIEW_CHARS: usize = 200;
+
+/// A request that arrived again within the duplicate window.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub method: String,
+    pub path: String,
+    pub body_preview: String,
+    /// Number of repeats, not counting the first request.
+    pub duplicates: usize,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// Spots identical method/path/body requests arriving within a short window,
+/// which usually means client retry storms or double submits.
+#[derive(Default)]
+pub struct DuplicateTracker {
+    last_seen: HashMap<u64, Instant>,
+    reports: HashMap<u64, DuplicateReport>,
+}
+
+impl DuplicateTracker {
+    pub fn record(&mut self, incoming: &IncomingRequest, window: Duration) {
+        let mut hasher = DefaultHasher::new();
+        incoming.method.as_str().hash(&mut hasher);
+        incoming.path.hash(&mut hasher);
+        incoming.body.hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        let now = Instant::now();
+        if self.last_seen.len() >= MAX_TRACKED {
+            self.last_seen.retain(|_, seen| now.duration_since(*seen) <= window);
+        }
+
+        let repeated = self
+            .last_seen
+            .insert(fingerprint, now)
+            .is_some_and(|previous| now.duration_since(previous) <= window);
+        if !repeated {
+            return;
+        }
+
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        let report = self.reports.entry(fingerprint).or_insert_with(|| DuplicateReport {
+            method: incoming.method.to_string(),
+            path: incoming.path.clone(),
+            body_preview: String::from_utf8_lossy(&incoming.body).chars().take(BODY_PREVIEW_CHARS).collect(),
+            duplicates: 0,
+            first_seen: timestamp.clone(),
+            last_seen: timestamp.clone(),
+        });
+        report.duplicates += 1;
+        report.last_seen = timestamp;
+    }
+}
+
+/// Duplicate requests seen so far, most repeated first.
+#[tauri::command]
+pub async fn get_duplicate_report(state: tauri::State<'_, AppState>) -> Result<Vec<DuplicateReport>, String> {
+    let mut reports: Vec<DuplicateReport> = state.duplicates.read().await.reports.values().cloned().collect();
+    reports.sort_by(|a, b| b.duplicates.cmp(&a.duplicates));
+    Ok(reports)
+}
+
+#[tauri::command]
+pub async fn clear_duplicate_report(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    *state.duplicates.write().await = DuplicateTracker::default();
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
@@ -12,24 +13,409 @@ pub struct Endpoint {
     pub path: String,
     pub status: u16,
     pub delay: u64,
+    /// Random delay drawn per request; replaces `delay` when set.
+    #[serde(default)]
+    pub delay_jitter: Option<crate::jitter::DelayJitter>,
     pub response: String,
+    #[serde(default)]
+    pub match_type: MatchType,
+    #[serde(default)]
+    pub match_query: Vec<QueryMatcher>,
+    /// Request headers that must be present with exactly these values.
+    #[serde(default)]
+    pub match_headers: HashMap<String, String>,
+    #[serde(default)]
+    pub match_body: Vec<BodyMatcher>,
+    /// Ids of upstream endpoints this one "calls"; their delays add to this one's.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Embed the upstream responses in this endpoint's body, keyed by their path.
+    #[serde(default)]
+    pub compose_dependencies: bool,
+    /// `Content-Type` of the response, e.g. `text/plain` or `application/xml`.
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+    /// Extra response headers, sent in order; a repeated name (e.g. `Set-Cookie`)
+    /// becomes separate header lines. A `Content-Type` entry replaces `content_type`.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// JSON Schema describing the response body, used for mutation testing.
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
+    #[serde(default)]
+    pub response_source: ResponseSource,
+    /// File streamed as the body when `response_source` is `file`.
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Send the response as an attachment (`Content-Disposition: attachment`).
+    #[serde(default)]
+    pub download: bool,
+    /// Filename offered for downloads; defaults to the file's own name.
+    #[serde(default)]
+    pub download_name: Option<String>,
+    /// Replay the first response for repeated POSTs with the same `Idempotency-Key`.
+    #[serde(default)]
+    pub idempotent: bool,
+    /// Match on the SOAP operation and wrap responses in SOAP envelopes.
+    #[serde(default)]
+    pub soap: Option<crate::soap::SoapConfig>,
+    /// Disabled endpoints stay in the project but are never matched.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub kind: EndpointKind,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Name of the SLO profile applied to this endpoint; overrides tag and global profiles.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Caps the response body at this many bytes per second, e.g. 50000 for
+    /// a slow 3G link; overrides the profile's rate.
+    #[serde(default)]
+    pub bytes_per_sec: Option<u64>,
+    /// Breaks the response on purpose, to exercise client error handling.
+    #[serde(default)]
+    pub fault: Option<crate::fault::Fault>,
+    /// Requests beyond the limit get 429 without touching the endpoint's
+    /// sequence, scenario or inbox.
+    #[serde(default)]
+    pub rate_limit: Option<crate::rate_limit::RateLimit>,
+    /// Requests handled at once; the rest queue or get 503.
+    #[serde(default)]
+    pub concurrency: Option<crate::concurrency::ConcurrencyLimit>,
+    /// Overrides the server-wide `bearer_auth` for this endpoint.
+    #[serde(default)]
+    pub bearer_auth: Option<crate::bearer::BearerAuth>,
+    /// Overrides the server-wide `api_key_auth` for this endpoint.
+    #[serde(default)]
+    pub api_key_auth: Option<crate::api_key::ApiKeyAuth>,
+    /// Save the most recent matching request as `example_request`.
+    #[serde(default)]
+    pub capture_example: bool,
+    #[serde(default)]
+    pub example_request: Option<ExampleRequest>,
+    /// Name of the only server that serves this endpoint; `None` serves it on every server.
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Responses served one per call in place of `status`/`response`.
+    #[serde(default)]
+    pub sequence: Vec<crate::sequence::SequenceStep>,
+    #[serde(default)]
+    pub sequence_mode: crate::sequence::SequenceMode,
+    /// Weighted alternatives to `status`/`response`; ignored while `sequence` is set.
+    #[serde(default)]
+    pub variants: Vec<crate::variants::ResponseVariant>,
+    /// Checksum and HMAC headers computed over the response body.
+    #[serde(default)]
+    pub signature: Option<crate::signing::SignatureConfig>,
+    /// Scenario state this endpoint requires and moves to when matched.
+    #[serde(default)]
+    pub scenario: Option<crate::scenarios::ScenarioRule>,
+    #[serde(default)]
+    pub resource: Option<crate::resources::ResourceConfig>,
+    /// Locked endpoints reject edits until unlocked with `set_endpoint_locked`.
+    #[serde(default)]
+    pub locked: bool,
+    /// Off for noisy or sensitive endpoints (e.g. a health check polled every
+    /// 500ms): their requests stay out of the request log and PCAP capture.
+    #[serde(default = "default_enabled")]
+    pub log_requests: bool,
+    #[serde(default)]
+    pub websocket: Option<crate::websocket::WebSocketConfig>,
+    #[serde(default)]
+    pub sse: Option<crate::sse::SseConfig>,
+    /// Match on the GraphQL operation name or root field.
+    #[serde(default)]
+    pub graphql: Option<crate::graphql::GraphqlConfig>,
+    /// Answer a unary gRPC method; the response is the output message as JSON.
+    #[serde(default)]
+    pub grpc: Option<crate::grpc::GrpcConfig>,
+    /// Match on the JSON-RPC `method` and answer in a JSON-RPC envelope.
+    #[serde(default)]
+    pub jsonrpc: Option<crate::jsonrpc::JsonRpcConfig>,
+    /// Folder served by `Static` endpoints.
+    #[serde(default)]
+    pub static_files: Option<crate::static_files::StaticConfig>,
+}
+
+/// A real request that matched an endpoint, kept as documentation of how it is called.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExampleRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub query: Vec<(String, String)>,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+    pub captured_at: String,
+}
+
+/// What an endpoint does with the requests it matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointKind {
+    /// Serves the configured response.
+    #[default]
+    Mock,
+    /// Accepts any method, stores the request in the inbox and serves the configured response.
+    Inbox,
+    /// CRUD collection at `path` backed by an in-memory store; see `resource`.
+    Resource,
+    /// Accepts WebSocket upgrades at `path` and talks as described by `websocket`.
+    #[serde(rename = "websocket")]
+    WebSocket,
+    /// Serves files from a local folder under `path`; see `static_files`.
+    Static,
+}
+
+/// Where the response body comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseSource {
+    /// The UTF-8 text in `Endpoint.response`.
+    #[default]
+    Inline,
+    /// The bytes of the file at `Endpoint.file_path`.
+    File,
+    /// A Rhai script in `Endpoint.response` that computes the response.
+    Script,
+    /// Server-sent events described by `Endpoint.sse`.
+    Events,
+}
+
+fn default_content_type() -> String {
+    "application/json".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// How `Endpoint.path` is compared against the request path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchType {
+    /// Literal path, optionally containing `*` / `**` wildcards.
+    #[default]
+    Exact,
+    /// `path` is a regular expression that must match the whole request path.
+    Regex,
+}
+
+/// A condition on a query parameter that must hold for the endpoint to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryMatcher {
+    pub name: String,
+    #[serde(default)]
+    pub kind: ValueMatchKind,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueMatchKind {
+    #[default]
+    Exact,
+    Regex,
+    /// Only requires the parameter to be present; `value` is ignored.
+    Present,
+}
+
+/// A condition on the request body that must hold for the endpoint to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BodyMatcher {
+    /// The body parses as JSON equal to `value`.
+    Equals { value: serde_json::Value },
+    /// The JSONPath resolves to some value.
+    JsonPathExists { path: String },
+    /// The JSONPath resolves to exactly `value`.
+    JsonPathEquals { path: String, value: serde_json::Value },
+    /// The raw body contains `value` as a substring.
+    Contains { value: String },
+}
+
+impl Endpoint {
+    pub fn new(method: String, path: String, status: u16, delay: u64, response: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            method,
+            path,
+            status,
+            delay,
+            delay_jitter: None,
+            response,
+            match_type: MatchType::default(),
+            match_query: Vec::new(),
+            match_headers: HashMap::new(),
+            match_body: Vec::new(),
+            depends_on: Vec::new(),
+            compose_dependencies: false,
+            content_type: default_content_type(),
+            headers: Vec::new(),
+            schema: None,
+            response_source: ResponseSource::default(),
+            file_path: None,
+            download: false,
+            download_name: None,
+            idempotent: false,
+            soap: None,
+            enabled: true,
+            kind: EndpointKind::default(),
+            tags: Vec::new(),
+            profile: None,
+            bytes_per_sec: None,
+            fault: None,
+            rate_limit: None,
+            concurrency: None,
+            bearer_auth: None,
+            api_key_auth: None,
+            capture_example: false,
+            example_request: None,
+            server: None,
+            sequence: Vec::new(),
+            sequence_mode: crate::sequence::SequenceMode::default(),
+            variants: Vec::new(),
+            signature: None,
+            scenario: None,
+            resource: None,
+            locked: false,
+            log_requests: true,
+            websocket: None,
+            sse: None,
+            graphql: None,
+            grpc: None,
+            jsonrpc: None,
+            static_files: None,
+        }
+    }
+
+    pub fn ensure_unlocked(&self) -> Result<(), String> {
+        if self.locked {
+            return Err(format!("Endpoint is locked: {}", self.id));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlsConfig {
+    #[serde(alias = "certPath")]
     pub cert_path: String,
+    #[serde(alias = "keyPath")]
     pub key_path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerSettings {
     pub port: u16,
+    #[serde(alias = "bindAddr")]
     pub bind_addr: String,
+    #[serde(alias = "enableTls")]
     pub enable_tls: bool,
+    #[serde(default, alias = "latencyBudgetMs")]
+    pub latency_budget_ms: Option<u64>,
+    /// When set, every exchange is written to this PCAP file.
+    #[serde(default, alias = "pcapPath")]
+    pub pcap_path: Option<String>,
+    /// Serves an S3-compatible object store next to the mock endpoints.
+    #[serde(default)]
+    pub s3: Option<crate::s3::S3Settings>,
+    /// Endpoint changes scheduled relative to server start.
+    #[serde(default)]
+    pub timeline: Vec<crate::timeline::TimelineStep>,
+    /// Seeds resources, variables and scenarios when the first server starts.
+    #[serde(default)]
+    pub startup: Vec<crate::startup::StartupAction>,
+    /// User-defined SLO profiles in addition to the built-in ones.
+    #[serde(default)]
+    pub profiles: Vec<crate::profiles::Profile>,
+    /// Profile applied to endpoints without an endpoint or tag profile.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Profile name per endpoint tag.
+    #[serde(default, alias = "tagProfiles")]
+    pub tag_profiles: HashMap<String, String>,
+    /// Identical requests within this many milliseconds are reported as duplicates; 0 disables tracking.
+    #[serde(default = "default_duplicate_window_ms", alias = "duplicateWindowMs")]
+    pub duplicate_window_ms: u64,
+    /// Write HTTP/1 response header names in Title-Case (`Set-Cookie`) instead of lowercase.
+    #[serde(default, alias = "titleCaseHeaders")]
+    pub title_case_headers: bool,
+    /// Write HTTP/1 response header names exactly as configured (`ETag`,
+    /// `X-API-Key`), overriding `title_case_headers` for those names.
+    #[serde(default, alias = "preserveHeaderCase")]
+    pub preserve_header_case: bool,
+    /// Reject requests without a valid access token, for mocks exposed beyond the LAN.
+    #[serde(default, alias = "requireAccessToken")]
+    pub require_access_token: bool,
+    /// Requests that match no endpoint are forwarded here instead of getting a 404.
+    #[serde(default)]
+    pub proxy: Option<crate::proxy::ProxySettings>,
+    /// Act as an HTTP forward proxy (CONNECT and absolute-URI requests).
+    #[serde(default, alias = "forwardProxy")]
+    pub forward_proxy: Option<crate::forward_proxy::ForwardProxySettings>,
+    /// Rejects every endpoint edit until turned off with `set_read_only`.
+    #[serde(default, alias = "readOnly")]
+    pub read_only: bool,
+    /// Simulated `X-RateLimit-*` headers on every response.
+    #[serde(default, alias = "rateLimitHeaders")]
+    pub rate_limit_headers: Option<crate::rate_limit::RateLimit>,
+    /// Limit applied to every request, on top of per-endpoint limits.
+    #[serde(default, alias = "rateLimit")]
+    pub rate_limit: Option<crate::rate_limit::RateLimit>,
+    /// Bearer token check for every endpoint without its own `bearer_auth`.
+    #[serde(default, alias = "bearerAuth")]
+    pub bearer_auth: Option<crate::bearer::BearerAuth>,
+    /// API key check for every endpoint without its own `api_key_auth`.
+    #[serde(default, alias = "apiKeyAuth")]
+    pub api_key_auth: Option<crate::api_key::ApiKeyAuth>,
+    /// Built-in OAuth2 token endpoint.
+    #[serde(default)]
+    pub oauth: Option<crate::oauth::OAuthProvider>,
+    /// Delay before an accepted connection is served. TCP connects still
+    /// succeed at once: under TLS this delays the handshake (exercising
+    /// handshake timeouts), for plain HTTP only the first response byte.
+    #[serde(default, alias = "connectDelayMs")]
+    pub connect_delay_ms: u64,
+    /// Project-wide request/response rules, applied in order around endpoint matching.
+    #[serde(default)]
+    pub middleware: Vec<crate::middleware::MiddlewareRule>,
+    /// Trailing-slash and case leniency for endpoint paths.
+    #[serde(default, alias = "pathMatching")]
+    pub path_matching: crate::matching::PathMatching,
+    /// Include the closest endpoints in fallback 404 responses and the request log.
+    #[serde(default, alias = "notFoundSuggestions")]
+    pub not_found_suggestions: bool,
+    /// Named server setups started with `start_server_with_config`.
+    #[serde(default, alias = "runConfigs")]
+    pub run_configs: Vec<crate::run_configs::RunConfig>,
+    /// Files uploaded as `multipart/form-data` are saved here; without it
+    /// they are only listed in the request log.
+    #[serde(default, alias = "uploadDir")]
+    pub upload_dir: Option<String>,
+    /// Cap in MiB on the request log, inbox and resource stores together;
+    /// the oldest data is evicted beyond it.
+    #[serde(default, alias = "memoryBudgetMb")]
+    pub memory_budget_mb: Option<u64>,
+    /// Reusable fragments, e.g. a standard `user` object, included in
+    /// templated bodies and headers with `{{> name}}`.
+    #[serde(default)]
+    pub partials: HashMap<String, String>,
+}
+
+fn default_duplicate_window_ms() -> u64 {
+    1000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectData {
+    /// Schema version; see `crate::migration`.
+    #[serde(default)]
+    pub version: u32,
     pub name: String,
     #[serde(rename = "lastSaved")]
     pub last_saved: String,
@@ -37,29 +423,114 @@ pub struct ProjectData {
     pub settings: ServerSettings,
     #[serde(rename = "tlsConfig")]
     pub tls_config: Option<TlsConfig>,
+    /// Deleted endpoints kept around so they can be restored. Left untouched
+    /// by `set_project_state` when absent.
+    #[serde(default)]
+    pub trash: Option<Vec<Endpoint>>,
+    /// Variable sets; left untouched by `set_project_state` when absent.
+    #[serde(default)]
+    pub environments: Option<crate::environments::Environments>,
 }
 
 pub struct AppState {
     pub endpoints: Arc<RwLock<Vec<Endpoint>>>,
-    pub server_handle: Arc<RwLock<Option<crate::server::ServerHandle>>>,
+    pub trash: Arc<RwLock<Vec<Endpoint>>>,
+    /// Running mock servers keyed by name; the GUI's single server is `default`.
+    pub servers: Arc<RwLock<HashMap<String, crate::server::ServerHandle>>>,
     pub tls_config: Arc<RwLock<Option<TlsConfig>>>,
     pub temp_cert_paths: Arc<RwLock<Option<(String, String)>>>,
     pub server_settings: Arc<RwLock<ServerSettings>>, // Add this line
+    pub mutation_runs: Arc<RwLock<HashMap<String, crate::mutation::MutationRun>>>,
+    pub inbox: crate::inbox::Inbox,
+    pub share_handle: Arc<RwLock<Option<crate::server::ServerHandle>>>,
+    pub duplicates: Arc<RwLock<crate::duplicates::DuplicateTracker>>,
+    pub request_log: crate::request_log::RequestLog,
+    pub access_tokens: crate::access::AccessTokens,
+    pub sequences: crate::sequence::SequenceCounters,
+    pub scenarios: crate::scenarios::ScenarioStates,
+    pub resources: crate::resources::ResourceStore,
+    /// Open SQLite store when persistence is turned on.
+    pub store: Arc<RwLock<Option<crate::store::StoreHandle>>>,
+    pub environments: crate::environments::SharedEnvironments,
+    pub scripts: crate::scripting::ScriptStates,
+    pub recording: crate::recording::SharedRecording,
+    /// Sizes of the request log, inbox and resources against `memory_budget_mb`.
+    pub memory: crate::memory::SharedBudget,
+    /// Compiled response templates, with the partials of the saved settings
+    /// registered; shared by every server.
+    pub templates: Arc<RwLock<crate::template::TemplateCache>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let inbox: crate::inbox::Inbox = Arc::new(RwLock::new(std::collections::VecDeque::new()));
+        let request_log: crate::request_log::RequestLog = Arc::new(RwLock::new(std::collections::VecDeque::new()));
+        let resources: crate::resources::ResourceStore = Arc::new(RwLock::new(HashMap::new()));
         Self {
             endpoints: Arc::new(RwLock::new(Vec::new())),
-            server_handle: Arc::new(RwLock::new(None)),
+            trash: Arc::new(RwLock::new(Vec::new())),
+            servers: Arc::new(RwLock::new(HashMap::new())),
             tls_config: Arc::new(RwLock::new(None)),
             temp_cert_paths: Arc::new(RwLock::new(None)),
             server_settings: Arc::new(RwLock::new(ServerSettings { // Initialize with default settings
                 port: 3000,
                 bind_addr: "127.0.0.1".to_string(),
                 enable_tls: false,
+                latency_budget_ms: None,
+                pcap_path: None,
+                s3: None,
+                timeline: Vec::new(),
+                startup: Vec::new(),
+                profiles: Vec::new(),
+                profile: None,
+                tag_profiles: HashMap::new(),
+                duplicate_window_ms: default_duplicate_window_ms(),
+                title_case_headers: false,
+                preserve_header_case: false,
+                require_access_token: false,
+                proxy: None,
+                forward_proxy: None,
+                read_only: false,
+                rate_limit_headers: None,
+                rate_limit: None,
+                bearer_auth: None,
+                api_key_auth: None,
+                oauth: None,
+                connect_delay_ms: 0,
+                middleware: Vec::new(),
+                path_matching: crate::matching::PathMatching::default(),
+                not_found_suggestions: false,
+                run_configs: Vec::new(),
+                upload_dir: None,
+                memory_budget_mb: None,
+                partials: HashMap::new(),
             })),
+            mutation_runs: Arc::new(RwLock::new(HashMap::new())),
+            inbox: inbox.clone(),
+            share_handle: Arc::new(RwLock::new(None)),
+            duplicates: Arc::new(RwLock::new(crate::duplicates::DuplicateTracker::default())),
+            request_log: request_log.clone(),
+            access_tokens: Arc::new(RwLock::new(Vec::new())),
+            sequences: Arc::new(RwLock::new(HashMap::new())),
+            scenarios: Arc::new(RwLock::new(HashMap::new())),
+            resources: resources.clone(),
+            store: Arc::new(RwLock::new(None)),
+            environments: Arc::new(RwLock::new(crate::environments::Environments::default())),
+            scripts: Arc::new(crate::scripting::ScriptStore::default()),
+            recording: Arc::new(RwLock::new(None)),
+            memory: Arc::new(crate::memory::MemoryBudget::new(request_log, inbox, resources)),
+            templates: Arc::new(RwLock::new(crate::template::TemplateCache::new())),
+        }
+    }
+}
+
+impl AppState {
+    /// Fails while the project is in read-only mode.
+    pub async fn ensure_writable(&self) -> Result<(), String> {
+        if self.server_settings.read().await.read_only {
+            return Err("Project is read-only".to_string());
         }
+        Ok(())
     }
 }
 
@@ -72,14 +543,9 @@ pub async fn add_endpoint(
     status: u16,
     delay: u64,
 ) -> Result<Endpoint, String> {
-    let endpoint = Endpoint {
-        id: uuid::Uuid::new_v4().to_string(),
-        method,
-        path,
-        status,
-        delay,
-        response,
-    };
+    state.ensure_writable().await?;
+    let mut endpoint = Endpoint::new(method, path, status, delay, response);
+    crate::lenient_json::normalize_response(&mut endpoint);
 
     state.endpoints.write().await.push(endpoint.clone());
     Ok(endpoint)
@@ -90,64 +556,250 @@ pub async fn get_endpoints(state: tauri::State<'_, AppState>) -> Result<Vec<Endp
     Ok(state.endpoints.read().await.clone())
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEndpointParams {
+    id: String,
+    /// Fields to overwrite, in the same shape as a serialized `Endpoint`.
+    changes: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Edits an endpoint in place, keeping its id.
+#[tauri::command]
+pub async fn update_endpoint(
+    state: tauri::State<'_, AppState>,
+    params: UpdateEndpointParams,
+) -> Result<Endpoint, String> {
+    state.ensure_writable().await?;
+    let mut endpoints = state.endpoints.write().await;
+    let endpoint = endpoints
+        .iter_mut()
+        .find(|e| e.id == params.id)
+        .ok_or_else(|| format!("Endpoint not found: {}", params.id))?;
+    endpoint.ensure_unlocked()?;
+
+    *endpoint = apply_changes(endpoint, params.changes)?;
+    crate::lenient_json::normalize_response(endpoint);
+    Ok(endpoint.clone())
+}
+
+/// Returns a copy of `endpoint` with the given serialized fields replaced; `id` is never changed.
+pub fn apply_changes(
+    endpoint: &Endpoint,
+    changes: serde_json::Map<String, serde_json::Value>,
+) -> Result<Endpoint, String> {
+    let mut value = serde_json::to_value(endpoint).map_err(|e| e.to_string())?;
+    if let serde_json::Value::Object(fields) = &mut value {
+        for (key, change) in changes.into_iter().filter(|(key, _)| key != "id") {
+            fields.insert(key, change);
+        }
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid endpoint fields: {}", e))
+}
+
+/// Clones an endpoint under a new id, inserting the copy right after the original.
+/// The copy of a locked endpoint starts out unlocked.
+#[tauri::command]
+pub async fn duplicate_endpoint(state: tauri::State<'_, AppState>, id: String) -> Result<Endpoint, String> {
+    state.ensure_writable().await?;
+    let mut endpoints = state.endpoints.write().await;
+    let index = endpoints
+        .iter()
+        .position(|e| e.id == id)
+        .ok_or_else(|| format!("Endpoint not found: {}", id))?;
+
+    let mut copy = endpoints[index].clone();
+    copy.id = uuid::Uuid::new_v4().to_string();
+    copy.locked = false;
+    endpoints.insert(index + 1, copy.clone());
+    Ok(copy)
+}
+
+/// Flips an endpoint between enabled and disabled, returning the updated endpoint.
+#[tauri::command]
+pub async fn toggle_endpoint(state: tauri::State<'_, AppState>, id: String) -> Result<Endpoint, String> {
+    state.ensure_writable().await?;
+    let mut endpoints = state.endpoints.write().await;
+    let endpoint = endpoints
+        .iter_mut()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Endpoint not found: {}", id))?;
+    endpoint.ensure_unlocked()?;
+
+    endpoint.enabled = !endpoint.enabled;
+    Ok(endpoint.clone())
+}
+
+/// Locks or unlocks an endpoint against edits.
+#[tauri::command]
+pub async fn set_endpoint_locked(state: tauri::State<'_, AppState>, id: String, locked: bool) -> Result<Endpoint, String> {
+    state.ensure_writable().await?;
+    let mut endpoints = state.endpoints.write().await;
+    let endpoint = endpoints
+        .iter_mut()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Endpoint not found: {}", id))?;
+
+    endpoint.locked = locked;
+    Ok(endpoint.clone())
+}
+
+/// Turns the project-wide read-only mode on or off.
+#[tauri::command]
+pub async fn set_read_only(state: tauri::State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.server_settings.write().await.read_only = enabled;
+    Ok(())
+}
+
+/// Moves an endpoint to the trash; use `restore_endpoint` to bring it back.
 #[tauri::command]
 pub async fn delete_endpoint(state: tauri::State<'_, AppState>, id: String) -> Result<(), String> {
+    state.ensure_writable().await?;
     let mut endpoints = state.endpoints.write().await;
-    endpoints.retain(|e| e.id != id);
+    if let Some(index) = endpoints.iter().position(|e| e.id == id) {
+        endpoints[index].ensure_unlocked()?;
+        let endpoint = endpoints.remove(index);
+        state.trash.write().await.push(endpoint);
+    }
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_trash(state: tauri::State<'_, AppState>) -> Result<Vec<Endpoint>, String> {
+    Ok(state.trash.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn restore_endpoint(state: tauri::State<'_, AppState>, id: String) -> Result<Endpoint, String> {
+    state.ensure_writable().await?;
+    let mut trash = state.trash.write().await;
+    let index = trash
+        .iter()
+        .position(|e| e.id == id)
+        .ok_or_else(|| format!("Endpoint not found in trash: {}", id))?;
+    let endpoint = trash.remove(index);
+    state.endpoints.write().await.push(endpoint.clone());
+    Ok(endpoint)
+}
+
+/// Permanently removes everything in the trash, returning how many endpoints were dropped.
+#[tauri::command]
+pub async fn purge_trash(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    state.ensure_writable().await?;
+    let mut trash = state.trash.write().await;
+    let count = trash.len();
+    trash.clear();
+    Ok(count)
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StartServerParams {
+    #[serde(default = "default_server_name")]
+    name: String,
     port: u16,
     bind_addr: String,
     enable_tls: bool,
+    /// Only endpoints carrying at least one of these tags are served; empty serves all.
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn default_server_name() -> String {
+    crate::server::DEFAULT_SERVER.to_string()
 }
 
+impl From<&crate::run_configs::RunConfig> for StartServerParams {
+    fn from(config: &crate::run_configs::RunConfig) -> Self {
+        Self {
+            name: default_server_name(),
+            port: config.port,
+            bind_addr: config.bind_addr.clone(),
+            enable_tls: config.enable_tls,
+            tags: config.tags.clone(),
+        }
+    }
+}
+
+/// Starts (or restarts) the server called `name`; servers on different ports run side by side.
 #[tauri::command]
 pub async fn start_server(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     params: StartServerParams,
 ) -> Result<String, String> {
-    let mut handle = state.server_handle.write().await;
+    launch_server(app, &state, params).await
+}
+
+pub(crate) async fn launch_server(app: tauri::AppHandle, state: &AppState, params: StartServerParams) -> Result<String, String> {
+    let mut servers = state.servers.write().await;
+
+    // Shared state is primed once, by the first server to start. This and
+    // everything else that can fail runs before a running server of the same
+    // name is stopped, so a failure leaves that server running.
+    if servers.keys().all(|name| *name == params.name) {
+        let actions = state.server_settings.read().await.startup.clone();
+        crate::startup::run(state, &actions).await?;
+    }
+
+    let mut settings = state
+        .environments
+        .read()
+        .await
+        .resolve_settings(&state.server_settings.read().await);
+    settings.port = params.port;
+    settings.bind_addr = params.bind_addr.clone();
+    settings.enable_tls = params.enable_tls;
+    let server_state = crate::server::ServerState::new(state, &settings)?
+        .with_name(params.name.clone())
+        .with_tags(params.tags.clone())
+        .with_app_handle(app);
+    let stats = server_state.stats();
+    let tls = if params.enable_tls {
+        let tls_config = state.tls_config.read().await.clone();
+        Some(tls_config.ok_or_else(|| "TLS is enabled but no certificate configured".to_string())?)
+    } else {
+        None
+    };
 
     // 이미 실행 중이면 먼저 종료
-    if let Some(mut existing_handle) = handle.take() {
+    if let Some(mut existing_handle) = servers.remove(&params.name) {
         if let Some(tx) = existing_handle.shutdown_tx.take() {
             let _ = tx.send(());
             // 잠깐 대기
-            drop(handle);
+            drop(servers);
             tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
-            handle = state.server_handle.write().await;
+            servers = state.servers.write().await;
         }
     }
 
-    let shutdown_tx = if params.enable_tls {
+    let shutdown_tx = if let Some(tls) = tls {
         // Start TLS server
-        let tls_config = state.tls_config.read().await;
-        let tls = tls_config.as_ref()
-            .ok_or_else(|| "TLS is enabled but no certificate configured".to_string())?;
-
         crate::server::start_tls_server(
             params.port,
             params.bind_addr.clone(),
-            state.endpoints.clone(),
-            tls.cert_path.clone(),
-            tls.key_path.clone(),
+            server_state,
+            tls.cert_path,
+            tls.key_path,
         )
         .await
         .map_err(|e| format!("Failed to start TLS server: {}", e))?
     } else {
         // Start regular HTTP server
-        crate::server::start_server(params.port, params.bind_addr.clone(), state.endpoints.clone())
+        crate::server::start_server(params.port, params.bind_addr.clone(), server_state)
             .await
             .map_err(|e| format!("Failed to start server: {}", e))?
     };
 
-    let mut server_handle = crate::server::ServerHandle::new(params.port, params.enable_tls);
+    let mut server_handle = crate::server::ServerHandle::new(params.port, params.bind_addr.clone(), params.enable_tls);
     server_handle.shutdown_tx = Some(shutdown_tx);
-    *handle = Some(server_handle);
+    server_handle.tags = params.tags;
+    server_handle.stats = Some(stats);
+    // The timeline edits shared endpoints, so only one server runs it
+    if !servers.values().any(|server| server.timeline.is_some()) {
+        server_handle.timeline = crate::timeline::spawn(state.endpoints.clone(), settings.timeline);
+    }
+    servers.insert(params.name.clone(), server_handle);
 
     let protocol = if params.enable_tls { "https" } else { "http" };
     let display_addr = &params.bind_addr;
@@ -186,11 +838,13 @@ pub async fn clear_tls_config(state: tauri::State<'_, AppState>) -> Result<Strin
     Ok("TLS configuration cleared".to_string())
 }
 
+/// Stops the server called `name` (`default` when omitted).
 #[tauri::command]
-pub async fn stop_server(state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let mut handle = state.server_handle.write().await;
+pub async fn stop_server(state: tauri::State<'_, AppState>, name: Option<String>) -> Result<String, String> {
+    let name = name.unwrap_or_else(default_server_name);
+    let mut servers = state.servers.write().await;
 
-    if let Some(mut server_handle) = handle.take() {
+    if let Some(mut server_handle) = servers.remove(&name) {
         if let Some(tx) = server_handle.shutdown_tx.take() {
             let _ = tx.send(());
             Ok("Server stopped".to_string())
@@ -202,19 +856,37 @@ pub async fn stop_server(state: tauri::State<'_, AppState>) -> Result<String, St
     }
 }
 
+/// Status of the server called `name` (`default` when omitted), plus every running server.
 #[tauri::command]
-pub async fn get_server_status(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let handle = state.server_handle.read().await;
+pub async fn get_server_status(state: tauri::State<'_, AppState>, name: Option<String>) -> Result<serde_json::Value, String> {
+    let name = name.unwrap_or_else(default_server_name);
+    let servers = state.servers.read().await;
+    let running: Vec<serde_json::Value> = servers
+        .iter()
+        .map(|(name, server)| {
+            serde_json::json!({
+                "name": name,
+                "port": server.port,
+                "is_tls": server.is_tls,
+                "url": server.base_url(),
+                "tags": server.tags,
+                "stats": server.stats.as_ref().map(|stats| stats.snapshot()),
+            })
+        })
+        .collect();
 
-    if let Some(server_handle) = handle.as_ref() {
+    if let Some(server_handle) = servers.get(&name) {
         Ok(serde_json::json!({
             "running": true,
             "port": server_handle.port,
-            "is_tls": server_handle.is_tls
+            "is_tls": server_handle.is_tls,
+            "stats": server_handle.stats.as_ref().map(|stats| stats.snapshot()),
+            "servers": running,
         }))
     } else {
         Ok(serde_json::json!({
-            "running": false
+            "running": false,
+            "servers": running,
         }))
     }
 }
@@ -320,6 +992,8 @@ pub struct SaveProjectParams {
 
 #[tauri::command]
 pub async fn save_project(app: tauri::AppHandle, params: SaveProjectParams) -> Result<(), String> {
+    // Saved files are always strict JSON, even if the data was written as JSON5
+    let data = crate::lenient_json::to_strict(&params.data).unwrap_or(params.data);
     let (tx, rx) = tokio::sync::oneshot::channel();
     app.dialog()
         .file()
@@ -329,7 +1003,7 @@ pub async fn save_project(app: tauri::AppHandle, params: SaveProjectParams) -> R
             let result = match file_path {
                 Some(path) => {
                     match path.into_path() {
-                        Ok(path_buf) => fs::write(path_buf, &params.data).map_err(|e| e.to_string()),
+                        Ok(path_buf) => fs::write(path_buf, &data).map_err(|e| e.to_string()),
                         Err(e) => Err(format!("Invalid file path: {}", e)),
                     }
                 },
@@ -350,7 +1024,9 @@ pub async fn load_project(app: tauri::AppHandle) -> Result<String, String> {
         let result = match file_path {
             Some(path) => {
                 match path.into_path() {
-                    Ok(path_buf) => fs::read_to_string(path_buf).map_err(|e| e.to_string()),
+                    Ok(path_buf) => fs::read_to_string(path_buf)
+                        .map(|content| crate::lenient_json::to_strict(&content).unwrap_or(content))
+                        .map_err(|e| e.to_string()),
                     Err(e) => Err(format!("Invalid file path: {}", e)),
                 }
             },
@@ -361,10 +1037,70 @@ pub async fn load_project(app: tauri::AppHandle) -> Result<String, String> {
     rx.await.unwrap_or(Ok(String::new()))
 }
 
+/// `loaded` with each locked endpoint of `current` kept as it is, in place
+/// of the loaded version or appended when the loaded project lacks it.
+fn keep_locked(current: &[Endpoint], mut loaded: Vec<Endpoint>) -> Vec<Endpoint> {
+    for locked in current.iter().filter(|e| e.locked) {
+        match loaded.iter_mut().find(|e| e.id == locked.id) {
+            Some(endpoint) => *endpoint = locked.clone(),
+            None => loaded.push(locked.clone()),
+        }
+    }
+    loaded
+}
+
+/// Replaces the backend state with `project_data` (migrated to the current
+/// version first) and reports which endpoints were added, removed or now
+/// behave differently. Refused while the project is read-only; locked
+/// endpoints keep their current version.
 #[tauri::command]
-pub async fn set_project_state(state: tauri::State<'_, AppState>, project_data: ProjectData) -> Result<(), String> {
-    *state.endpoints.write().await = project_data.endpoints;
+pub async fn set_project_state(
+    state: tauri::State<'_, AppState>,
+    project_data: serde_json::Value,
+) -> Result<Vec<crate::diff::EndpointChange>, String> {
+    state.ensure_writable().await?;
+    let project_data = crate::migration::migrate(project_data)?;
+    crate::template::sync_partials(&state.templates, &project_data.settings.partials).await?;
+    let mut endpoints = state.endpoints.write().await;
+    let loaded = keep_locked(&endpoints, project_data.endpoints);
+    let changes = crate::diff::diff_endpoints(&endpoints, &loaded);
+    *endpoints = loaded;
+    drop(endpoints);
+
+    if let Some(trash) = project_data.trash {
+        *state.trash.write().await = trash;
+    }
+    if let Some(environments) = project_data.environments {
+        *state.environments.write().await = environments;
+    }
     *state.tls_config.write().await = project_data.tls_config;
     *state.server_settings.write().await = project_data.settings;
-    Ok(())
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(path: &str, locked: bool) -> Endpoint {
+        let mut endpoint = Endpoint::new("GET".to_string(), path.to_string(), 200, 0, String::new());
+        endpoint.locked = locked;
+        endpoint
+    }
+
+    #[test]
+    fn loading_a_project_keeps_locked_endpoints() {
+        let locked = endpoint("/locked", true);
+        let current = [locked.clone(), endpoint("/removed", true), endpoint("/open", false)];
+
+        let mut overwritten = locked.clone();
+        overwritten.locked = false;
+        overwritten.status = 500;
+        let loaded = keep_locked(&current, vec![overwritten, endpoint("/added", false)]);
+
+        let paths: Vec<&str> = loaded.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["/locked", "/added", "/removed"]);
+        assert_eq!(loaded[0].status, 200);
+        assert!(loaded[0].locked);
+    }
 }
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, ServerSettings};
+
+/// A named set of variables, e.g. `local` or `staging`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
+
+/// The project's environments and which one is active.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environments {
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub sets: Vec<Environment>,
+}
+
+pub type SharedEnvironments = Arc<RwLock<Environments>>;
+
+impl Environments {
+    pub fn get(&self, name: &str) -> Option<&Environment> {
+        self.sets.iter().find(|env| env.name == name)
+    }
+
+    pub fn active(&self) -> Option<&Environment> {
+        self.get(self.active.as_deref()?)
+    }
+
+    /// The named environment, created empty if it doesn't exist yet.
+    pub fn get_or_create(&mut self, name: &str) -> &mut Environment {
+        let index = match self.sets.iter().position(|env| env.name == name) {
+            Some(index) => index,
+            None => {
+                self.sets.push(Environment {
+                    name: name.to_string(),
+                    variables: BTreeMap::new(),
+                });
+                self.sets.len() - 1
+            }
+        };
+        &mut self.sets[index]
+    }
+
+    /// Variables of the active environment, for the `env` template context.
+    pub fn active_variables(&self) -> serde_json::Value {
+        let variables = self.active().map(|env| env.variables.clone()).unwrap_or_default();
+        serde_json::to_value(variables).unwrap_or_default()
+    }
+
+    /// Replaces `{{env.NAME}}` with the active environment's value; unknown
+    /// names are left as written.
+    pub fn substitute(&self, text: &str) -> String {
+        let Some(env) = self.active() else {
+            return text.to_string();
+        };
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("{{env.") {
+            let Some(end) = rest[start..].find("}}") else {
+                break;
+            };
+            let name = rest[start + "{{env.".len()..start + end].trim();
+            result.push_str(&rest[..start]);
+            match env.variables.get(name) {
+                Some(value) => result.push_str(value),
+                None => result.push_str(&rest[start..start + end + 2]),
+            }
+            rest = &rest[start + end + 2..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Settings with `{{env.NAME}}` substituted in every string value.
+    pub fn resolve_settings(&self, settings: &ServerSettings) -> ServerSettings {
+        let Ok(mut value) = serde_json::to_value(settings) else {
+            return settings.clone();
+        };
+        self.substitute_strings(&mut value);
+        serde_json::from_value(value).unwrap_or_else(|_| settings.clone())
+    }
+
+    fn substitute_strings(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => *text = self.substitute(text),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|item| self.substitute_strings(item)),
+            serde_json::Value::Object(fields) => fields.values_mut().for_each(|field| self.substitute_strings(field)),
+            _ => {}
+        }
+    }
+}
+
+/// Parses `.env` contents: `KEY=value` lines with optional `export ` prefixes,
+/// `#` comments and single- or double-quoted values. Double-quoted values
+/// understand `\n`, `\"` and `\\`.
+pub fn parse_dotenv(contents: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut variables = BTreeMap::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid .env line {}: missing '='", number + 1))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("Invalid .env line {}: empty name", number + 1));
+        }
+        variables.insert(key.to_string(), parse_value(value.trim()));
+    }
+    Ok(variables)
+}
+
+fn parse_value(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut unescaped = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            }
+        }
+        return unescaped;
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.to_string();
+    }
+    // Unquoted values end at an inline comment
+    match value.find(" #") {
+        Some(index) => value[..index].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// Writes variables as `.env` lines, double-quoting values that need it.
+pub fn to_dotenv(variables: &BTreeMap<String, String>) -> String {
+    variables
+        .iter()
+        .map(|(key, value)| {
+            let plain = value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_-./:@,+".contains(c));
+            if plain {
+                format!("{}={}\n", key, value)
+            } else {
+                let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+                format!("{}=\"{}\"\n", key, escaped)
+            }
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEnvFileParams {
+    /// Environment to merge into; created if missing.
+    environment: String,
+    contents: String,
+}
+
+/// Merges the variables of a `.env` file into an environment, overwriting existing names.
+#[tauri::command]
+pub async fn import_env_file(
+    state: tauri::State<'_, AppState>,
+    params: ImportEnvFileParams,
+) -> Result<Environment, String> {
+    let variables = parse_dotenv(&params.contents)?;
+    let mut environments = state.environments.write().await;
+    let environment = environments.get_or_create(&params.environment);
+    environment.variables.extend(variables);
+    Ok(environment.clone())
+}
+
+/// `.env` contents of the named environment, or of the active one.
+#[tauri::command]
+pub async fn export_env_file(state: tauri::State<'_, AppState>, environment: Option<String>) -> Result<String, String> {
+    let environments = state.environments.read().await;
+    let found = match &environment {
+        Some(name) => environments.get(name),
+        None => environments.active(),
+    };
+    match found {
+        Some(env) => Ok(to_dotenv(&env.variables)),
+        None => Err(match environment {
+            Some(name) => format!("Environment not found: {}", name),
+            None => "No active environment".to_string(),
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn list_environments(state: tauri::State<'_, AppState>) -> Result<Environments, String> {
+    Ok(state.environments.read().await.clone())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveEnvironmentParams {
+    name: String,
+    variables: BTreeMap<String, String>,
+}
+
+/// Creates an environment or replaces the variables of an existing one.
+#[tauri::command]
+pub async fn save_environment(
+    state: tauri::State<'_, AppState>,
+    params: SaveEnvironmentParams,
+) -> Result<Environment, String> {
+    if params.name.trim().is_empty() {
+        return Err("Environment name must not be empty".to_string());
+    }
+    let mut environments = state.environments.write().await;
+    let environment = environments.get_or_create(&params.name);
+    environment.variables = params.variables;
+    Ok(environment.clone())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameEnvironmentParams {
+    name: String,
+    new_name: String,
+}
+
+#[tauri::command]
+pub async fn rename_environment(
+    state: tauri::State<'_, AppState>,
+    params: RenameEnvironmentParams,
+) -> Result<Environment, String> {
+    let mut environments = state.environments.write().await;
+    if environments.get(&params.new_name).is_some() {
+        return Err(format!("Environment already exists: {}", params.new_name));
+    }
+    if environments.active.as_deref() == Some(params.name.as_str()) {
+        environments.active = Some(params.new_name.clone());
+    }
+    let environment = environments
+        .sets
+        .iter_mut()
+        .find(|env| env.name == params.name)
+        .ok_or_else(|| format!("Environment not found: {}", params.name))?;
+    environment.name = params.new_name;
+    Ok(environment.clone())
+}
+
+/// Deletes an environment; deleting the active one leaves no environment active.
+#[tauri::command]
+pub async fn delete_environment(state: tauri::State<'_, AppState>, name: String) -> Result<(), String> {
+    let mut environments = state.environments.write().await;
+    let index = environments
+        .sets
+        .iter()
+        .position(|env| env.name == name)
+        .ok_or_else(|| format!("Environment not found: {}", name))?;
+    environments.sets.remove(index);
+    if environments.active.as_deref() == Some(name.as_str()) {
+        environments.active = None;
+    }
+    Ok(())
+}
+
+/// Selects the environment used for `{{env.NAME}}`, or none.
+#[tauri::command]
+pub async fn set_active_environment(state: tauri::State<'_, AppState>, name: Option<String>) -> Result<(), String> {
+    let mut environments = state.environments.write().await;
+    if let Some(name) = &name {
+        if environments.get(name).is_none() {
+            return Err(format!("Environment not found: {}", name));
+        }
+    }
+    environments.active = name;
+    Ok(())
+}
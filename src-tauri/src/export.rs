@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::AppState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDockerParams {
+    project_file: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerArtifacts {
+    pub dockerfile: String,
+    pub compose: String,
+}
+
+/// Builds a Dockerfile and compose service that run the saved project in headless mode.
+#[tauri::command]
+pub async fn export_docker(
+    state: tauri::State<'_, AppState>,
+    params: ExportDockerParams,
+) -> Result<DockerArtifacts, String> {
+    let file_name = std::path::Path::new(&params.project_file)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "Project file name is required".to_string())?;
+
+    let port = state.server_settings.read().await.port;
+    Ok(docker_artifacts(&file_name, port))
+}
+
+/// The image builds aka from source, so the build context must be the
+/// fakeapi source tree with the project file copied into its root.
+fn docker_artifacts(file_name: &str, port: u16) -> DockerArtifacts {
+    // JSON strings are valid in both the exec form of COPY and YAML's double-quoted scalars
+    let quoted = serde_json::to_string(file_name).unwrap_or_default();
+
+    let dockerfile = format!(
+        r#"# Build context: the fakeapi source tree, with {quoted} copied into its root.
+# The project is baked into the image, so CI needs nothing else to run it.
+FROM node:20-bookworm AS frontend
+WORKDIR /src
+COPY . .
+RUN npm ci && npm run build
+
+FROM rust:1-bookworm AS build
+RUN apt-get update && apt-get install -y --no-install-recommends \
+    libwebkit2gtk-4.1-dev libgtk-3-dev librsvg2-dev libayatana-appindicator3-dev
+WORKDIR /src
+COPY --from=frontend /src /src
+RUN cargo build --release --manifest-path src-tauri/Cargo.toml
+
+FROM debian:bookworm-slim
+RUN apt-get update && apt-get install -y --no-install-recommends \
+    libwebkit2gtk-4.1-0 libgtk-3-0 ca-certificates && rm -rf /var/lib/apt/lists/*
+COPY --from=build /src/src-tauri/target/release/aka /usr/local/bin/aka
+COPY [{quoted}, "/mock/project.json"]
+EXPOSE {port}
+CMD ["aka", "--headless", "/mock/project.json", "--bind", "0.0.0.0", "--port", "{port}"]
+"#,
+    );
+
+    let compose = format!(
+        r#"# Run from the fakeapi source tree, next to the Dockerfile and {quoted}.
+services:
+  fakeapi:
+    build: .
+    ports:
+      - "{port}:{port}"
+"#,
+    );
+
+    DockerArtifacts { dockerfile, compose }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_are_escaped() {
+        let artifacts = docker_artifacts(r#"my "mock" \ v2.json"#, 3000);
+        let copy = artifacts
+            .dockerfile
+            .lines()
+            .find(|line| line.starts_with("COPY [") && line.contains("/mock/project.json"))
+            .unwrap();
+        let sources: Vec<String> = serde_json::from_str(&copy["COPY ".len()..]).unwrap();
+        assert_eq!(sources, [r#"my "mock" \ v2.json"#, "/mock/project.json"]);
+        let cmd = r#"CMD ["aka", "--headless", "/mock/project.json", "--bind", "0.0.0.0", "--port", "3000"]"#;
+        assert!(artifacts.dockerfile.contains(cmd));
+    }
+
+    #[test]
+    fn compose_builds_the_image_without_mounting_the_project() {
+        let compose: serde_yaml::Value = serde_yaml::from_str(&docker_artifacts("mock.json", 8080).compose).unwrap();
+        let service = &compose["services"]["fakeapi"];
+        assert_eq!(service["build"], ".");
+        assert_eq!(service["ports"][0], "8080:8080");
+        assert!(service.get("volumes").is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::{json, Value};
+
+const FIRST_NAMES: &[&str] = &[
+    "James", "Mary", "John", "Patricia", "Robert", "Jennifer", "Michael", "Linda", "William", "Elizabeth",
+    "David", "Barbara", "Richard", "Susan", "Joseph", "Jessica", "Thomas", "Sarah", "Charles", "Karen",
+    "Minjun", "Seoyeon", "Jiho", "Haeun", "Yuna", "Hiroshi", "Sakura", "Wei", "Mei", "Lucas",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Smith", "Johnson", "Williams", "Brown", "Jones", "Garcia", "Miller", "Davis", "Rodriguez", "Martinez",
+    "Kim", "Lee", "Park", "Choi", "Jung", "Tanaka", "Suzuki", "Wang", "Chen", "Nguyen",
+];
+
+const DOMAINS: &[&str] = &["example.com", "example.org", "mail.test", "fakeapi.dev"];
+
+const CITIES: &[&str] = &[
+    "Seoul", "Busan", "Tokyo", "New York", "London", "Berlin", "Paris", "Toronto", "Sydney", "Singapore",
+];
+
+const COMPANIES: &[&str] = &[
+    "Acme Corp", "Globex", "Initech", "Umbrella", "Hooli", "Stark Industries", "Wayne Enterprises", "Soylent",
+];
+
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet", "kilo", "lima",
+];
+
+fn pick(items: &[&'static str]) -> &'static str {
+    items.choose(&mut rand::thread_rng()).copied().unwrap_or_default()
+}
+
+/// Generates one fake value by kind, e.g. `name`, `email` or `uuid`.
+pub fn fake(kind: &str) -> Option<String> {
+    let value = match kind {
+        "firstName" => pick(FIRST_NAMES).to_string(),
+        "lastName" => pick(LAST_NAMES).to_string(),
+        "name" => format!("{} {}", pick(FIRST_NAMES), pick(LAST_NAMES)),
+        "email" => format!(
+            "{}.{}{}@{}",
+            pick(FIRST_NAMES).to_lowercase(),
+            pick(LAST_NAMES).to_lowercase(),
+            rand::thread_rng().gen_range(1..100),
+            pick(DOMAINS)
+        ),
+        "phone" => {
+            let mut rng = rand::thread_rng();
+            format!("010-{:04}-{:04}", rng.gen_range(0..10000), rng.gen_range(0..10000))
+        }
+        "city" => pick(CITIES).to_string(),
+        "company" => pick(COMPANIES).to_string(),
+        "word" => pick(WORDS).to_string(),
+        "uuid" => uuid::Uuid::new_v4().to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// The `faker` object exposed to templates; values are drawn once per render.
+pub fn context() -> Value {
+    let kinds = ["firstName", "lastName", "name", "email", "phone", "city", "company", "word", "uuid"];
+    let mut map = serde_json::Map::new();
+    for kind in kinds {
+        if let Some(value) = fake(kind) {
+            map.insert(kind.to_string(), json!(value));
+        }
+    }
+    Value::Object(map)
+}
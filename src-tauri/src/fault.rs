@@ -0,0 +1,83 @@
+use axum::body::{Body, Bytes};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tokio_util::io::ReaderStream;
+
+use crate::server::{MockBody, MockResponse};
+
+/// A broken response served in place of the configured one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fault {
+    /// Headers and half the body, then the connection is closed.
+    Reset,
+    /// The connection is closed before anything is sent, not even a status
+    /// line, like a server that accepted the request and then died.
+    Empty,
+    /// The body cut short with invalid bytes appended, under the same
+    /// `Content-Type`, so JSON clients fail to parse it.
+    Garbage,
+}
+
+/// Bytes appended to a truncated body; never valid JSON or UTF-8.
+const GARBAGE: &[u8] = b"\x00\xff\xfe{\"";
+
+pub fn inject(fault: Fault, response: &mut MockResponse) {
+    let body = match &response.body {
+        MockBody::Bytes(bytes) => bytes.clone(),
+        _ => Bytes::new(),
+    };
+    match fault {
+        Fault::Reset => {
+            // Announce the full length so the client sees a truncated body
+            response.headers.retain(|(name, _)| !name.eq_ignore_ascii_case("Content-Length"));
+            response.headers.push(("Content-Length".to_string(), body.len().to_string()));
+            response.body = MockBody::Reset(body.slice(..body.len() / 2));
+        }
+        Fault::Empty => {
+            // The body fails on its first read, so the head is never flushed
+            response.headers.clear();
+            response.body = MockBody::Reset(Bytes::new());
+        }
+        Fault::Garbage => {
+            let mut garbled = body[..body.len() / 2].to_vec();
+            garbled.extend_from_slice(GARBAGE);
+            response.body = MockBody::Bytes(Bytes::from(garbled));
+        }
+    }
+}
+
+/// Fails every read, which makes the server abort the connection.
+struct Broken;
+
+impl AsyncRead for Broken {
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, _: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "injected connection reset")))
+    }
+}
+
+/// Streams `sent`, then drops the connection.
+pub fn reset_body(sent: Bytes) -> Body {
+    Body::from_stream(ReaderStream::new(io::Cursor::new(sent).chain(Broken)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn empty_fails_before_any_byte() {
+        let mut response = MockResponse::json(StatusCode::OK, r#"{"id": 1}"#);
+        inject(Fault::Empty, &mut response);
+        assert!(response.headers.is_empty());
+        let MockBody::Reset(sent) = &response.body else {
+            panic!("Empty should reset the connection");
+        };
+        assert!(sent.is_empty());
+        assert!(axum::body::to_bytes(reset_body(sent.clone()), usize::MAX).await.is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{StatusCode, Uri},
+    response::Response,
+};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+
+/// Lets the mock act as an HTTP forward proxy for clients that only honor
+/// system proxy settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardProxySettings {
+    /// Hosts answered from the mocks; `*.example.com` also matches subdomains.
+    #[serde(default, alias = "mockHosts")]
+    pub mock_hosts: Vec<String>,
+    /// Forward requests for other hosts to the real server instead of rejecting them.
+    #[serde(default, alias = "passThrough")]
+    pub pass_through: bool,
+}
+
+impl ForwardProxySettings {
+    pub fn is_mocked(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.mock_hosts.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+                None => host == pattern,
+            }
+        })
+    }
+}
+
+/// Where a proxied request should go.
+pub enum Route {
+    /// Answer from the mock endpoints.
+    Mock,
+    /// Pass through to the origin, e.g. `http://example.com:8080`.
+    Upstream(String),
+    Rejected,
+}
+
+/// Routes absolute-form requests (`GET http://host/path`); origin-form requests
+/// are ordinary mock traffic.
+pub fn route(settings: &ForwardProxySettings, uri: &Uri) -> Route {
+    let (Some(scheme), Some(authority)) = (uri.scheme_str(), uri.authority()) else {
+        return Route::Mock;
+    };
+    if settings.is_mocked(authority.host()) {
+        Route::Mock
+    } else if settings.pass_through {
+        Route::Upstream(format!("{}://{}", scheme, authority))
+    } else {
+        Route::Rejected
+    }
+}
+
+pub fn rejected(host: &str) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "error": format!("Host {} is not served by this proxy", host) }).to_string(),
+        ))
+        .unwrap()
+}
+
+/// Answers `CONNECT host:port`. Mocked hosts are tunnelled into this server at
+/// `local_addr` (use TLS with a certificate for the host when clients speak
+/// HTTPS); other hosts are tunnelled to the real destination when pass-through
+/// is on.
+pub fn connect(settings: &ForwardProxySettings, req: Request, local_addr: &str) -> Response<Body> {
+    let Some(authority) = req.uri().authority().cloned() else {
+        return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from("CONNECT requires host:port"))
+            .unwrap();
+    };
+
+    let target = if settings.is_mocked(authority.host()) {
+        local_addr.to_string()
+    } else if settings.pass_through {
+        authority.to_string()
+    } else {
+        return rejected(authority.host());
+    };
+
+    tokio::spawn(async move {
+        let upgraded = match hyper::upgrade::on(req).await {
+            Ok(upgraded) => upgraded,
+            Err(e) => {
+                eprintln!("CONNECT upgrade failed: {}", e);
+                return;
+            }
+        };
+        match tokio::net::TcpStream::connect(&target).await {
+            Ok(mut server) => {
+                let _ = tokio::io::copy_bidirectional(&mut TokioIo::new(upgraded), &mut server).await;
+            }
+            Err(e) => eprintln!("CONNECT to {} failed: {}", target, e),
+        }
+    });
+
+    Response::new(Body::empty())
+}
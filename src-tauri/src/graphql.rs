@@ -0,0 +1,497 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::IncomingRequest;
+
+pub const DEFAULT_PATH: &str = "/graphql";
+/// Nested object types deeper than this are returned as `null` in generated data.
+const MAX_GENERATED_DEPTH: usize = 3;
+
+/// GraphQL behavior for an endpoint: which operations it answers. Every
+/// condition that is set must hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphqlConfig {
+    /// `operationName`, or the name written in the query document.
+    #[serde(default, alias = "operationName")]
+    pub operation_name: Option<String>,
+    /// A top-level field the operation selects, e.g. `user` in `{ user(id: 1) { name } }`.
+    #[serde(default, alias = "rootField")]
+    pub root_field: Option<String>,
+}
+
+/// The operation a request runs: its name and the top-level fields it selects.
+#[derive(Debug, Default)]
+pub struct Operation {
+    pub name: Option<String>,
+    pub root_fields: Vec<String>,
+}
+
+pub fn matches(config: &GraphqlConfig, incoming: &IncomingRequest) -> bool {
+    let Some(operation) = request_operation(incoming) else {
+        return false;
+    };
+    config
+        .operation_name
+        .as_ref()
+        .is_none_or(|name| operation.name.as_ref() == Some(name))
+        && config
+            .root_field
+            .as_ref()
+            .is_none_or(|field| operation.root_fields.contains(field))
+}
+
+/// Reads the query from a JSON body (`{"query", "operationName"}`), an
+/// `application/graphql` body or the `query`/`operationName` parameters of a GET.
+pub fn request_operation(incoming: &IncomingRequest) -> Option<Operation> {
+    let param = |name: &str| {
+        incoming
+            .query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+    let (query, operation_name) = match &incoming.json_body {
+        Some(body) => (
+            body.get("query")?.as_str()?.to_string(),
+            body.get("operationName").and_then(Value::as_str).map(str::to_string),
+        ),
+        None if !incoming.body.is_empty() => (String::from_utf8_lossy(&incoming.body).to_string(), None),
+        None => (param("query")?, param("operationName")),
+    };
+    parse_operation(&query, operation_name.as_deref())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(char),
+    Spread,
+    /// Strings, numbers and anything else that never affects structure.
+    Other,
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ',' => {}
+            '#' => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            '"' => {
+                if chars.next_if_eq(&'"').is_some() {
+                    // Either "" or the start of a """block string"""
+                    if chars.next_if_eq(&'"').is_some() {
+                        let mut quotes = 0;
+                        for c in chars.by_ref() {
+                            quotes = if c == '"' { quotes + 1 } else { 0 };
+                            if quotes == 3 {
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                tokens.push(Token::Other);
+            }
+            '.' => {
+                while chars.next_if_eq(&'.').is_some() {}
+                tokens.push(Token::Spread);
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                tokens.push(Token::Name(name));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                while chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '+' || *c == '-').is_some() {}
+                tokens.push(Token::Other);
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    tokens
+}
+
+/// Index just past the group opened at `start` (a `{`, `(` or `[`).
+fn skip_group(tokens: &[Token], start: usize) -> usize {
+    let (open, close) = match tokens.get(start) {
+        Some(Token::Punct('{')) => ('{', '}'),
+        Some(Token::Punct('(')) => ('(', ')'),
+        Some(Token::Punct('[')) => ('[', ']'),
+        _ => return start + 1,
+    };
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Punct(c) if *c == open => depth += 1,
+            Token::Punct(c) if *c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Top-level field names (aliases resolved) of the selection set at `start`.
+fn root_fields(tokens: &[Token], start: usize) -> Vec<String> {
+    let end = skip_group(tokens, start);
+    let mut fields = Vec::new();
+    let mut index = start + 1;
+    while index < end.saturating_sub(1) {
+        match &tokens[index] {
+            Token::Punct('(') | Token::Punct('{') => {
+                index = skip_group(tokens, index);
+                continue;
+            }
+            // Fragment spreads and directives name something that isn't a field
+            Token::Spread | Token::Punct('@') => index += 1,
+            Token::Name(name) => match tokens.get(index + 1) {
+                Some(Token::Punct(':')) => {
+                    if let Some(Token::Name(field)) = tokens.get(index + 2) {
+                        fields.push(field.clone());
+                    }
+                    index += 2;
+                }
+                _ => fields.push(name.clone()),
+            },
+            _ => {}
+        }
+        index += 1;
+    }
+    fields
+}
+
+/// The operation to run: the one named `operation_name`, else the first.
+pub fn parse_operation(query: &str, operation_name: Option<&str>) -> Option<Operation> {
+    let tokens = tokenize(query);
+    let mut operations = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::Punct('{') => {
+                operations.push(Operation {
+                    name: None,
+                    root_fields: root_fields(&tokens, index),
+                });
+                index = skip_group(&tokens, index);
+            }
+            Token::Name(keyword) if matches!(keyword.as_str(), "query" | "mutation" | "subscription" | "fragment") => {
+                let name = match tokens.get(index + 1) {
+                    Some(Token::Name(name)) => Some(name.clone()),
+                    _ => None,
+                };
+                let mut body = index + 1;
+                while body < tokens.len() && tokens[body] != Token::Punct('{') {
+                    body = if tokens[body] == Token::Punct('(') { skip_group(&tokens, body) } else { body + 1 };
+                }
+                if keyword != "fragment" && body < tokens.len() {
+                    operations.push(Operation {
+                        name,
+                        root_fields: root_fields(&tokens, body),
+                    });
+                }
+                index = skip_group(&tokens, body);
+            }
+            _ => index += 1,
+        }
+    }
+
+    let mut operation = match operation_name {
+        Some(wanted) => {
+            let position = operations.iter().position(|op| op.name.as_deref() == Some(wanted))?;
+            operations.swap_remove(position)
+        }
+        None => operations.into_iter().next()?,
+    };
+    if operation.name.is_none() {
+        operation.name = operation_name.map(str::to_string);
+    }
+    Some(operation)
+}
+
+/// A field's type with list and non-null wrappers removed, except for the list flag.
+#[derive(Debug, Clone)]
+struct TypeRef {
+    name: String,
+    list: bool,
+}
+
+#[derive(Debug, Default)]
+struct Schema {
+    /// Object, interface and input types with their fields in order.
+    types: HashMap<String, Vec<(String, TypeRef)>>,
+    enums: HashMap<String, Vec<String>>,
+    /// First member of each union.
+    unions: HashMap<String, String>,
+    query_type: String,
+    mutation_type: String,
+}
+
+fn parse_type_ref(tokens: &[Token], index: &mut usize) -> Option<TypeRef> {
+    let list = tokens.get(*index) == Some(&Token::Punct('['));
+    if list {
+        *index += 1;
+    }
+    let name = match tokens.get(*index) {
+        Some(Token::Name(name)) => name.clone(),
+        _ => return None,
+    };
+    *index += 1;
+    while matches!(tokens.get(*index), Some(Token::Punct('!' | ']'))) {
+        *index += 1;
+    }
+    Some(TypeRef { name, list })
+}
+
+fn parse_schema(sdl: &str) -> Result<Schema, String> {
+    let tokens = tokenize(sdl);
+    let mut schema = Schema {
+        query_type: "Query".to_string(),
+        mutation_type: "Mutation".to_string(),
+        ..Schema::default()
+    };
+    let mut index = 0;
+    while index < tokens.len() {
+        let Token::Name(keyword) = &tokens[index] else {
+            index += 1;
+            continue;
+        };
+        match keyword.as_str() {
+            "schema" => {
+                let start = index + 1;
+                if tokens.get(start) != Some(&Token::Punct('{')) {
+                    return Err("Invalid GraphQL schema: missing '{' after 'schema'".to_string());
+                }
+                let end = skip_group(&tokens, start);
+                for pair in tokens.get(start..end).unwrap_or_default().windows(3) {
+                    if let [Token::Name(kind), Token::Punct(':'), Token::Name(name)] = pair {
+                        match kind.as_str() {
+                            "query" => schema.query_type = name.clone(),
+                            "mutation" => schema.mutation_type = name.clone(),
+                            _ => {}
+                        }
+                    }
+                }
+                index = end;
+            }
+            "type" | "interface" | "input" => {
+                let Some(Token::Name(name)) = tokens.get(index + 1).cloned() else {
+                    return Err(format!("Invalid GraphQL schema: missing name after '{}'", keyword));
+                };
+                let mut body = index + 2;
+                while body < tokens.len() && tokens[body] != Token::Punct('{') {
+                    body += 1;
+                }
+                let end = skip_group(&tokens, body);
+                let mut fields = Vec::new();
+                let mut cursor = body + 1;
+                while cursor < end.saturating_sub(1) {
+                    match &tokens[cursor] {
+                        Token::Name(field) => {
+                            cursor += 1;
+                            if tokens.get(cursor) == Some(&Token::Punct('(')) {
+                                cursor = skip_group(&tokens, cursor);
+                            }
+                            if tokens.get(cursor) != Some(&Token::Punct(':')) {
+                                continue;
+                            }
+                            cursor += 1;
+                            if let Some(type_ref) = parse_type_ref(&tokens, &mut cursor) {
+                                fields.push((field.clone(), type_ref));
+                            }
+                        }
+                        _ => cursor += 1,
+                    }
+                }
+                schema.types.entry(name).or_default().extend(fields);
+                index = end;
+            }
+            "extend" => index += 1,
+            "enum" => {
+                let Some(Token::Name(name)) = tokens.get(index + 1).cloned() else {
+                    return Err("Invalid GraphQL schema: missing enum name".to_string());
+                };
+                let body = index + 2;
+                if tokens.get(body) != Some(&Token::Punct('{')) {
+                    return Err(format!("Invalid GraphQL schema: missing values of enum '{}'", name));
+                }
+                let end = skip_group(&tokens, body);
+                let values = tokens
+                    .get(body..end)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|token| match token {
+                        Token::Name(value) => Some(value.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                schema.enums.insert(name, values);
+                index = end;
+            }
+            "union" => {
+                let Some(Token::Name(name)) = tokens.get(index + 1) else {
+                    return Err("Invalid GraphQL schema: missing union name".to_string());
+                };
+                let member = tokens.get(index + 2..).unwrap_or_default().iter().find_map(|token| match token {
+                    Token::Name(member) => Some(member.clone()),
+                    _ => None,
+                });
+                let Some(member) = member else {
+                    return Err(format!("Invalid GraphQL schema: union '{}' has no members", name));
+                };
+                schema.unions.insert(name.clone(), member);
+                index += 2;
+            }
+            _ => index += 1,
+        }
+    }
+    Ok(schema)
+}
+
+fn fake_scalar(type_name: &str, field: &str) -> Value {
+    let field = field.to_lowercase();
+    let by_field = ["email", "phone", "city", "company", "firstName", "lastName", "name"]
+        .into_iter()
+        .find(|kind| field.contains(&kind.to_lowercase()));
+    match type_name {
+        "ID" => json!(crate::faker::fake("uuid").unwrap_or_default()),
+        "Int" => json!(rand::random::<u8>() as i64 + 1),
+        "Float" => json!((rand::random::<f64>() * 1000.0).round() / 10.0),
+        "Boolean" => json!(true),
+        _ => json!(crate::faker::fake(by_field.unwrap_or("word")).unwrap_or_default()),
+    }
+}
+
+fn fake_value(schema: &Schema, type_ref: &TypeRef, field: &str, depth: usize) -> Value {
+    let single = fake_single(schema, &type_ref.name, field, depth);
+    if type_ref.list {
+        json!([single])
+    } else {
+        single
+    }
+}
+
+fn fake_single(schema: &Schema, type_name: &str, field: &str, depth: usize) -> Value {
+    let type_name = schema.unions.get(type_name).map(String::as_str).unwrap_or(type_name);
+    if let Some(values) = schema.enums.get(type_name) {
+        return values.first().map(|value| json!(value)).unwrap_or(Value::Null);
+    }
+    match schema.types.get(type_name) {
+        Some(_) if depth >= MAX_GENERATED_DEPTH => Value::Null,
+        Some(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, type_ref)| (name.clone(), fake_value(schema, type_ref, name, depth + 1)))
+                .collect(),
+        ),
+        None => fake_scalar(type_name, field),
+    }
+}
+
+/// One endpoint per `Query` and `Mutation` field, answering with fake data
+/// shaped like the field's type.
+pub fn endpoints_from_schema(sdl: &str, path: &str) -> Result<Vec<Endpoint>, String> {
+    let schema = parse_schema(sdl)?;
+    let mut endpoints = Vec::new();
+    for root in [&schema.query_type, &schema.mutation_type] {
+        let Some(fields) = schema.types.get(root) else {
+            continue;
+        };
+        for (field, type_ref) in fields {
+            let data = json!({ "data": { field.clone(): fake_value(&schema, type_ref, field, 0) } });
+            let mut endpoint = Endpoint::new(
+                "POST".to_string(),
+                path.to_string(),
+                200,
+                0,
+                serde_json::to_string_pretty(&data).unwrap_or_default(),
+            );
+            endpoint.graphql = Some(GraphqlConfig {
+                operation_name: None,
+                root_field: Some(field.clone()),
+            });
+            endpoint.tags.push("graphql".to_string());
+            endpoints.push(endpoint);
+        }
+    }
+    if endpoints.is_empty() {
+        return Err("GraphQL schema has no Query or Mutation fields".to_string());
+    }
+    Ok(endpoints)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportGraphqlSchemaParams {
+    sdl: String,
+    /// Path the generated endpoints answer on; defaults to `/graphql`.
+    path: Option<String>,
+}
+
+/// Adds an endpoint per root field of the schema and returns the new endpoints.
+#[tauri::command]
+pub async fn import_graphql_schema(
+    state: tauri::State<'_, AppState>,
+    params: ImportGraphqlSchemaParams,
+) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let path = params.path.unwrap_or_else(|| DEFAULT_PATH.to_string());
+    let imported = endpoints_from_schema(&params.sdl, &path)?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        schema { query: Root }
+        type Root { user(id: ID!): User, users: [User!]! }
+        type User { id: ID!, role: Role, pet: Pet }
+        enum Role { ADMIN MEMBER }
+        union Pet = Cat | Dog
+        type Cat { name: String }
+        type Dog { name: String }
+    "#;
+
+    #[test]
+    fn parses_types_enums_and_unions() {
+        let schema = parse_schema(SDL).unwrap();
+        assert_eq!(schema.query_type, "Root");
+        assert_eq!(schema.types["Root"].len(), 2);
+        assert!(schema.types["Root"][1].1.list);
+        assert_eq!(schema.enums["Role"], ["ADMIN", "MEMBER"]);
+        assert_eq!(schema.unions["Pet"], "Cat");
+    }
+
+    #[test]
+    fn truncated_schemas_do_not_panic() {
+        for end in 0..SDL.len() {
+            let _ = parse_schema(&SDL[..end]);
+        }
+        assert!(parse_schema("enum Role").is_err());
+        assert!(parse_schema("type Query { a: Int } union").is_err());
+        assert!(parse_schema("union Pet =").is_err());
+        assert!(parse_schema("schema").is_err());
+    }
+}
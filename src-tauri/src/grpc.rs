@@ -0,0 +1,205 @@
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use base64::Engine;
+use http_body::Frame;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::{MockBody, MockResponse};
+
+pub const GRPC_CONTENT_TYPE: &str = "application/grpc";
+/// `INTERNAL`, for responses that can't be encoded as the method's output message.
+const STATUS_INTERNAL: u32 = 13;
+
+/// gRPC behavior for an endpoint answering one unary method at
+/// `/<package.Service>/<Method>`. The endpoint's response is the output
+/// message written as protobuf JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    /// Base64 `FileDescriptorSet` holding the service.
+    pub descriptor: String,
+    /// gRPC status code; anything but 0 (OK) answers with that error and no message.
+    #[serde(default)]
+    pub status: u32,
+    /// `grpc-message` sent with a non-OK status.
+    #[serde(default)]
+    pub message: String,
+}
+
+fn output_descriptor(config: &GrpcConfig, path: &str) -> Result<MessageDescriptor, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&config.descriptor)
+        .map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let (service, method) = path
+        .trim_start_matches('/')
+        .split_once('/')
+        .ok_or_else(|| format!("Not a gRPC method path: {}", path))?;
+    let service = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| format!("Service not found: {}", service))?;
+    let method = service
+        .methods()
+        .find(|m| m.name() == method)
+        .ok_or_else(|| format!("Method not found: {}", method))?;
+    Ok(method.output())
+}
+
+/// Percent-encodes `grpc-message` as the gRPC spec requires, keeping it a
+/// valid header value.
+fn encode_message(message: &str) -> String {
+    const RESERVED: &AsciiSet = &CONTROLS.add(b'%');
+    utf8_percent_encode(message, RESERVED).to_string()
+}
+
+/// A response with no message: the status travels in the headers (gRPC
+/// "Trailers-Only").
+fn status_only(code: u32, message: &str) -> MockResponse {
+    let mut response = MockResponse::new(StatusCode::OK, GRPC_CONTENT_TYPE, Bytes::new());
+    response.headers.push(("grpc-status".to_string(), code.to_string()));
+    if !message.is_empty() {
+        response.headers.push(("grpc-message".to_string(), encode_message(message)));
+    }
+    response
+}
+
+/// Encodes `body` (protobuf JSON) as the output message of the method at
+/// `path`, or answers with the configured error status.
+pub fn build_response(config: &GrpcConfig, path: &str, body: &str) -> MockResponse {
+    if config.status != 0 {
+        return status_only(config.status, &config.message);
+    }
+    let encoded = output_descriptor(config, path).and_then(|descriptor| {
+        let mut deserializer = serde_json::Deserializer::from_str(body);
+        DynamicMessage::deserialize(descriptor, &mut deserializer)
+            .map(|message| message.encode_to_vec())
+            .map_err(|e| format!("Response doesn't match the output message: {}", e))
+    });
+    match encoded {
+        Ok(message) => {
+            // Length-prefixed message: uncompressed flag, then the big-endian length
+            let mut frame = Vec::with_capacity(message.len() + 5);
+            frame.push(0);
+            frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+            frame.extend_from_slice(&message);
+            let mut response = MockResponse::new(StatusCode::OK, GRPC_CONTENT_TYPE, Bytes::new());
+            response.body = MockBody::Grpc(Bytes::from(frame));
+            response
+        }
+        Err(e) => status_only(STATUS_INTERNAL, &e),
+    }
+}
+
+/// One framed message followed by `grpc-status: 0` trailers.
+struct UnaryBody {
+    message: Option<Bytes>,
+    trailers: Option<HeaderMap>,
+}
+
+impl http_body::Body for UnaryBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        if let Some(message) = self.message.take() {
+            return Poll::Ready(Some(Ok(Frame::data(message))));
+        }
+        Poll::Ready(self.trailers.take().map(|trailers| Ok(Frame::trailers(trailers))))
+    }
+}
+
+pub fn body(message: Bytes) -> Body {
+    let mut trailers = HeaderMap::new();
+    trailers.insert("grpc-status", HeaderValue::from_static("0"));
+    Body::new(UnaryBody {
+        message: Some(message),
+        trailers: Some(trailers),
+    })
+}
+
+/// Compiles a `.proto` file (imports resolved next to it), or reads a
+/// descriptor set produced by `protoc --descriptor_set_out`.
+fn load_descriptor(path: &std::path::Path) -> Result<Vec<u8>, String> {
+    if path.extension().is_some_and(|ext| ext == "proto") {
+        let include = path.parent().unwrap_or(std::path::Path::new("."));
+        let set = protox::compile([path], [include]).map_err(|e| format!("Failed to compile proto: {}", e))?;
+        Ok(set.encode_to_vec())
+    } else {
+        std::fs::read(path).map_err(|e| format!("Failed to read descriptor: {}", e))
+    }
+}
+
+/// The output message with every field at its default value, as a starting
+/// point for editing the response.
+fn skeleton(descriptor: MessageDescriptor) -> String {
+    let message = DynamicMessage::new(descriptor);
+    let options = SerializeOptions::new().skip_default_fields(false);
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::pretty(&mut out);
+    match message.serialize_with_options(&mut serializer, &options) {
+        Ok(()) => String::from_utf8(out).unwrap_or_default(),
+        Err(_) => "{}".to_string(),
+    }
+}
+
+/// One endpoint per unary method in the descriptor. Streaming methods are skipped.
+pub fn endpoints_from_descriptor(bytes: &[u8]) -> Result<Vec<Endpoint>, String> {
+    let pool = DescriptorPool::decode(bytes).map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let descriptor = base64::engine::general_purpose::STANDARD.encode(bytes);
+    let mut endpoints = Vec::new();
+    for service in pool.services() {
+        for method in service.methods() {
+            if method.is_client_streaming() || method.is_server_streaming() {
+                continue;
+            }
+            let mut endpoint = Endpoint::new(
+                "POST".to_string(),
+                format!("/{}/{}", service.full_name(), method.name()),
+                200,
+                0,
+                skeleton(method.output()),
+            );
+            endpoint.content_type = GRPC_CONTENT_TYPE.to_string();
+            endpoint.grpc = Some(GrpcConfig {
+                descriptor: descriptor.clone(),
+                status: 0,
+                message: String::new(),
+            });
+            endpoint.tags.push("grpc".to_string());
+            endpoint.tags.push(service.name().to_string());
+            endpoints.push(endpoint);
+        }
+    }
+    if endpoints.is_empty() {
+        return Err("Descriptor has no unary methods".to_string());
+    }
+    Ok(endpoints)
+}
+
+/// Adds an endpoint per unary method of a `.proto` file or descriptor set and
+/// returns the new endpoints.
+#[tauri::command]
+pub async fn import_proto(state: tauri::State<'_, AppState>, path: String) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let bytes = load_descriptor(std::path::Path::new(&path))?;
+    let imported = endpoints_from_descriptor(&bytes)?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grpc_message_is_percent_encoded() {
+        assert_eq!(encode_message("bad\r\nvalue 100%"), "bad%0D%0Avalue 100%25");
+        assert_eq!(encode_message("café"), "caf%C3%A9");
+    }
+}
@@ -0,0 +1,118 @@
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::endpoints::{AppState, Endpoint, QueryMatcher, ValueMatchKind};
+
+/// Response headers that describe the recorded transfer rather than the mock.
+const SKIPPED_HEADERS: [&str; 6] = [
+    "content-length",
+    "content-encoding",
+    "transfer-encoding",
+    "date",
+    "connection",
+    "keep-alive",
+];
+
+/// Converts HAR entries into endpoints with the recorded status, headers and body.
+///
+/// Query parameters become exact query matchers. When the same request was
+/// recorded more than once, every recording is kept but only the first is
+/// enabled.
+pub fn endpoints_from_har(har: &str) -> Result<Vec<Endpoint>, String> {
+    let doc: Value = serde_json::from_str(har).map_err(|e| format!("Failed to parse HAR file: {}", e))?;
+    let entries = doc
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or("HAR file has no log entries")?;
+
+    let mut endpoints: Vec<Endpoint> = Vec::new();
+    for entry in entries {
+        let (Some(request), Some(response)) = (entry.get("request"), entry.get("response")) else {
+            continue;
+        };
+        let Some(url) = request
+            .get("url")
+            .and_then(Value::as_str)
+            .and_then(|url| url::Url::parse(url).ok())
+        else {
+            continue;
+        };
+        let method = request.get("method").and_then(Value::as_str).unwrap_or("GET").to_uppercase();
+        let status = response
+            .get("status")
+            .and_then(Value::as_u64)
+            .and_then(|status| u16::try_from(status).ok())
+            .filter(|status| *status > 0)
+            .unwrap_or(200);
+
+        let mut endpoint = Endpoint::new(method, url.path().to_string(), status, 0, response_body(response));
+        endpoint.match_query = url
+            .query_pairs()
+            .map(|(name, value)| QueryMatcher {
+                name: name.to_string(),
+                kind: ValueMatchKind::Exact,
+                value: value.to_string(),
+            })
+            .collect();
+        if let Some(mime) = response.pointer("/content/mimeType").and_then(Value::as_str) {
+            if !mime.is_empty() {
+                endpoint.content_type = mime.to_string();
+            }
+        }
+        for header in response.get("headers").and_then(Value::as_array).into_iter().flatten() {
+            let (Some(name), Some(value)) = (
+                header.get("name").and_then(Value::as_str),
+                header.get("value").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            let lower = name.to_ascii_lowercase();
+            if lower == "content-type" || lower.starts_with(':') || SKIPPED_HEADERS.contains(&lower.as_str()) {
+                continue;
+            }
+            endpoint.headers.push((name.to_string(), value.to_string()));
+        }
+
+        endpoint.enabled = !endpoints.iter().any(|existing| same_request(existing, &endpoint));
+        endpoints.push(endpoint);
+    }
+    Ok(endpoints)
+}
+
+/// Recorded body text, decoding base64 content; binary bodies are kept lossily.
+fn response_body(response: &Value) -> String {
+    let text = response.pointer("/content/text").and_then(Value::as_str).unwrap_or_default();
+    match response.pointer("/content/encoding").and_then(Value::as_str) {
+        Some("base64") => base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+            .unwrap_or_else(|_| text.to_string()),
+        _ => text.to_string(),
+    }
+}
+
+fn same_request(a: &Endpoint, b: &Endpoint) -> bool {
+    a.method == b.method
+        && a.path == b.path
+        && a.match_query.len() == b.match_query.len()
+        && a.match_query
+            .iter()
+            .zip(&b.match_query)
+            .all(|(x, y)| x.name == y.name && x.value == y.value)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportHarParams {
+    har: String,
+}
+
+/// Adds the recorded exchanges as endpoints and returns the new endpoints.
+#[tauri::command]
+pub async fn import_har(state: tauri::State<'_, AppState>, params: ImportHarParams) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let imported = endpoints_from_har(&params.har)?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
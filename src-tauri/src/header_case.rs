@@ -0,0 +1,197 @@
+use axum::body::Body;
+use axum::http::Response;
+use axum_server::accept::Accept;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tower_http::add_extension::AddExtension;
+
+/// Header names of a response as configured, attached by `MockResponse`
+/// since the `HeaderMap` only keeps them lowercase.
+#[derive(Debug, Clone)]
+pub struct HeaderCasing(pub Vec<String>);
+
+/// The casing the next response head on an HTTP/1 connection is written
+/// with, handed from the request handler to the connection's stream.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionCasing(Arc<Mutex<Option<Vec<String>>>>);
+
+impl ConnectionCasing {
+    /// Moves the response's `HeaderCasing` to the connection, to be applied
+    /// when hyper writes the head. HTTP/1 writes one response at a time, so
+    /// the head written next is this response's.
+    pub fn prepare(&self, response: &mut Response<Body>) {
+        let names = response.extensions_mut().remove::<HeaderCasing>().map(|casing| casing.0);
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = names;
+    }
+
+    fn take(&self) -> Option<Vec<String>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+/// Writes configured header names with the casing they were typed in,
+/// e.g. `ETag` and `X-API-Key`, where hyper would write them lowercase or
+/// Title-Case. Only the casing changes, so lengths stay the same.
+fn rewrite_head(head: &mut [u8], names: &[String]) {
+    // The status line comes first and has no header name
+    let mut lines = head.split_mut(|b| *b == b'\n').skip(1);
+    for line in &mut lines {
+        let Some(colon) = line.iter().position(|b| *b == b':') else {
+            continue;
+        };
+        let name = &mut line[..colon];
+        if let Some(configured) = names.iter().find(|n| n.as_bytes().eq_ignore_ascii_case(name)) {
+            name.copy_from_slice(configured.as_bytes());
+        }
+    }
+}
+
+/// Wraps a connection's stream so the head of each HTTP/1 response is
+/// written with the casing its handler prepared.
+pub struct CasedStream<I> {
+    inner: I,
+    casing: ConnectionCasing,
+    /// The rewritten head, from the point hyper's writes have reached.
+    head: Vec<u8>,
+}
+
+impl<I: AsyncRead + Unpin> AsyncRead for CasedStream<I> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<I: AsyncWrite + Unpin> AsyncWrite for CasedStream<I> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        if this.head.is_empty() {
+            // hyper buffers the whole head before writing it, so it starts
+            // this write and ends at the blank line
+            let end = buf.windows(4).position(|w| w == b"\r\n\r\n");
+            match (this.casing.take(), end) {
+                (Some(names), Some(end)) if !names.is_empty() && buf.starts_with(b"HTTP/1.") => {
+                    this.head = buf[..end + 4].to_vec();
+                    rewrite_head(&mut this.head, &names);
+                }
+                _ => return Pin::new(&mut this.inner).poll_write(cx, buf),
+            }
+        }
+        // Later writes repeat the unwritten rest of the head, which has the same length
+        let len = this.head.len().min(buf.len());
+        let written = std::task::ready!(Pin::new(&mut this.inner).poll_write(cx, &this.head[..len]))?;
+        this.head.drain(..written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Hands each connection a `ConnectionCasing`, as a request extension, and
+/// wraps its stream to apply it. With `preserve` off the stream writes
+/// unchanged.
+#[derive(Debug, Clone)]
+pub struct HeaderCaseAcceptor<A> {
+    inner: A,
+    preserve: bool,
+}
+
+impl<A> HeaderCaseAcceptor<A> {
+    pub fn new(inner: A, preserve: bool) -> Self {
+        Self { inner, preserve }
+    }
+}
+
+impl<I, S, A> Accept<I, S> for HeaderCaseAcceptor<A>
+where
+    A: Accept<I, S> + Clone + Send + 'static,
+    A::Future: Send,
+    A::Stream: Send,
+    A::Service: Send,
+    I: Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = CasedStream<A::Stream>;
+    type Service = AddExtension<A::Service, ConnectionCasing>;
+    type Future = Pin<Box<dyn Future<Output = io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let (inner, preserve) = (self.inner.clone(), self.preserve);
+        Box::pin(async move {
+            let (stream, service) = inner.accept(stream, service).await?;
+            let casing = ConnectionCasing::default();
+            let stream = CasedStream {
+                inner: stream,
+                // A connection without a shared casing never gets names to apply
+                casing: if preserve { casing.clone() } else { ConnectionCasing::default() },
+                head: Vec::new(),
+            };
+            Ok((stream, AddExtension::new(service, casing)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+    use tokio::io::AsyncReadExt;
+
+    #[test]
+    fn rewrites_only_configured_names() {
+        let mut head = b"HTTP/1.1 200 OK\r\netag: \"1\"\r\nx-api-key: a:b\r\nvary: accept\r\n\r\n".to_vec();
+        rewrite_head(&mut head, &["ETag".to_string(), "X-API-Key".to_string()]);
+        assert_eq!(
+            head,
+            b"HTTP/1.1 200 OK\r\nETag: \"1\"\r\nX-API-Key: a:b\r\nvary: accept\r\n\r\n".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn hyper_writes_configured_casing_on_the_wire() {
+        let (client, server) = tokio::io::duplex(4096);
+        let casing = ConnectionCasing::default();
+        let stream = CasedStream {
+            inner: server,
+            casing: casing.clone(),
+            head: Vec::new(),
+        };
+        let service = service_fn(move |_request| {
+            let casing = casing.clone();
+            async move {
+                let mut response = Response::builder()
+                    .header("ETag", "\"1\"")
+                    .header("Set-Cookie", "a=1")
+                    .header("Set-Cookie", "b=2")
+                    .extension(HeaderCasing(vec!["ETag".to_string(), "Set-Cookie".to_string()]))
+                    .body(Body::from("ok"))
+                    .unwrap();
+                casing.prepare(&mut response);
+                Ok::<_, std::convert::Infallible>(response)
+            }
+        });
+        tokio::spawn(hyper::server::conn::http1::Builder::new().serve_connection(TokioIo::new(stream), service));
+
+        let (mut read, mut write) = tokio::io::split(client);
+        tokio::io::AsyncWriteExt::write_all(&mut write, b"GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut wire = String::new();
+        read.read_to_string(&mut wire).await.unwrap();
+
+        assert!(wire.contains("\r\nETag: \"1\"\r\n"), "{}", wire);
+        assert!(wire.contains("\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n"), "{}", wire);
+        assert!(wire.contains("\r\ncontent-length: 2\r\n"), "{}", wire);
+        assert!(wire.ends_with("\r\n\r\nok"), "{}", wire);
+    }
+}
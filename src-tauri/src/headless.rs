@@ -0,0 +1,311 @@
+use std::time::{Duration, SystemTime};
+use tokio::task::AbortHandle;
+
+use crate::endpoints::{AppState, Endpoint, EndpointKind, ProjectData, ServerSettings};
+
+/// Command-line options for running a project without the GUI.
+pub struct HeadlessArgs {
+    pub project_path: String,
+    pub port: Option<u16>,
+    pub bind_addr: Option<String>,
+    /// Reload the project whenever the file changes on disk.
+    pub watch: bool,
+}
+
+impl HeadlessArgs {
+    /// Returns `Ok(None)` when `--headless` was not passed, so the GUI should start.
+    pub fn parse(args: &[String]) -> Result<Option<Self>, String> {
+        if !args.iter().any(|a| a == "--headless") {
+            return Ok(None);
+        }
+
+        let mut project_path = None;
+        let mut port = None;
+        let mut bind_addr = None;
+        let mut watch = false;
+
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--headless" => {
+                    project_path = Some(
+                        iter.next()
+                            .ok_or_else(|| "--headless requires a project file".to_string())?
+                            .clone(),
+                    );
+                }
+                "--port" => {
+                    let value = iter.next().ok_or_else(|| "--port requires a value".to_string())?;
+                    port = Some(value.parse::<u16>().map_err(|e| format!("Invalid port '{}': {}", value, e))?);
+                }
+                "--bind" => {
+                    bind_addr = Some(
+                        iter.next()
+                            .ok_or_else(|| "--bind requires an address".to_string())?
+                            .clone(),
+                    );
+                }
+                "--watch" => watch = true,
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+
+        Ok(Some(Self {
+            project_path: project_path.ok_or_else(|| "--headless requires a project file".to_string())?,
+            port,
+            bind_addr,
+            watch,
+        }))
+    }
+}
+
+pub fn load_project_file(path: &str) -> Result<ProjectData, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read project file {}: {}", path, e))?;
+    let project = crate::lenient_json::parse(&content)
+        .map_err(|e| format!("Invalid project file {}: {}", path, e))?;
+    crate::migration::migrate(project).map_err(|e| format!("{}: {}", path, e))
+}
+
+pub fn run(args: HeadlessArgs) -> Result<(), String> {
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| format!("Failed to start async runtime: {}", e))?;
+    runtime.block_on(serve(args))
+}
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+async fn serve(args: HeadlessArgs) -> Result<(), String> {
+    let project = load_project_file(&args.project_path)?;
+    let app_state = AppState::new();
+    *app_state.endpoints.write().await = project.endpoints;
+    *app_state.environments.write().await = project.environments.unwrap_or_default();
+    crate::startup::run(&app_state, &project.settings.startup).await?;
+
+    let mut settings = app_state.environments.read().await.resolve_settings(&project.settings);
+    if let Some(port) = args.port {
+        settings.port = port;
+    }
+    if let Some(bind_addr) = args.bind_addr {
+        settings.bind_addr = bind_addr;
+    }
+    let (port, bind_addr, enable_tls) = (settings.port, settings.bind_addr.clone(), settings.enable_tls);
+
+    crate::template::sync_partials(&app_state.templates, &settings.partials).await?;
+    *app_state.server_settings.write().await = settings.clone();
+    let server_state = crate::server::ServerState::new(&app_state, &settings)?;
+
+    let shutdown_tx = if enable_tls {
+        let tls = project.tls_config
+            .ok_or_else(|| "TLS is enabled but no certificate configured".to_string())?;
+
+        crate::server::start_tls_server(port, bind_addr.clone(), server_state, tls.cert_path, tls.key_path)
+            .await
+            .map_err(|e| format!("Failed to start TLS server: {}", e))?
+    } else {
+        crate::server::start_server(port, bind_addr.clone(), server_state)
+            .await
+            .map_err(|e| format!("Failed to start server: {}", e))?
+    };
+
+    let protocol = if enable_tls { "https" } else { "http" };
+    println!("Serving project \"{}\" on {}://{}:{}", project.name, protocol, bind_addr, port);
+    print!("{}", route_table(&app_state.endpoints.read().await, &settings));
+
+    let mut timeline = crate::timeline::spawn(app_state.endpoints.clone(), settings.timeline);
+
+    let mut hangup = listen_for_hangup()?;
+    let mut last_modified = modified_time(&args.project_path);
+    loop {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result.map_err(|e| format!("Failed to listen for shutdown signal: {}", e))?;
+                break;
+            }
+            _ = next_hangup(&mut hangup) => {
+                last_modified = modified_time(&args.project_path);
+                reload(&args.project_path, &app_state, &mut timeline).await;
+            }
+            _ = tokio::time::sleep(WATCH_INTERVAL), if args.watch => {
+                let modified = modified_time(&args.project_path);
+                if modified != last_modified {
+                    last_modified = modified;
+                    reload(&args.project_path, &app_state, &mut timeline).await;
+                }
+            }
+        }
+    }
+
+    if let Some(timeline) = timeline {
+        timeline.abort();
+    }
+    let _ = shutdown_tx.send(());
+    println!("Server stopped");
+    Ok(())
+}
+
+/// Plain-text table of the enabled endpoints, so CI logs record what was served.
+pub fn route_table(endpoints: &[Endpoint], settings: &ServerSettings) -> String {
+    let header = ["METHOD", "PATH", "STATUS", "DELAY", "FAULTS"];
+    let rows: Vec<[String; 5]> = endpoints
+        .iter()
+        .filter(|e| e.enabled)
+        .map(|endpoint| {
+            let method = match endpoint.kind {
+                EndpointKind::Inbox => "ANY".to_string(),
+                EndpointKind::Resource => "CRUD".to_string(),
+                EndpointKind::WebSocket => "WS".to_string(),
+                EndpointKind::Static => "STATIC".to_string(),
+                EndpointKind::Mock => endpoint.method.to_uppercase(),
+            };
+            let faults = crate::profiles::resolve(settings, endpoint)
+                .map(|profile| format!("profile:{}", profile.name))
+                .unwrap_or_else(|| "-".to_string());
+            let delay = match &endpoint.delay_jitter {
+                Some(jitter) => jitter.to_string(),
+                None => format!("{}ms", crate::dependencies::fixed_delay(endpoints, endpoint)),
+            };
+            [
+                method,
+                endpoint.path.clone(),
+                endpoint.status.to_string(),
+                delay,
+                faults,
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; 5]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("  {}\n", line.join("  ").trim_end())
+    };
+
+    let mut table = format_row(header);
+    for row in &rows {
+        table.push_str(&format_row(row.each_ref().map(String::as_str)));
+    }
+    table.push_str(&format!("  {} route(s)\n", rows.len()));
+    table
+}
+
+/// Keeps the settings the running server was built with, naming each that
+/// the project file changed, since they only apply on a restart.
+fn keep_restart_only_settings(settings: &mut ServerSettings, current: &ServerSettings) -> Vec<&'static str> {
+    fn keep<T: PartialEq + Clone>(name: &'static str, loaded: &mut T, current: &T, changed: &mut Vec<&'static str>) {
+        if loaded != current {
+            changed.push(name);
+            *loaded = current.clone();
+        }
+    }
+
+    let mut changed = Vec::new();
+    keep("enable_tls", &mut settings.enable_tls, &current.enable_tls, &mut changed);
+    keep("s3", &mut settings.s3, &current.s3, &mut changed);
+    keep("pcap_path", &mut settings.pcap_path, &current.pcap_path, &mut changed);
+    keep("forward_proxy", &mut settings.forward_proxy, &current.forward_proxy, &mut changed);
+    keep("startup", &mut settings.startup, &current.startup, &mut changed);
+    keep("title_case_headers", &mut settings.title_case_headers, &current.title_case_headers, &mut changed);
+    keep("preserve_header_case", &mut settings.preserve_header_case, &current.preserve_header_case, &mut changed);
+    keep("connect_delay_ms", &mut settings.connect_delay_ms, &current.connect_delay_ms, &mut changed);
+    settings.port = current.port;
+    settings.bind_addr = current.bind_addr.clone();
+    changed
+}
+
+/// Swaps in the endpoints and settings from the project file while the
+/// listener keeps running. The address, port, TLS mode and the settings in
+/// `keep_restart_only_settings` can't change without a restart. On error
+/// the previous project keeps being served.
+async fn reload(path: &str, app_state: &AppState, timeline: &mut Option<AbortHandle>) {
+    let project = match load_project_file(path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Reload failed, still serving the previous project: {}", e);
+            return;
+        }
+    };
+
+    let environments = project.environments.unwrap_or_default();
+    let mut settings = environments.resolve_settings(&project.settings);
+    let changed = keep_restart_only_settings(&mut settings, &*app_state.server_settings.read().await);
+    if !changed.is_empty() {
+        eprintln!("{} changed in {}; restart to apply", changed.join(", "), path);
+    }
+    if let Err(e) = crate::template::sync_partials(&app_state.templates, &settings.partials).await {
+        eprintln!("Reload failed, still serving the previous project: {}", e);
+        return;
+    }
+
+    *app_state.endpoints.write().await = project.endpoints;
+    *app_state.environments.write().await = environments;
+    if let Some(previous) = timeline.take() {
+        previous.abort();
+    }
+    *timeline = crate::timeline::spawn(app_state.endpoints.clone(), settings.timeline.clone());
+
+    println!("Reloaded project \"{}\"", project.name);
+    print!("{}", route_table(&app_state.endpoints.read().await, &settings));
+    *app_state.server_settings.write().await = settings;
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(unix)]
+type Hangup = tokio::signal::unix::Signal;
+#[cfg(not(unix))]
+type Hangup = ();
+
+#[cfg(unix)]
+fn listen_for_hangup() -> Result<Hangup, String> {
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .map_err(|e| format!("Failed to listen for SIGHUP: {}", e))
+}
+
+#[cfg(not(unix))]
+fn listen_for_hangup() -> Result<Hangup, String> {
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn next_hangup(signal: &mut Hangup) {
+    signal.recv().await;
+}
+
+#[cfg(not(unix))]
+async fn next_hangup(_: &mut Hangup) {
+    std::future::pending::<()>().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reload_keeps_settings_that_need_a_restart() {
+        let current = AppState::new().server_settings.read().await.clone();
+        let mut loaded = current.clone();
+        loaded.port = 4000;
+        loaded.connect_delay_ms = 250;
+        loaded.pcap_path = Some("capture.pcap".to_string());
+        loaded.latency_budget_ms = Some(100);
+
+        let changed = keep_restart_only_settings(&mut loaded, &current);
+        assert_eq!(changed, ["pcap_path", "connect_delay_ms"]);
+        assert_eq!((loaded.port, loaded.connect_delay_ms), (current.port, 0));
+        assert_eq!(loaded.pcap_path, None);
+        assert_eq!(loaded.latency_budget_ms, Some(100));
+    }
+}
@@ -0,0 +1,156 @@
+use axum::http::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::server::MockResponse;
+
+/// Responses are replayed for a day, as most real APIs keep their keys.
+const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Keys kept at most; beyond this the oldest responses are forgotten first.
+const MAX_KEYS: usize = 1000;
+
+/// `(endpoint id, Idempotency-Key)`
+pub type IdempotencyKey = (String, String);
+
+/// Responses produced per key, and keys whose first request is still being answered.
+pub type IdempotencyCache = Arc<Mutex<HashMap<IdempotencyKey, Entry>>>;
+
+pub enum Entry {
+    Pending,
+    Done {
+        response: MockResponse,
+        stored: Instant,
+        /// `memory::tick` at the time, which orders entries stored within the same instant.
+        order: u64,
+    },
+}
+
+pub enum Lookup {
+    /// A response was already produced for the key.
+    Replay(MockResponse),
+    /// Another request with the key is still being answered.
+    Conflict(MockResponse),
+    /// The key is new, and this request answers it.
+    Claimed(Claim),
+}
+
+/// Marks a key as being answered. Dropping it without `complete` frees the
+/// key again, for responses that are never replayed such as faults.
+pub struct Claim {
+    cache: IdempotencyCache,
+    key: Option<IdempotencyKey>,
+}
+
+impl Claim {
+    /// Stores `response` to replay for later requests with the key.
+    pub fn complete(mut self, response: &MockResponse) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let mut entries = lock(&self.cache);
+        let entry = Entry::Done {
+            response: response.clone(),
+            stored: Instant::now(),
+            order: crate::memory::tick(),
+        };
+        entries.insert(key, entry);
+        evict(&mut entries);
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            lock(&self.cache).remove(&key);
+        }
+    }
+}
+
+fn lock(cache: &IdempotencyCache) -> MutexGuard<'_, HashMap<IdempotencyKey, Entry>> {
+    cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Replays the response stored for `key`, or claims the key for this request.
+pub fn lookup(cache: &IdempotencyCache, key: IdempotencyKey) -> Lookup {
+    let mut entries = lock(cache);
+    match entries.get(&key) {
+        Some(Entry::Done { response, stored, .. }) if stored.elapsed() < TTL => {
+            return Lookup::Replay(response.clone())
+        }
+        Some(Entry::Pending) => {
+            return Lookup::Conflict(MockResponse::json(
+                StatusCode::CONFLICT,
+                serde_json::json!({ "error": "A request with this Idempotency-Key is still in progress" }).to_string(),
+            ));
+        }
+        _ => {}
+    }
+    entries.insert(key.clone(), Entry::Pending);
+    Lookup::Claimed(Claim {
+        cache: cache.clone(),
+        key: Some(key),
+    })
+}
+
+/// Forgets expired responses, then the oldest ones while over `MAX_KEYS`.
+fn evict(entries: &mut HashMap<IdempotencyKey, Entry>) {
+    entries.retain(|_, entry| !matches!(entry, Entry::Done { stored, .. } if stored.elapsed() >= TTL));
+    while entries.len() > MAX_KEYS {
+        let oldest = entries
+            .iter()
+            .filter_map(|(key, entry)| match entry {
+                Entry::Done { order, .. } => Some((key, *order)),
+                Entry::Pending => None,
+            })
+            .min_by_key(|(_, order)| *order)
+            .map(|(key, _)| key.clone());
+        match oldest {
+            Some(key) => entries.remove(&key),
+            None => break,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> IdempotencyKey {
+        ("orders".to_string(), name.to_string())
+    }
+
+    fn claim(cache: &IdempotencyCache, name: &str) -> Claim {
+        match lookup(cache, key(name)) {
+            Lookup::Claimed(claim) => claim,
+            _ => panic!("{} was not claimed", name),
+        }
+    }
+
+    #[test]
+    fn replays_completed_keys_and_rejects_pending_ones() {
+        let cache = IdempotencyCache::default();
+        let first = claim(&cache, "a");
+        assert!(matches!(lookup(&cache, key("a")), Lookup::Conflict(r) if r.status == StatusCode::CONFLICT));
+
+        first.complete(&MockResponse::json(StatusCode::CREATED, "{}"));
+        assert!(matches!(lookup(&cache, key("a")), Lookup::Replay(r) if r.status == StatusCode::CREATED));
+    }
+
+    #[test]
+    fn dropped_claims_free_the_key() {
+        let cache = IdempotencyCache::default();
+        drop(claim(&cache, "a"));
+        claim(&cache, "a");
+    }
+
+    #[test]
+    fn oldest_responses_are_forgotten_beyond_the_cap() {
+        let cache = IdempotencyCache::default();
+        for i in 0..=MAX_KEYS {
+            claim(&cache, &i.to_string()).complete(&MockResponse::json(StatusCode::OK, "{}"));
+        }
+        assert_eq!(lock(&cache).len(), MAX_KEYS);
+        assert!(matches!(lookup(&cache, key("0")), Lookup::Claimed(_)));
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::memory::{Area, MemoryBudget};
+use crate::server::IncomingRequest;
+
+/// Oldest entries are dropped once the inbox holds this many requests.
+const MAX_INBOX_ENTRIES: usize = 1000;
+
+pub type Inbox = Arc<RwLock<VecDeque<InboxEntry>>>;
+
+/// A request captured by an inbox endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct InboxEntry {
+    pub id: String,
+    pub endpoint_id: String,
+    pub received_at: String,
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// When the entry was stored, for evicting the oldest data across stores.
+    #[serde(skip)]
+    pub(crate) accessed: u64,
+}
+
+pub async fn record(inbox: &Inbox, budget: &MemoryBudget, endpoint: &Endpoint, incoming: &IncomingRequest) {
+    let entry = InboxEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        endpoint_id: endpoint.id.clone(),
+        received_at: chrono::Utc::now().to_rfc3339(),
+        method: incoming.method.to_string(),
+        path: incoming.path.clone(),
+        query: incoming.query.clone(),
+        headers: incoming.header_pairs(),
+        body: String::from_utf8_lossy(&incoming.body).to_string(),
+        accessed: crate::memory::tick(),
+    };
+
+    {
+        let mut entries = inbox.write().await;
+        if entries.len() >= MAX_INBOX_ENTRIES {
+            if let Some(dropped) = entries.pop_front() {
+                budget.removed(Area::Inbox, crate::memory::inbox_entry_size(&dropped));
+            }
+        }
+        budget.added(Area::Inbox, crate::memory::inbox_entry_size(&entry));
+        entries.push_back(entry);
+    }
+    budget.enforce().await;
+}
+
+/// Lists captured requests, newest first, optionally for a single endpoint.
+#[tauri::command]
+pub async fn get_inbox(
+    state: tauri::State<'_, AppState>,
+    endpoint_id: Option<String>,
+) -> Result<Vec<InboxEntry>, String> {
+    let entries = state.inbox.read().await;
+    Ok(entries
+        .iter()
+        .rev()
+        .filter(|entry| endpoint_id.as_ref().is_none_or(|id| &entry.endpoint_id == id))
+        .cloned()
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_inbox_entry(state: tauri::State<'_, AppState>, id: String) -> Result<InboxEntry, String> {
+    state
+        .inbox
+        .read()
+        .await
+        .iter()
+        .find(|entry| entry.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Inbox entry not found: {}", id))
+}
+
+/// Removes captured requests (all, or one endpoint's), returning how many were dropped.
+#[tauri::command]
+pub async fn clear_inbox(state: tauri::State<'_, AppState>, endpoint_id: Option<String>) -> Result<usize, String> {
+    let mut entries = state.inbox.write().await;
+    let before = entries.len();
+    let mut freed = 0;
+    entries.retain(|entry| {
+        let keep = endpoint_id.as_ref().is_some_and(|id| &entry.endpoint_id != id);
+        if !keep {
+            freed += crate::memory::inbox_entry_size(entry);
+        }
+        keep
+    });
+    state.memory.removed(Area::Inbox, freed);
+    Ok(before - entries.len())
+}
@@ -0,0 +1,155 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Sampled delays are capped here so a long-tailed draw can't stall a client
+/// for good.
+const MAX_SAMPLED_MS: f64 = 60_000.0;
+
+/// A random delay drawn per request, used in place of the fixed `delay`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "lowercase")]
+pub enum DelayJitter {
+    /// Any value in `min_ms..=max_ms`, equally likely.
+    Uniform {
+        #[serde(alias = "minMs")]
+        min_ms: u64,
+        #[serde(alias = "maxMs")]
+        max_ms: u64,
+    },
+    /// Clustered around `mean_ms`; negative draws count as 0.
+    Normal {
+        #[serde(alias = "meanMs")]
+        mean_ms: f64,
+        #[serde(alias = "stdDevMs")]
+        std_dev_ms: f64,
+    },
+    /// At least `scale_ms`, with a long tail of slow responses that grows
+    /// heavier as `shape` gets smaller.
+    Pareto {
+        #[serde(alias = "scaleMs")]
+        scale_ms: f64,
+        shape: f64,
+    },
+}
+
+impl DelayJitter {
+    pub fn sample(&self) -> u64 {
+        let mut rng = rand::thread_rng();
+        let ms = match self {
+            Self::Uniform { min_ms, max_ms } => {
+                return if min_ms < max_ms {
+                    rng.gen_range(*min_ms..=*max_ms)
+                } else {
+                    *min_ms
+                };
+            }
+            Self::Normal { mean_ms, std_dev_ms } => {
+                // Box-Muller transform
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                mean_ms + std_dev_ms * z
+            }
+            Self::Pareto { scale_ms, shape } => {
+                if *shape <= 0.0 {
+                    *scale_ms
+                } else {
+                    // Inverse of the CDF, with u in (0, 1]
+                    let u: f64 = 1.0 - rng.gen::<f64>();
+                    scale_ms / u.powf(1.0 / shape)
+                }
+            }
+        };
+        ms.clamp(0.0, MAX_SAMPLED_MS) as u64
+    }
+
+    /// The slowest delay worth planning for: the uniform maximum, three
+    /// standard deviations above the normal mean, or the cap for a Pareto tail.
+    pub fn upper_bound(&self) -> u64 {
+        let ms = match self {
+            Self::Uniform { min_ms, max_ms } => return *min_ms.max(max_ms),
+            Self::Normal { mean_ms, std_dev_ms } => mean_ms + 3.0 * std_dev_ms.abs(),
+            Self::Pareto { scale_ms, shape } if *shape <= 0.0 => *scale_ms,
+            Self::Pareto { .. } => MAX_SAMPLED_MS,
+        };
+        ms.clamp(0.0, MAX_SAMPLED_MS) as u64
+    }
+}
+
+impl std::fmt::Display for DelayJitter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uniform { min_ms, max_ms } => write!(f, "{}-{}ms delay", min_ms, max_ms),
+            Self::Normal { mean_ms, std_dev_ms } => write!(f, "{}ms ± {}ms delay", mean_ms, std_dev_ms),
+            Self::Pareto { scale_ms, shape } => write!(f, "{}ms+ long-tail delay (shape {})", scale_ms, shape),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_stays_within_bounds() {
+        let jitter = DelayJitter::Uniform { min_ms: 10, max_ms: 20 };
+        assert!((0..1000).map(|_| jitter.sample()).all(|ms| (10..=20).contains(&ms)));
+        assert_eq!(DelayJitter::Uniform { min_ms: 30, max_ms: 5 }.sample(), 30);
+    }
+
+    #[test]
+    fn normal_never_goes_negative() {
+        let jitter = DelayJitter::Normal {
+            mean_ms: 0.0,
+            std_dev_ms: 1_000.0,
+        };
+        assert!((0..1000).map(|_| jitter.sample()).all(|ms| ms as f64 <= MAX_SAMPLED_MS));
+        let nan = DelayJitter::Normal {
+            mean_ms: f64::NAN,
+            std_dev_ms: 1.0,
+        };
+        assert_eq!(nan.sample(), 0);
+    }
+
+    #[test]
+    fn pareto_starts_at_scale_and_is_capped() {
+        let jitter = DelayJitter::Pareto {
+            scale_ms: 100.0,
+            shape: 0.1,
+        };
+        assert!((0..1000)
+            .map(|_| jitter.sample())
+            .all(|ms| (100..=MAX_SAMPLED_MS as u64).contains(&ms)));
+        assert_eq!(
+            DelayJitter::Pareto {
+                scale_ms: 100.0,
+                shape: 0.0
+            }
+            .sample(),
+            100
+        );
+        assert_eq!(
+            DelayJitter::Pareto {
+                scale_ms: 1e12,
+                shape: -1.0
+            }
+            .sample(),
+            MAX_SAMPLED_MS as u64
+        );
+    }
+
+    #[test]
+    fn upper_bound_is_fixed_per_distribution() {
+        assert_eq!(DelayJitter::Uniform { min_ms: 10, max_ms: 20 }.upper_bound(), 20);
+        let normal = DelayJitter::Normal {
+            mean_ms: 100.0,
+            std_dev_ms: 20.0,
+        };
+        assert_eq!(normal.upper_bound(), 160);
+        let pareto = DelayJitter::Pareto {
+            scale_ms: 100.0,
+            shape: 1.5,
+        };
+        assert_eq!(pareto.upper_bound(), MAX_SAMPLED_MS as u64);
+    }
+}
@@ -0,0 +1,52 @@
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// JSON-RPC 2.0 behavior for an endpoint: one endpoint per method. Batch
+/// requests are not matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcConfig {
+    /// Matched against the `method` member of the request.
+    pub method: String,
+    /// Respond with this error object instead of a result.
+    #[serde(default)]
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    /// e.g. -32601 (method not found) or an application-defined code
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+pub fn matches(config: &JsonRpcConfig, incoming: &IncomingRequest) -> bool {
+    incoming
+        .json_body
+        .as_ref()
+        .and_then(|body| body.get("method"))
+        .and_then(Value::as_str)
+        .is_some_and(|method| method == config.method)
+}
+
+/// Wraps the body (JSON, or text sent as a string) as the `result` of a
+/// response carrying the request's `id`, or sends the configured error.
+/// Notifications (requests without an `id`) get an empty 204.
+pub fn build_response(config: &JsonRpcConfig, incoming: &IncomingRequest, body: &str) -> MockResponse {
+    let Some(id) = incoming.json_body.as_ref().and_then(|body| body.get("id")).cloned() else {
+        return MockResponse::new(StatusCode::NO_CONTENT, "application/json", Bytes::new());
+    };
+    let envelope = match &config.error {
+        Some(error) => json!({ "jsonrpc": "2.0", "error": error, "id": id }),
+        None => {
+            let result = serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()));
+            json!({ "jsonrpc": "2.0", "result": result, "id": id })
+        }
+    };
+    MockResponse::json(StatusCode::OK, envelope.to_string())
+}
@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+use crate::endpoints::{Endpoint, ResponseSource};
+
+/// JSON5 fixture files up to this size are converted when served; larger
+/// ones stream as they are.
+const MAX_CONVERTED_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Parses strict JSON, falling back to JSON5, which also covers JSONC:
+/// comments, trailing commas, single quotes and unquoted keys.
+pub fn parse(text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).or_else(|strict| {
+        json5::from_str(text).map_err(|_| strict.to_string())
+    })
+}
+
+/// `text` as strict JSON: unchanged when it already is, re-serialized when
+/// it only parses as JSON5, and `None` when it is neither.
+pub fn to_strict(text: &str) -> Option<String> {
+    if is_strict(text) {
+        return Some(text.to_string());
+    }
+    let value: Value = json5::from_str(text).ok()?;
+    serde_json::to_string_pretty(&value).ok()
+}
+
+fn is_strict(text: &str) -> bool {
+    serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok()
+}
+
+/// Rewrites an inline JSON response written as JSON5 so it is served as
+/// strict JSON. Other bodies, including templates, are left alone; file
+/// sources are converted when served, by `strict_file`.
+pub fn normalize_response(endpoint: &mut Endpoint) {
+    if endpoint.response_source != ResponseSource::Inline || !endpoint.content_type.contains("json") {
+        return;
+    }
+    if let Some(strict) = to_strict(&endpoint.response) {
+        endpoint.response = strict;
+    }
+}
+
+/// A JSON5 fixture file's content as strict JSON, to serve in place of the
+/// file. `None` when the file is already strict JSON, isn't JSON5 either or
+/// is too large to convert, so it is streamed unchanged.
+pub async fn strict_file(path: &str) -> Option<String> {
+    if tokio::fs::metadata(path).await.ok()?.len() > MAX_CONVERTED_FILE_BYTES {
+        return None;
+    }
+    let text = tokio::fs::read_to_string(path).await.ok()?;
+    if is_strict(&text) {
+        return None;
+    }
+    to_strict(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const JSONC: &str = r#"{
+        // the user
+        "id": 1,
+        name: 'Ada', /* unquoted key, single quotes */
+        "tags": ["admin",],
+    }"#;
+
+    #[test]
+    fn parses_comments_and_trailing_commas() {
+        assert_eq!(parse(JSONC).unwrap(), json!({ "id": 1, "name": "Ada", "tags": ["admin"] }));
+        assert_eq!(parse(r#"{"a": [1, 2]}"#).unwrap(), json!({ "a": [1, 2] }));
+        assert!(parse("{ not json").is_err());
+    }
+
+    #[test]
+    fn strict_json_is_kept_byte_for_byte() {
+        let strict = "{\"b\":1,   \"a\":2}";
+        assert_eq!(to_strict(strict).as_deref(), Some(strict));
+        let converted = to_strict(JSONC).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&converted).unwrap()["tags"], json!(["admin"]));
+    }
+
+    #[test]
+    fn templated_bodies_pass_through() {
+        let mut endpoint = Endpoint::new("GET".to_string(), "/users".to_string(), 200, 0, String::new());
+        endpoint.content_type = "application/json".to_string();
+        endpoint.response = r#"{"id": {{request.params.id}}, "items": [{{#each items}}{{this}},{{/each}}]}"#.to_string();
+        let template = endpoint.response.clone();
+        normalize_response(&mut endpoint);
+        assert_eq!(endpoint.response, template);
+
+        endpoint.response = "{ id: 1, }".to_string();
+        normalize_response(&mut endpoint);
+        assert_eq!(serde_json::from_str::<Value>(&endpoint.response).unwrap(), json!({ "id": 1 }));
+    }
+
+    #[tokio::test]
+    async fn json5_files_are_converted_when_served() {
+        let dir = std::env::temp_dir().join(format!("aka_lenient_json_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (lenient, strict) = (dir.join("lenient.json"), dir.join("strict.json"));
+        std::fs::write(&lenient, JSONC).unwrap();
+        std::fs::write(&strict, r#"{"id": 1}"#).unwrap();
+
+        let converted = strict_file(lenient.to_str().unwrap()).await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&converted).unwrap()["name"], "Ada");
+        assert_eq!(strict_file(strict.to_str().unwrap()).await, None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
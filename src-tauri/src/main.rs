@@ -1,15 +1,92 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod access;
+mod analysis;
+mod api_key;
+mod bearer;
+mod concurrency;
+mod connect_delay;
+mod cors_check;
+mod decompress;
+mod dependencies;
+mod diff;
+mod docs;
+mod duplicates;
 mod endpoints;
+mod environments;
+mod export;
+mod faker;
+mod fault;
+mod forward_proxy;
+mod graphql;
+mod grpc;
+mod har;
+mod header_case;
+mod idempotency;
+mod headless;
+mod inbox;
+mod jitter;
+mod jsonrpc;
+mod lenient_json;
+mod match_debug;
+mod matching;
+mod memory;
+mod middleware;
+mod migration;
+mod multipart;
+mod mutation;
+mod oauth;
+mod oidc;
+mod openapi;
+mod pcap;
+mod postman;
+mod presets;
+mod profiles;
+mod proxy;
+mod rate_limit;
+mod recording;
+mod report;
+mod request_log;
+mod resources;
+mod run_configs;
+mod s3;
+mod scenarios;
+mod scripting;
+mod sequence;
 mod server;
+mod share;
+mod signing;
+mod snippets;
+mod soap;
+mod sse;
+mod startup;
+mod static_files;
+mod stats;
+mod store;
+mod suggestions;
+mod template;
+mod throttle;
+mod timeline;
+mod tls_info;
+mod trace;
+mod variants;
+mod websocket;
 use tauri::Manager;
 
 use endpoints::{
     AppState,
     add_endpoint,
     get_endpoints,
+    update_endpoint,
+    duplicate_endpoint,
+    toggle_endpoint,
+    set_endpoint_locked,
+    set_read_only,
     delete_endpoint,
+    get_trash,
+    restore_endpoint,
+    purge_trash,
     start_server,
     stop_server,
     get_server_status,
@@ -23,15 +100,80 @@ use endpoints::{
     load_project,
     set_project_state
 };
+use access::{create_access_token, list_access_tokens, revoke_access_tokens};
+use analysis::{analyze_latency, check_head_consistency};
+use cors_check::validate_cors;
+use duplicates::{clear_duplicate_report, get_duplicate_report};
+use environments::{
+    delete_environment, export_env_file, import_env_file, list_environments, rename_environment, save_environment,
+    set_active_environment,
+};
+use export::export_docker;
+use graphql::import_graphql_schema;
+use grpc::import_proto;
+use har::import_har;
+use inbox::{clear_inbox, get_inbox, get_inbox_entry};
+use match_debug::match_request;
+use memory::get_memory_usage;
+use migration::migrate_project;
+use mutation::{start_mutation_test, stop_mutation_test};
+use openapi::{export_openapi, import_openapi};
+use postman::{export_postman, import_postman};
+use presets::{import_preset, list_presets};
+use profiles::{apply_profile, list_profiles};
+use proxy::set_proxy_recording;
+use recording::{
+    accept_recording_suggestions, get_recording_status, get_recording_suggestions, start_recording, stop_recording,
+};
+use report::export_report;
+use request_log::{clear_request_log, get_request_log};
+use resources::reset_resources;
+use run_configs::{delete_run_config, save_run_config, start_server_with_config};
+use scenarios::{get_scenarios, reset_scenarios};
+use scripting::reset_script_state;
+use sequence::reset_sequence;
+use share::{start_share_server, stop_share_server};
+use snippets::generate_snippets;
+use soap::import_wsdl;
+use store::{query_request_history, set_sqlite_store};
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match headless::HeadlessArgs::parse(&args) {
+        Ok(Some(headless_args)) => {
+            if let Err(e) = headless::run(headless_args) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::new())
+        .setup(|app| {
+            memory::spawn_budget_task(app.handle().clone());
+            store::restore(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             add_endpoint,
             get_endpoints,
+            update_endpoint,
+            duplicate_endpoint,
+            toggle_endpoint,
+            set_endpoint_locked,
+            set_read_only,
             delete_endpoint,
+            get_trash,
+            restore_endpoint,
+            purge_trash,
             start_server,
             stop_server,
             get_server_status,
@@ -43,7 +185,65 @@ fn main() {
             cleanup_temp_certificates,
             save_project,
             load_project,
-            set_project_state
+            set_project_state,
+            migrate_project,
+            import_env_file,
+            export_env_file,
+            list_environments,
+            save_environment,
+            rename_environment,
+            delete_environment,
+            set_active_environment,
+            export_docker,
+            analyze_latency,
+            check_head_consistency,
+            validate_cors,
+            generate_snippets,
+            import_openapi,
+            export_openapi,
+            import_postman,
+            export_postman,
+            export_report,
+            start_recording,
+            stop_recording,
+            get_recording_status,
+            get_recording_suggestions,
+            accept_recording_suggestions,
+            import_har,
+            import_graphql_schema,
+            import_proto,
+            import_wsdl,
+            match_request,
+            save_run_config,
+            delete_run_config,
+            start_server_with_config,
+            get_memory_usage,
+            start_share_server,
+            stop_share_server,
+            list_profiles,
+            apply_profile,
+            list_presets,
+            import_preset,
+            get_duplicate_report,
+            clear_duplicate_report,
+            get_request_log,
+            clear_request_log,
+            set_sqlite_store,
+            query_request_history,
+            create_access_token,
+            list_access_tokens,
+            revoke_access_tokens,
+            set_proxy_recording,
+            reset_sequence,
+            get_scenarios,
+            reset_scenarios,
+            reset_resources,
+            reset_script_state,
+            get_inbox,
+            get_inbox_entry,
+            clear_inbox,
+            start_mutation_test,
+            stop_mutation_test
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
@@ -0,0 +1,83 @@
+use axum::body::Bytes;
+use axum::http::{Request, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::AppState;
+use crate::server::{IncomingRequest, MockResponse, ServerState};
+
+/// A request to match without sending it.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchRequestParams {
+    method: String,
+    /// Path, optionally with a query string.
+    path: String,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default)]
+    body: String,
+}
+
+impl MatchRequestParams {
+    fn to_incoming(&self) -> Result<IncomingRequest, String> {
+        let mut builder = Request::builder()
+            .method(self.method.to_uppercase().as_str())
+            .uri(self.path.as_str());
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let (parts, ()) = builder
+            .body(())
+            .map_err(|e| format!("Invalid request: {}", e))?
+            .into_parts();
+        Ok(IncomingRequest::from_parts(&parts, Bytes::from(self.body.clone())))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointMatch {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    /// The first rule the request fails; `None` when the endpoint matches.
+    pub rejected: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchReport {
+    /// Id of the endpoint that would answer; among several matches the most
+    /// specific one wins.
+    pub matched: Option<String>,
+    pub endpoints: Vec<EndpointMatch>,
+}
+
+/// `POST /__admin/match` with a `MatchRequestParams` body.
+pub async fn respond(state: &ServerState, incoming: &IncomingRequest) -> MockResponse {
+    let request = serde_json::from_slice::<MatchRequestParams>(&incoming.body)
+        .map_err(|e| format!("Invalid match request: {}", e))
+        .and_then(|params| params.to_incoming());
+    match request {
+        Ok(request) => {
+            let report = crate::server::explain(state, &request).await;
+            MockResponse::json(StatusCode::OK, serde_json::to_string_pretty(&report).unwrap_or_default())
+        }
+        Err(e) => MockResponse::json(StatusCode::BAD_REQUEST, serde_json::json!({ "error": e }).to_string()),
+    }
+}
+
+/// Reports which endpoint a request would match and why the others don't,
+/// using the current endpoints and settings without starting a server.
+#[tauri::command]
+pub async fn match_request(
+    state: tauri::State<'_, AppState>,
+    params: MatchRequestParams,
+) -> Result<MatchReport, String> {
+    let incoming = params.to_incoming()?;
+    let mut settings = state.server_settings.read().await.clone();
+    // A dry run must not create a capture file
+    settings.pcap_path = None;
+    let server = ServerState::new(&state, &settings)?;
+    Ok(crate::server::explain(&server, &incoming).await)
+}
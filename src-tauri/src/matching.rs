@@ -0,0 +1,188 @@
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use unicode_normalization::UnicodeNormalization;
+
+/// Project-wide leniency for literal and wildcard endpoint paths.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PathMatching {
+    /// `/users` and `/users/` match the same endpoints.
+    #[serde(default, alias = "ignoreTrailingSlash")]
+    pub ignore_trailing_slash: bool,
+    /// `/Users` matches an endpoint at `/users`.
+    #[serde(default, alias = "caseInsensitive")]
+    pub case_insensitive: bool,
+    /// Percent-decode and NFC-normalize paths, so `/caf%C3%A9 menu` and
+    /// `/café%20menu` match an endpoint at `/café menu`. On by default.
+    #[serde(default = "default_decode_paths", alias = "decodePaths")]
+    pub decode_paths: bool,
+}
+
+fn default_decode_paths() -> bool {
+    true
+}
+
+impl Default for PathMatching {
+    fn default() -> Self {
+        Self {
+            ignore_trailing_slash: false,
+            case_insensitive: false,
+            decode_paths: default_decode_paths(),
+        }
+    }
+}
+
+impl PathMatching {
+    /// The path as compared under these options.
+    pub fn normalize<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let mut normalized = Cow::Borrowed(path);
+        if self.decode_paths && (path.contains('%') || !path.is_ascii()) {
+            normalized = Cow::Owned(percent_decode_str(path).decode_utf8_lossy().nfc().collect());
+        }
+        if self.ignore_trailing_slash && normalized.len() > 1 && normalized.ends_with('/') {
+            let trimmed = match normalized.trim_end_matches('/') {
+                "" => "/",
+                trimmed => trimmed,
+            };
+            normalized = Cow::Owned(trimmed.to_string());
+        }
+        if self.case_insensitive {
+            normalized = Cow::Owned(normalized.to_lowercase());
+        }
+        normalized
+    }
+
+    pub fn matches(&self, pattern: &str, path: &str) -> bool {
+        path_matches(&self.normalize(pattern), &self.normalize(path))
+    }
+}
+
+/// Matches a request path against an endpoint path pattern.
+///
+/// `*` matches any characters within a single segment and a `**` segment
+/// matches any number of segments, so `/api/v1/**` covers a whole subtree.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    if !is_wildcard(pattern) {
+        return pattern == path;
+    }
+
+    let pattern_segments: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    segments_match(&pattern_segments, &path_segments)
+}
+
+pub fn is_wildcard(pattern: &str) -> bool {
+    pattern.contains('*')
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => glob_segment(first, segment) && segments_match(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+fn glob_segment(pattern: &str, segment: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == segment,
+        Some(idx) => {
+            let prefix = &pattern[..idx];
+            let rest = &pattern[idx + 1..];
+            if !segment.starts_with(prefix) {
+                return false;
+            }
+            let remaining = &segment[prefix.len()..];
+            (0..=remaining.len())
+                .filter(|i| remaining.is_char_boundary(*i))
+                .any(|i| glob_segment(rest, &remaining[i..]))
+        }
+    }
+}
+
+/// Resolves a small JSONPath subset (`$.a.b[0]`, `$['a'].b`) against a value.
+/// The leading `$` is optional, so `user.id` works too.
+pub fn json_path_lookup<'v>(value: &'v serde_json::Value, path: &str) -> Option<&'v serde_json::Value> {
+    let path = path.trim();
+    let relative;
+    let mut rest = match path.strip_prefix('$') {
+        Some(rest) => rest,
+        None => {
+            relative = format!(".{}", path);
+            relative.as_str()
+        }
+    };
+    let mut current = value;
+
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            let key = &after_dot[..end];
+            if key.is_empty() {
+                return None;
+            }
+            current = current.get(key)?;
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket.find(']')?;
+            let token = after_bracket[..end].trim();
+            current = match token.strip_prefix(['\'', '"']) {
+                Some(quoted) => current.get(quoted.strip_suffix(['\'', '"'])?)?,
+                None => current.get(token.parse::<usize>().ok()?)?,
+            };
+            rest = &after_bracket[end + 1..];
+        } else {
+            return None;
+        }
+    }
+
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_star_stays_within_a_segment() {
+        assert!(path_matches("/users/*", "/users/42"));
+        assert!(path_matches("/files/*.json", "/files/a.json"));
+        assert!(path_matches("/files/*.json", "/files/.json"));
+        assert!(!path_matches("/users/*", "/users/42/posts"));
+        assert!(!path_matches("/files/*.json", "/files/a.xml"));
+        assert!(path_matches("/caf*", "/café"));
+    }
+
+    #[test]
+    fn double_star_spans_segments() {
+        assert!(path_matches("/api/**", "/api"));
+        assert!(path_matches("/api/**", "/api/v1/users/42"));
+        assert!(path_matches("/api/**/users", "/api/v1/v2/users"));
+        assert!(path_matches("/**/health", "/health"));
+        assert!(!path_matches("/api/**/users", "/api/v1/orders"));
+        assert!(!path_matches("/api/**", "/apis/v1"));
+    }
+
+    #[test]
+    fn literal_paths_compare_exactly() {
+        assert!(path_matches("/users", "/users"));
+        assert!(!path_matches("/users", "/users/"));
+        assert!(!path_matches("/users", "/Users"));
+    }
+
+    #[test]
+    fn options_normalize_both_sides() {
+        let lenient = PathMatching {
+            ignore_trailing_slash: true,
+            case_insensitive: true,
+            decode_paths: true,
+        };
+        assert!(lenient.matches("/users", "/Users/"));
+        assert!(lenient.matches("/café menu", "/caf%C3%A9%20menu"));
+        assert!(lenient.matches("/", "//"));
+        assert!(!PathMatching::default().matches("/users", "/users/"));
+    }
+}
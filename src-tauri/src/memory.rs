@@ -0,0 +1,304 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::endpoints::AppState;
+use crate::inbox::{Inbox, InboxEntry};
+use crate::request_log::{RequestLog, RequestLogEntry};
+use crate::resources::{Record, ResourceStore};
+
+pub const WARNING_EVENT: &str = "memory://warning";
+/// How often the budget is picked up from the settings and checked for warnings.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// A warning is emitted once usage crosses this share of the budget.
+const WARNING_RATIO: f64 = 0.9;
+
+/// Approximate bytes held by captured data.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryUsage {
+    pub request_log: usize,
+    pub inbox: usize,
+    pub resources: usize,
+    pub total: usize,
+    pub budget: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    RequestLog,
+    Inbox,
+    Resources,
+}
+
+/// A process-wide counter stamped on captured data when it is stored or
+/// read, so eviction can compare recency across the log, inbox and resources.
+pub fn tick() -> u64 {
+    static TICK: AtomicU64 = AtomicU64::new(0);
+    TICK.fetch_add(1, Ordering::Relaxed)
+}
+
+fn pairs_size(pairs: &[(String, String)]) -> usize {
+    pairs.iter().map(|(name, value)| name.len() + value.len()).sum()
+}
+
+pub(crate) fn log_entry_size(entry: &RequestLogEntry) -> usize {
+    entry.body.len() + entry.path.len() + pairs_size(&entry.headers) + pairs_size(&entry.query)
+}
+
+pub(crate) fn inbox_entry_size(entry: &InboxEntry) -> usize {
+    entry.body.len() + entry.path.len() + pairs_size(&entry.headers) + pairs_size(&entry.query)
+}
+
+pub type SharedBudget = Arc<MemoryBudget>;
+
+/// Sizes of the captured data, kept up to date as it is stored and removed,
+/// and the stores to evict from when they exceed `memory_budget_mb`.
+pub struct MemoryBudget {
+    /// Bytes; 0 when no budget is set.
+    limit: AtomicUsize,
+    request_log_bytes: AtomicUsize,
+    inbox_bytes: AtomicUsize,
+    resource_bytes: AtomicUsize,
+    request_log: RequestLog,
+    inbox: Inbox,
+    resources: ResourceStore,
+}
+
+impl MemoryBudget {
+    pub fn new(request_log: RequestLog, inbox: Inbox, resources: ResourceStore) -> Self {
+        Self {
+            limit: AtomicUsize::new(0),
+            request_log_bytes: AtomicUsize::new(0),
+            inbox_bytes: AtomicUsize::new(0),
+            resource_bytes: AtomicUsize::new(0),
+            request_log,
+            inbox,
+            resources,
+        }
+    }
+
+    fn counter(&self, area: Area) -> &AtomicUsize {
+        match area {
+            Area::RequestLog => &self.request_log_bytes,
+            Area::Inbox => &self.inbox_bytes,
+            Area::Resources => &self.resource_bytes,
+        }
+    }
+
+    pub fn added(&self, area: Area, bytes: usize) {
+        self.counter(area).fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn removed(&self, area: Area, bytes: usize) {
+        let _ = self
+            .counter(area)
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| Some(current.saturating_sub(bytes)));
+    }
+
+    pub fn resized(&self, area: Area, before: usize, after: usize) {
+        if after >= before {
+            self.added(area, after - before);
+        } else {
+            self.removed(area, before - after);
+        }
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        let request_log = self.request_log_bytes.load(Ordering::Relaxed);
+        let inbox = self.inbox_bytes.load(Ordering::Relaxed);
+        let resources = self.resource_bytes.load(Ordering::Relaxed);
+        MemoryUsage {
+            request_log,
+            inbox,
+            resources,
+            total: request_log + inbox + resources,
+            budget: self.limit.load(Ordering::Relaxed),
+        }
+    }
+
+    fn excess(&self) -> usize {
+        let usage = self.usage();
+        if usage.budget == 0 {
+            0
+        } else {
+            usage.total.saturating_sub(usage.budget)
+        }
+    }
+
+    /// Evicts the least recently stored or read entries, across the request
+    /// log, inbox and resource items, until usage is back within the budget.
+    /// Call it after releasing the lock of the store that grew: it takes
+    /// each store's lock in turn, never two at once.
+    pub async fn enforce(&self) {
+        loop {
+            let excess = self.excess();
+            if excess == 0 {
+                return;
+            }
+            let request_log = self.request_log.read().await.front().map(|e| e.accessed);
+            let inbox = self.inbox.read().await.front().map(|e| e.accessed);
+            let resources = self.resources.read().await.values().flatten().map(|r| r.accessed).min();
+            let mut oldest: Vec<(u64, Area)> = [
+                (request_log, Area::RequestLog),
+                (inbox, Area::Inbox),
+                (resources, Area::Resources),
+            ]
+            .into_iter()
+            .filter_map(|(accessed, area)| Some((accessed?, area)))
+            .collect();
+            oldest.sort_by_key(|(accessed, _)| *accessed);
+            let Some(&(_, area)) = oldest.first() else {
+                return;
+            };
+            // Evict from that store until its entries are more recent than another store's oldest
+            let until = oldest.get(1).map_or(u64::MAX, |(accessed, _)| *accessed);
+            let freed = match area {
+                Area::RequestLog => {
+                    let mut log = self.request_log.write().await;
+                    evict_front(&mut *log, excess, until, |e| e.accessed, log_entry_size)
+                }
+                Area::Inbox => {
+                    let mut inbox = self.inbox.write().await;
+                    evict_front(&mut *inbox, excess, until, |e| e.accessed, inbox_entry_size)
+                }
+                Area::Resources => evict_records(&mut *self.resources.write().await, excess, until),
+            };
+            if freed == 0 {
+                return;
+            }
+            self.removed(area, freed);
+        }
+    }
+}
+
+/// Pops entries, oldest first, while they were accessed no later than `until`.
+fn evict_front<T>(
+    entries: &mut std::collections::VecDeque<T>,
+    excess: usize,
+    until: u64,
+    accessed: impl Fn(&T) -> u64,
+    size: impl Fn(&T) -> usize,
+) -> usize {
+    let mut freed = 0;
+    while freed < excess && entries.front().is_some_and(|entry| accessed(entry) <= until) {
+        freed += entries.pop_front().map(|entry| size(&entry)).unwrap_or_default();
+    }
+    freed
+}
+
+/// Removes the least recently used resource items, across collections.
+fn evict_records(collections: &mut HashMap<String, Vec<Record>>, excess: usize, until: u64) -> usize {
+    let mut candidates: Vec<(u64, &str, usize, usize)> = collections
+        .iter()
+        .flat_map(|(id, records)| {
+            records
+                .iter()
+                .enumerate()
+                .map(move |(index, record)| (record.accessed, id.as_str(), index, record.size))
+        })
+        .filter(|(accessed, ..)| *accessed <= until)
+        .collect();
+    candidates.sort_unstable_by_key(|(accessed, ..)| *accessed);
+
+    let mut freed = 0;
+    let mut evicted: HashMap<String, HashSet<usize>> = HashMap::new();
+    for (_, id, index, size) in candidates {
+        if freed >= excess {
+            break;
+        }
+        freed += size;
+        evicted.entry(id.to_string()).or_default().insert(index);
+    }
+    for (id, indexes) in evicted {
+        if let Some(records) = collections.get_mut(&id) {
+            let mut index = 0;
+            records.retain(|_| {
+                index += 1;
+                !indexes.contains(&(index - 1))
+            });
+        }
+    }
+    freed
+}
+
+/// Picks up `memory_budget_mb` from the settings, warning the UI when usage
+/// nears the budget. The budget itself is enforced whenever data is stored.
+pub fn spawn_budget_task(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut warned = false;
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let budget_mb = state.server_settings.read().await.memory_budget_mb.unwrap_or(0);
+            let budget = (budget_mb as usize).saturating_mul(1024 * 1024);
+            state.memory.limit.store(budget, Ordering::Relaxed);
+            if budget == 0 {
+                warned = false;
+                continue;
+            }
+            // A lowered budget applies right away
+            state.memory.enforce().await;
+
+            let current = state.memory.usage();
+            let near = current.total as f64 >= budget as f64 * WARNING_RATIO;
+            if near && !warned {
+                if let Err(e) = app.emit(WARNING_EVENT, &current) {
+                    eprintln!("Failed to emit memory warning: {}", e);
+                }
+            }
+            warned = near;
+        }
+    });
+}
+
+/// Current usage against the budget (0 when no budget is set).
+#[tauri::command]
+pub async fn get_memory_usage(state: tauri::State<'_, AppState>) -> Result<MemoryUsage, String> {
+    Ok(state.memory.usage())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn budget() -> MemoryBudget {
+        MemoryBudget::new(Default::default(), Default::default(), Default::default())
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_resource_items() {
+        let budget = budget();
+        let old = Record::new(json!({ "id": 1 }));
+        let mut read = Record::new(json!({ "id": 2 }));
+        let fresh = Record::new(json!({ "id": 3 }));
+        let size = old.size;
+        // Read after the other two were stored
+        read.accessed = tick();
+        budget.resources.write().await.insert("users".to_string(), vec![old, read]);
+        budget.resources.write().await.insert("orders".to_string(), vec![fresh]);
+        budget.added(Area::Resources, size * 3);
+
+        budget.limit.store(size, Ordering::Relaxed);
+        budget.enforce().await;
+
+        let resources = budget.resources.read().await;
+        assert_eq!(resources["users"].len(), 1);
+        assert_eq!(resources["users"][0].value, json!({ "id": 2 }));
+        assert!(resources["orders"].is_empty());
+        assert_eq!(budget.usage().resources, size);
+    }
+
+    #[tokio::test]
+    async fn no_budget_evicts_nothing() {
+        let budget = budget();
+        let record = Record::new(json!({ "id": 1 }));
+        budget.added(Area::Resources, record.size);
+        budget.resources.write().await.insert("users".to_string(), vec![record]);
+        budget.enforce().await;
+        assert_eq!(budget.resources.read().await["users"].len(), 1);
+    }
+}
@@ -0,0 +1,162 @@
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// One project-wide rule. Rules run in order: request actions before
+/// endpoint matching, response actions after the response is generated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiddlewareRule {
+    /// Requests to this path or below it, e.g. `/api` covers `/api` and
+    /// `/api/users` but not `/apiary`; every request when empty.
+    #[serde(default, alias = "pathPrefix")]
+    pub path_prefix: String,
+    /// Request methods the rule applies to; every method when empty.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(flatten)]
+    pub action: MiddlewareAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum MiddlewareAction {
+    /// Replaces `path_prefix` with `to`, e.g. `/api/v2` -> `/v2`.
+    RewritePath { to: String },
+    StripRequestHeaders { headers: Vec<String> },
+    AddRequestHeaders { headers: Vec<(String, String)> },
+    StripResponseHeaders { headers: Vec<String> },
+    AddResponseHeaders { headers: Vec<(String, String)> },
+    /// Answers immediately; later rules and endpoint matching are skipped.
+    Reject {
+        status: u16,
+        #[serde(default)]
+        body: String,
+    },
+}
+
+impl MiddlewareRule {
+    fn prefix(&self) -> &str {
+        self.path_prefix.trim_end_matches('/')
+    }
+
+    fn applies_to(&self, incoming: &IncomingRequest) -> bool {
+        let under_prefix = incoming
+            .path
+            .strip_prefix(self.prefix())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+        under_prefix
+            && (self.methods.is_empty()
+                || self
+                    .methods
+                    .iter()
+                    .any(|method| method.eq_ignore_ascii_case(incoming.method.as_str())))
+    }
+}
+
+/// The request as endpoint matching should see it, or the response of a
+/// `reject` rule. The request is only copied when a rule changes it.
+pub fn apply_request<'a>(
+    rules: &[MiddlewareRule],
+    incoming: &'a IncomingRequest,
+) -> Result<Cow<'a, IncomingRequest>, MockResponse> {
+    let mut request = Cow::Borrowed(incoming);
+    for rule in rules {
+        if !rule.applies_to(&request) {
+            continue;
+        }
+        match &rule.action {
+            MiddlewareAction::RewritePath { to } => {
+                let request = request.to_mut();
+                let rest = &request.path[rule.prefix().len()..];
+                let mut path = format!("{}{}", to.trim_end_matches('/'), rest);
+                if !path.starts_with('/') {
+                    path.insert(0, '/');
+                }
+                request.path = path;
+            }
+            MiddlewareAction::StripRequestHeaders { headers } => {
+                let request = request.to_mut();
+                for name in headers {
+                    request.headers.remove(name.as_str());
+                }
+            }
+            MiddlewareAction::AddRequestHeaders { headers } => {
+                let request = request.to_mut();
+                for (name, value) in headers {
+                    if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+                        request.headers.insert(name, value);
+                    }
+                }
+            }
+            MiddlewareAction::Reject { status, body } => {
+                let status = StatusCode::from_u16(*status).unwrap_or(StatusCode::FORBIDDEN);
+                let content_type = if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+                    "application/json"
+                } else {
+                    "text/plain"
+                };
+                return Err(MockResponse::new(status, content_type, body.clone()));
+            }
+            MiddlewareAction::StripResponseHeaders { .. } | MiddlewareAction::AddResponseHeaders { .. } => {}
+        }
+    }
+    Ok(request)
+}
+
+/// Applies response header rules; conditions are checked against the
+/// request as the client sent it.
+pub fn apply_response(rules: &[MiddlewareRule], incoming: &IncomingRequest, response: &mut MockResponse) {
+    for rule in rules.iter().filter(|rule| rule.applies_to(incoming)) {
+        match &rule.action {
+            MiddlewareAction::StripResponseHeaders { headers } => response
+                .headers
+                .retain(|(name, _)| !headers.iter().any(|strip| strip.eq_ignore_ascii_case(name))),
+            MiddlewareAction::AddResponseHeaders { headers } => response.apply_headers(headers),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+
+    fn request(path: &str) -> IncomingRequest {
+        let (parts, ()) = axum::http::Request::builder().uri(path).body(()).unwrap().into_parts();
+        IncomingRequest::from_parts(&parts, Bytes::new())
+    }
+
+    fn rewrite(prefix: &str, to: &str) -> MiddlewareRule {
+        MiddlewareRule {
+            path_prefix: prefix.to_string(),
+            methods: Vec::new(),
+            action: MiddlewareAction::RewritePath { to: to.to_string() },
+        }
+    }
+
+    #[test]
+    fn prefix_matches_whole_segments() {
+        let rule = rewrite("/api", "/v2");
+        assert!(rule.applies_to(&request("/api")));
+        assert!(rule.applies_to(&request("/api/users")));
+        assert!(!rule.applies_to(&request("/apiary")));
+        assert!(rewrite("", "/v2").applies_to(&request("/anything")));
+    }
+
+    #[test]
+    fn rewrites_keep_the_rest_of_the_path() {
+        for prefix in ["/api", "/api/"] {
+            let rules = [rewrite(prefix, "/v2")];
+            let incoming = request("/api/users");
+            let Ok(routed) = apply_request(&rules, &incoming) else {
+                panic!("rewrite rejected the request");
+            };
+            assert_eq!(routed.path, "/v2/users");
+            let incoming = request("/api");
+            assert_eq!(apply_request(&rules, &incoming).ok().unwrap().path, "/v2");
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+use crate::endpoints::ProjectData;
+
+/// Version written into project files by this build. Files without a
+/// `version` field predate versioning and count as version 0.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades a version `n` project to version `n + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[normalize_endpoints];
+
+/// Upgrades raw project JSON to `CURRENT_VERSION` and parses it.
+pub fn migrate(mut project: Value) -> Result<ProjectData, String> {
+    let version = project.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Project file version {} is newer than this app supports ({}); please update the app",
+            version, CURRENT_VERSION
+        ));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut project);
+    }
+    if let Value::Object(fields) = &mut project {
+        fields.insert("version".to_string(), Value::from(CURRENT_VERSION));
+    }
+    let mut data: ProjectData =
+        serde_json::from_value(project).map_err(|e| format!("Invalid project data: {}", e))?;
+    // Hand-edited fixtures may use comments or trailing commas
+    for endpoint in data.endpoints.iter_mut().chain(data.trash.iter_mut().flatten()) {
+        crate::lenient_json::normalize_response(endpoint);
+    }
+    Ok(data)
+}
+
+/// Version 0 files were written by hand or by early GUI builds: endpoints may
+/// lack an id, carry `status`/`delay` as strings, or give `headers` as a
+/// `{name: value}` object instead of a list of pairs.
+fn normalize_endpoints(project: &mut Value) {
+    for list in ["endpoints", "trash"] {
+        let Some(endpoints) = project.get_mut(list).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for endpoint in endpoints.iter_mut().filter_map(Value::as_object_mut) {
+            if !endpoint.get("id").is_some_and(Value::is_string) {
+                endpoint.insert("id".to_string(), Value::from(uuid::Uuid::new_v4().to_string()));
+            }
+            for field in ["status", "delay"] {
+                if let Some(number) = endpoint
+                    .get(field)
+                    .and_then(Value::as_str)
+                    .and_then(|text| text.trim().parse::<u64>().ok())
+                {
+                    endpoint.insert(field.to_string(), Value::from(number));
+                }
+            }
+            if let Some(Value::Object(headers)) = endpoint.get("headers") {
+                let pairs: Vec<Value> = headers
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                        Value::from(vec![name.clone(), value])
+                    })
+                    .collect();
+                endpoint.insert("headers".to_string(), Value::Array(pairs));
+            }
+        }
+    }
+}
+
+/// Upgrades a project to the current version without loading it, e.g. to
+/// normalize a file before saving it back.
+#[tauri::command]
+pub async fn migrate_project(project_data: Value) -> Result<ProjectData, String> {
+    migrate(project_data)
+}
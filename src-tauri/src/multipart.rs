@@ -0,0 +1,224 @@
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::server::IncomingRequest;
+
+/// File parts beyond this many are left out of the request log.
+const MAX_FILES: usize = 20;
+/// Files are no longer written to the upload directory once a request's
+/// saved files add up to this many bytes.
+const MAX_SAVED_BYTES: usize = 16 * 1024 * 1024;
+
+/// One part of a `multipart/form-data` body.
+pub struct Part {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Bytes,
+}
+
+/// A file part as recorded in the request log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadedFile {
+    pub field: String,
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub size: usize,
+    /// Where the file was written, when an upload directory is configured.
+    pub saved_path: Option<String>,
+}
+
+pub fn boundary(headers: &HeaderMap) -> Option<String> {
+    let content_type = headers.get("Content-Type")?.to_str().ok()?;
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"').to_string())
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
+
+/// `name="file"; filename="a.png"` -> the value of `key`
+fn disposition_param(disposition: &str, key: &str) -> Option<String> {
+    disposition.split(';').find_map(|param| {
+        let (name, value) = param.trim().split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case(key)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+pub fn parse(body: &Bytes, boundary: &str) -> Vec<Part> {
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+    let mut parts = Vec::new();
+    let Some(mut start) = find(body, delimiter, 0) else {
+        return parts;
+    };
+
+    loop {
+        let after = start + delimiter.len();
+        if body.get(after..after + 2) == Some(&b"--"[..]) {
+            break;
+        }
+        let Some(headers_end) = find(body, b"\r\n\r\n", after) else {
+            break;
+        };
+        let Some(next) = find(body, delimiter, headers_end) else {
+            break;
+        };
+
+        let headers = String::from_utf8_lossy(&body[after..headers_end]);
+        let mut disposition = None;
+        let mut content_type = None;
+        for line in headers.lines() {
+            if let Some((name, value)) = line.split_once(':') {
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "content-disposition" => disposition = Some(value.trim().to_string()),
+                    "content-type" => content_type = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+        // The part's data ends at the CRLF before the next delimiter
+        let data_end = next.saturating_sub(2).max(headers_end + 4);
+        if let Some(disposition) = disposition {
+            parts.push(Part {
+                name: disposition_param(&disposition, "name").unwrap_or_default(),
+                filename: disposition_param(&disposition, "filename"),
+                content_type,
+                data: body.slice(headers_end + 4..data_end),
+            });
+        }
+        start = next;
+    }
+    parts
+}
+
+/// Keeps the last path component and replaces anything unusual, so the
+/// client can't choose where the file lands.
+fn safe_filename(filename: &str) -> String {
+    let name = Path::new(filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+        .collect();
+    if cleaned.trim_matches('.').is_empty() {
+        "upload".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// The first `MAX_FILES` file parts of a multipart request, written to `dir`
+/// when given and while they fit in `MAX_SAVED_BYTES`.
+pub async fn capture(dir: Option<&str>, incoming: &IncomingRequest) -> Vec<UploadedFile> {
+    let Some(boundary) = boundary(&incoming.headers) else {
+        return Vec::new();
+    };
+    let mut uploads = Vec::new();
+    let mut saved_bytes = 0;
+    let files = parse(&incoming.body, &boundary)
+        .into_iter()
+        .filter(|part| part.filename.is_some())
+        .take(MAX_FILES);
+    for part in files {
+        let filename = part.filename.unwrap_or_default();
+        let fits = saved_bytes + part.data.len() <= MAX_SAVED_BYTES;
+        let saved_path = match dir.filter(|dir| fits && !dir.is_empty()) {
+            Some(dir) => {
+                saved_bytes += part.data.len();
+                let path = Path::new(dir).join(format!("{}-{}", uuid::Uuid::new_v4(), safe_filename(&filename)));
+                let written = async {
+                    tokio::fs::create_dir_all(dir).await?;
+                    tokio::fs::write(&path, &part.data).await
+                };
+                match written.await {
+                    Ok(()) => Some(path.to_string_lossy().to_string()),
+                    Err(e) => {
+                        eprintln!("Failed to save upload {}: {}", filename, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        uploads.push(UploadedFile {
+            field: part.name,
+            filename,
+            content_type: part.content_type,
+            size: part.data.len(),
+            saved_path,
+        });
+    }
+    uploads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn form(files: usize) -> Bytes {
+        let mut body = String::from("--b\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nhello\r\n");
+        for index in 0..files {
+            body.push_str(&format!(
+                "--b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\ndata {}\r\n",
+                index, index
+            ));
+        }
+        body.push_str("--b--\r\n");
+        Bytes::from(body)
+    }
+
+    #[test]
+    fn parses_fields_and_files() {
+        let parts = parse(&form(1), "b");
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "title");
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(&parts[0].data[..], b"hello");
+        assert_eq!(parts[1].filename.as_deref(), Some("0.txt"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("text/plain"));
+        assert_eq!(&parts[1].data[..], b"data 0");
+    }
+
+    #[test]
+    fn truncated_bodies_do_not_panic() {
+        let body = form(2);
+        for end in 0..body.len() {
+            parse(&body.slice(..end), "b");
+        }
+        assert!(parse(&Bytes::from_static(b"--b\r\n\r\n--b"), "b").is_empty());
+    }
+
+    #[tokio::test]
+    async fn capture_keeps_at_most_max_files() {
+        let (parts, ()) = Request::builder()
+            .method("POST")
+            .header("Content-Type", "multipart/form-data; boundary=b")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let incoming = IncomingRequest::from_parts(&parts, form(MAX_FILES + 5));
+        let uploads = capture(None, &incoming).await;
+        assert_eq!(uploads.len(), MAX_FILES);
+        assert!(uploads.iter().all(|upload| upload.saved_path.is_none()));
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::endpoints::AppState;
+
+/// One contract-violating variant of an endpoint's response body.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mutation {
+    pub description: String,
+    pub body: Value,
+}
+
+/// Mutated bodies served in order for one endpoint until exhausted.
+#[derive(Debug, Clone)]
+pub struct MutationRun {
+    pub mutations: Vec<Mutation>,
+    pub next: usize,
+}
+
+/// Derives mutations from a JSON Schema: drop each required field, give each
+/// typed property a value of the wrong type and null out non-nullable ones.
+pub fn generate_mutations(schema: &Value, body: &Value) -> Vec<Mutation> {
+    let mut mutations = Vec::new();
+    mutate_object(schema, body, &mut Vec::new(), &mut mutations, body);
+    mutations
+}
+
+fn mutate_object(schema: &Value, value: &Value, path: &mut Vec<String>, out: &mut Vec<Mutation>, root: &Value) {
+    let (Some(properties), Some(object)) = (schema.get("properties").and_then(Value::as_object), value.as_object()) else {
+        return;
+    };
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for field in required.iter().filter_map(Value::as_str) {
+            if object.contains_key(field) {
+                path.push(field.to_string());
+                out.push(Mutation {
+                    description: format!("Missing required field '{}'", path.join(".")),
+                    body: replace_at(root, path, None),
+                });
+                path.pop();
+            }
+        }
+    }
+
+    for (name, property) in properties {
+        let Some(current) = object.get(name) else {
+            continue;
+        };
+        path.push(name.clone());
+
+        let types = schema_types(property);
+        if let Some(primary) = types.iter().find(|t| t.as_str() != "null") {
+            out.push(Mutation {
+                description: format!("Wrong type for '{}' (expected {})", path.join("."), primary),
+                body: replace_at(root, path, Some(wrong_type_value(primary))),
+            });
+        }
+        if !types.is_empty() && !types.iter().any(|t| t == "null") && !current.is_null() {
+            out.push(Mutation {
+                description: format!("Null for non-nullable '{}'", path.join(".")),
+                body: replace_at(root, path, Some(Value::Null)),
+            });
+        }
+
+        mutate_object(property, current, path, out, root);
+        path.pop();
+    }
+}
+
+fn schema_types(schema: &Value) -> Vec<String> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.clone()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn wrong_type_value(expected: &str) -> Value {
+    match expected {
+        "string" => Value::from(12345),
+        "number" | "integer" => Value::from("not-a-number"),
+        "boolean" => Value::from("true"),
+        "array" => Value::Object(serde_json::Map::new()),
+        "object" => Value::Array(Vec::new()),
+        _ => Value::from(0),
+    }
+}
+
+/// Returns a copy of `root` with the field at `path` replaced, or removed when `replacement` is `None`.
+fn replace_at(root: &Value, path: &[String], replacement: Option<Value>) -> Value {
+    let mut copy = root.clone();
+    let Some((last, parents)) = path.split_last() else {
+        return copy;
+    };
+
+    let pointer: String = parents
+        .iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect();
+    if let Some(object) = copy.pointer_mut(&pointer).and_then(Value::as_object_mut) {
+        match replacement {
+            Some(value) => {
+                object.insert(last.clone(), value);
+            }
+            None => {
+                object.remove(last);
+            }
+        }
+    }
+    copy
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationTestParams {
+    id: String,
+}
+
+/// Starts serving schema-violating variants of an endpoint's response, one per request.
+#[tauri::command]
+pub async fn start_mutation_test(
+    state: tauri::State<'_, AppState>,
+    params: MutationTestParams,
+) -> Result<Vec<String>, String> {
+    let endpoints = state.endpoints.read().await;
+    let endpoint = endpoints
+        .iter()
+        .find(|e| e.id == params.id)
+        .ok_or_else(|| format!("Endpoint not found: {}", params.id))?;
+    let schema = endpoint
+        .schema
+        .as_ref()
+        .ok_or_else(|| "Endpoint has no response schema".to_string())?;
+    let body: Value = serde_json::from_str(&endpoint.response)
+        .map_err(|e| format!("Response is not valid JSON: {}", e))?;
+
+    let mutations = generate_mutations(schema, &body);
+    if mutations.is_empty() {
+        return Err("Schema does not produce any mutations for this response".to_string());
+    }
+
+    let descriptions = mutations.iter().map(|m| m.description.clone()).collect();
+    state
+        .mutation_runs
+        .write()
+        .await
+        .insert(params.id, MutationRun { mutations, next: 0 });
+    Ok(descriptions)
+}
+
+#[tauri::command]
+pub async fn stop_mutation_test(
+    state: tauri::State<'_, AppState>,
+    params: MutationTestParams,
+) -> Result<(), String> {
+    state.mutation_runs.write().await.remove(&params.id);
+    Ok(())
+}
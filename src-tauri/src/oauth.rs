@@ -0,0 +1,265 @@
+use axum::http::{header, Method, StatusCode};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::bearer::{payload, sign_hs256, verify};
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Marks refresh tokens, so an access token can't be redeemed as one.
+pub(crate) const REFRESH_TOKEN_USE: &str = "refresh";
+
+/// A token endpoint issuing HS256 JWTs; `bearer_auth` with the same secret
+/// accepts the access tokens it hands out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthProvider {
+    #[serde(default = "default_token_path", alias = "tokenPath")]
+    pub token_path: String,
+    /// HS256 key; unused in OIDC mode.
+    #[serde(default)]
+    pub secret: String,
+    /// Sign with a generated ES256 key instead, serve the OIDC discovery
+    /// document and JWKS, and add an `id_token` when the `openid` scope is
+    /// requested. `bearer_auth` with `oidc` set accepts these tokens.
+    #[serde(default)]
+    pub oidc: bool,
+    /// Lifetime of access tokens; refresh tokens live ten times longer.
+    #[serde(default = "default_ttl_secs", alias = "ttlSecs")]
+    pub ttl_secs: u64,
+    #[serde(default)]
+    pub issuer: Option<String>,
+    /// Added to every access token, e.g. `{"role": "admin"}`.
+    #[serde(default)]
+    pub claims: Map<String, Value>,
+    /// Accepted clients; any client id is accepted when empty.
+    #[serde(default)]
+    pub clients: Vec<OAuthClient>,
+    /// Accepted users for the password grant; anyone is accepted when empty.
+    #[serde(default)]
+    pub users: Vec<OAuthUser>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthClient {
+    #[serde(alias = "clientId")]
+    pub client_id: String,
+    #[serde(default, alias = "clientSecret")]
+    pub client_secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthUser {
+    pub username: String,
+    pub password: String,
+}
+
+fn default_token_path() -> String {
+    "/oauth/token".to_string()
+}
+
+/// `exp` for a token lasting `factor` times the TTL, capped rather than
+/// overflowing on huge TTLs.
+fn expires_at(now: i64, ttl_secs: u64, factor: i64) -> i64 {
+    let ttl = i64::try_from(ttl_secs).unwrap_or(i64::MAX);
+    now.saturating_add(ttl.saturating_mul(factor))
+}
+
+fn default_ttl_secs() -> u64 {
+    3600
+}
+
+/// An RFC 6749 error response.
+fn error(status: StatusCode, code: &str, description: &str) -> MockResponse {
+    let mut response = MockResponse::json(
+        status,
+        serde_json::json!({ "error": code, "error_description": description }).to_string(),
+    );
+    response.headers.push(("Cache-Control".to_string(), "no-store".to_string()));
+    response
+}
+
+fn sign(provider: &OAuthProvider, claims: &Value) -> Result<String, String> {
+    if provider.oidc {
+        Ok(crate::oidc::sign_es256(claims))
+    } else {
+        sign_hs256(claims, &provider.secret)
+    }
+}
+
+/// Form or JSON body parameters.
+fn parameters(incoming: &IncomingRequest) -> HashMap<String, String> {
+    if let Some(Value::Object(fields)) = &incoming.json_body {
+        return fields
+            .iter()
+            .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+            .collect();
+    }
+    url::form_urlencoded::parse(&incoming.body).into_owned().collect()
+}
+
+/// Client credentials from HTTP Basic auth, else from the body.
+fn client_credentials(incoming: &IncomingRequest, params: &HashMap<String, String>) -> Option<(String, String)> {
+    let basic = incoming
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Basic "))
+        .and_then(|encoded| STANDARD.decode(encoded.trim()).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|pair| pair.split_once(':').map(|(id, secret)| (id.to_string(), secret.to_string())));
+    basic.or_else(|| {
+        let id = params.get("client_id")?.clone();
+        Some((id, params.get("client_secret").cloned().unwrap_or_default()))
+    })
+}
+
+/// Answers `POST <token_path>` for the client_credentials, password and
+/// refresh_token grants.
+pub fn token(provider: &OAuthProvider, incoming: &IncomingRequest) -> MockResponse {
+    let params = parameters(incoming);
+    let Some((client_id, client_secret)) = client_credentials(incoming, &params) else {
+        return error(StatusCode::UNAUTHORIZED, "invalid_client", "Client authentication is required");
+    };
+    if !provider.clients.is_empty()
+        && !provider
+            .clients
+            .iter()
+            .any(|c| c.client_id == client_id && c.client_secret == client_secret)
+    {
+        return error(StatusCode::UNAUTHORIZED, "invalid_client", "Unknown client or wrong secret");
+    }
+
+    let mut scope = params.get("scope").cloned();
+    let (subject, refreshable) = match params.get("grant_type").map(String::as_str) {
+        Some("client_credentials") => (client_id.clone(), false),
+        Some("password") => {
+            let (Some(username), Some(password)) = (params.get("username"), params.get("password")) else {
+                return error(StatusCode::BAD_REQUEST, "invalid_request", "username and password are required");
+            };
+            if !provider.users.is_empty()
+                && !provider
+                    .users
+                    .iter()
+                    .any(|u| &u.username == username && &u.password == password)
+            {
+                return error(StatusCode::BAD_REQUEST, "invalid_grant", "Wrong username or password");
+            }
+            (username.clone(), true)
+        }
+        Some("refresh_token") => {
+            let Some(refresh_token) = params.get("refresh_token") else {
+                return error(StatusCode::BAD_REQUEST, "invalid_request", "refresh_token is required");
+            };
+            let secret = (!provider.oidc).then_some(provider.secret.as_str());
+            let claims = verify(refresh_token, secret, provider.oidc)
+                .ok()
+                .and_then(|()| payload(refresh_token))
+                .filter(|claims| claims.get("token_use").and_then(Value::as_str) == Some(REFRESH_TOKEN_USE))
+                .filter(|claims| claims.get("client_id").and_then(Value::as_str) == Some(client_id.as_str()))
+                .filter(|claims| {
+                    claims
+                        .get("exp")
+                        .and_then(Value::as_i64)
+                        .is_some_and(|exp| exp > chrono::Utc::now().timestamp())
+                });
+            let Some(claims) = claims else {
+                return error(StatusCode::BAD_REQUEST, "invalid_grant", "Refresh token is invalid or expired");
+            };
+            if scope.is_none() {
+                scope = claims.get("scope").and_then(Value::as_str).map(str::to_string);
+            }
+            let subject = claims.get("sub").and_then(Value::as_str).unwrap_or_default().to_string();
+            (subject, true)
+        }
+        Some(_) => return error(StatusCode::BAD_REQUEST, "unsupported_grant_type", "Unsupported grant_type"),
+        None => return error(StatusCode::BAD_REQUEST, "invalid_request", "grant_type is required"),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let issuer = if provider.oidc {
+        Some(crate::oidc::issuer(provider, incoming))
+    } else {
+        provider.issuer.clone()
+    };
+    let mut claims = provider.claims.clone();
+    claims.insert("sub".to_string(), Value::from(subject.clone()));
+    claims.insert("client_id".to_string(), Value::from(client_id.clone()));
+    claims.insert("iat".to_string(), Value::from(now));
+    claims.insert("exp".to_string(), Value::from(expires_at(now, provider.ttl_secs, 1)));
+    claims.insert("jti".to_string(), Value::from(uuid::Uuid::new_v4().to_string()));
+    if let Some(issuer) = &issuer {
+        claims.insert("iss".to_string(), Value::from(issuer.clone()));
+    }
+    if let Some(scope) = &scope {
+        claims.insert("scope".to_string(), Value::from(scope.clone()));
+    }
+    let access_token = match sign(provider, &Value::Object(claims)) {
+        Ok(token) => token,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", &e),
+    };
+
+    let mut body = serde_json::json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": provider.ttl_secs,
+    });
+    if let Some(scope) = &scope {
+        body["scope"] = Value::from(scope.clone());
+    }
+    let openid = scope.as_deref().is_some_and(|scope| scope.split(' ').any(|s| s == "openid"));
+    if provider.oidc && openid {
+        let mut id_claims = provider.claims.clone();
+        id_claims.insert("iss".to_string(), Value::from(issuer.clone()));
+        id_claims.insert("sub".to_string(), Value::from(subject.clone()));
+        id_claims.insert("aud".to_string(), Value::from(client_id.clone()));
+        id_claims.insert("iat".to_string(), Value::from(now));
+        id_claims.insert("exp".to_string(), Value::from(expires_at(now, provider.ttl_secs, 1)));
+        body["id_token"] = Value::from(crate::oidc::sign_es256(&Value::Object(id_claims)));
+    }
+    if refreshable {
+        let refresh_claims = serde_json::json!({
+            "sub": subject,
+            "client_id": client_id,
+            "scope": scope,
+            "token_use": REFRESH_TOKEN_USE,
+            "iat": now,
+            "exp": expires_at(now, provider.ttl_secs, 10),
+            "jti": uuid::Uuid::new_v4().to_string(),
+        });
+        match sign(provider, &refresh_claims) {
+            Ok(token) => body["refresh_token"] = Value::from(token),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", &e),
+        }
+    }
+
+    let mut response = MockResponse::json(StatusCode::OK, body.to_string());
+    response.headers.push(("Cache-Control".to_string(), "no-store".to_string()));
+    response
+}
+
+/// The provider's response when the request targets its token endpoint or,
+/// in OIDC mode, its discovery document or JWKS.
+pub fn route(provider: &OAuthProvider, incoming: &IncomingRequest) -> Option<MockResponse> {
+    match incoming.method {
+        Method::POST if incoming.path == provider.token_path => Some(token(provider, incoming)),
+        Method::GET if provider.oidc && incoming.path == crate::oidc::DISCOVERY_PATH => {
+            Some(crate::oidc::discovery(provider, incoming))
+        }
+        Method::GET if provider.oidc && incoming.path == crate::oidc::JWKS_PATH => Some(crate::oidc::jwks()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_saturates_on_huge_ttls() {
+        assert_eq!(expires_at(100, 60, 10), 700);
+        assert_eq!(expires_at(100, u64::MAX, 10), i64::MAX);
+        assert_eq!(expires_at(100, i64::MAX as u64, 1), i64::MAX);
+    }
+}
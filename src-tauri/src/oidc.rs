@@ -0,0 +1,100 @@
+use axum::http::{header, StatusCode};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+use crate::oauth::OAuthProvider;
+use crate::server::{IncomingRequest, MockResponse};
+
+pub const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+pub const JWKS_PATH: &str = "/.well-known/jwks.json";
+
+/// ES256 key generated once per run; tokens signed with it stop verifying
+/// after a restart, like a real provider rotating its keys.
+fn signing_key() -> &'static SigningKey {
+    static KEY: OnceLock<SigningKey> = OnceLock::new();
+    KEY.get_or_init(|| SigningKey::random(&mut rand::rngs::OsRng))
+}
+
+/// The public key's coordinates, base64url-encoded.
+fn coordinates() -> (String, String) {
+    let point = signing_key().verifying_key().to_encoded_point(false);
+    let encode = |c: Option<&p256::FieldBytes>| c.map(|c| URL_SAFE_NO_PAD.encode(c)).unwrap_or_default();
+    (encode(point.x()), encode(point.y()))
+}
+
+/// RFC 7638 thumbprint of the public key.
+fn key_id() -> String {
+    let (x, y) = coordinates();
+    let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#, x, y);
+    URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// A compact ES256 JWT carrying `claims`, with the generated key's `kid`.
+pub fn sign_es256(claims: &Value) -> String {
+    let header = serde_json::json!({ "alg": "ES256", "typ": "JWT", "kid": key_id() });
+    let signed = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature: Signature = signing_key().sign(signed.as_bytes());
+    format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+}
+
+/// Checks the signature of `signed` (header and payload) against the generated key.
+pub fn verify_es256(signed: &str, signature: &[u8]) -> Result<(), String> {
+    let signature = Signature::from_slice(signature).map_err(|_| "Token signature is malformed".to_string())?;
+    signing_key()
+        .verifying_key()
+        .verify(signed.as_bytes(), &signature)
+        .map_err(|_| "Token signature is invalid".to_string())
+}
+
+/// The configured issuer, else the origin the request was sent to.
+pub fn issuer(provider: &OAuthProvider, incoming: &IncomingRequest) -> String {
+    if let Some(issuer) = &provider.issuer {
+        return issuer.trim_end_matches('/').to_string();
+    }
+    let host = incoming
+        .headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = if incoming.tls.is_some() { "https" } else { "http" };
+    format!("{}://{}", scheme, host)
+}
+
+/// The OpenID Provider metadata. `authorization_endpoint` is required by the
+/// spec but not served; only the token endpoint's grants are supported.
+pub fn discovery(provider: &OAuthProvider, incoming: &IncomingRequest) -> MockResponse {
+    let issuer = issuer(provider, incoming);
+    let body = serde_json::json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/oauth/authorize", issuer),
+        "token_endpoint": format!("{}{}", issuer, provider.token_path),
+        "jwks_uri": format!("{}{}", issuer, JWKS_PATH),
+        "response_types_supported": ["code", "token", "id_token"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["ES256"],
+        "grant_types_supported": ["client_credentials", "password", "refresh_token"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post"],
+        "scopes_supported": ["openid"],
+        "claims_supported": ["sub", "iss", "aud", "exp", "iat"],
+    });
+    MockResponse::json(StatusCode::OK, body.to_string())
+}
+
+/// The JSON Web Key Set holding the generated key's public half.
+pub fn jwks() -> MockResponse {
+    let (x, y) = coordinates();
+    let body = serde_json::json!({
+        "keys": [{ "kty": "EC", "crv": "P-256", "x": x, "y": y, "kid": key_id(), "alg": "ES256", "use": "sig" }]
+    });
+    MockResponse::json(StatusCode::OK, body.to_string())
+}
@@ -0,0 +1,318 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::endpoints::{AppState, Endpoint, EndpointKind, MatchType};
+
+const METHODS: [&str; 5] = ["get", "post", "put", "delete", "patch"];
+/// Guards against self-referencing `$ref` chains.
+const MAX_DEPTH: usize = 16;
+
+/// Builds one endpoint per operation of an OpenAPI 3 document (YAML or JSON).
+///
+/// Path parameters become `*` wildcards; the body comes from the first success
+/// response's example, or a stub generated from its schema.
+pub fn endpoints_from_spec(spec: &str) -> Result<Vec<Endpoint>, String> {
+    let doc: Value = serde_yaml::from_str(spec).map_err(|e| format!("Failed to parse OpenAPI spec: {}", e))?;
+    if !doc.get("openapi").and_then(Value::as_str).is_some_and(|v| v.starts_with('3')) {
+        return Err("Only OpenAPI 3.x documents are supported".to_string());
+    }
+    let paths = doc
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or("OpenAPI spec has no paths")?;
+
+    let mut endpoints = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(item, &doc, 0);
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            endpoints.push(endpoint_for(method, &wildcard_path(path), operation, &doc));
+        }
+    }
+    Ok(endpoints)
+}
+
+fn endpoint_for(method: &str, path: &str, operation: &Value, doc: &Value) -> Endpoint {
+    let (status, response) = pick_response(operation);
+    let response = resolve(&response, doc, 0);
+    let (content_type, media) = response
+        .get("content")
+        .and_then(Value::as_object)
+        .and_then(|content| {
+            content
+                .iter()
+                .find(|(content_type, _)| content_type.as_str() == "application/json")
+                .or_else(|| content.iter().next())
+        })
+        .map(|(content_type, media)| (content_type.clone(), media.clone()))
+        .unwrap_or_else(|| ("application/json".to_string(), Value::Null));
+
+    let schema = media.get("schema").map(|schema| resolve(schema, doc, 0));
+    let example = media
+        .get("example")
+        .cloned()
+        .or_else(|| {
+            media
+                .get("examples")
+                .and_then(Value::as_object)
+                .and_then(|examples| examples.values().next())
+                .map(|example| resolve(example, doc, 0))
+                .and_then(|example| example.get("value").cloned())
+        })
+        .or_else(|| schema.as_ref().map(|schema| stub(schema, 0)));
+
+    let body = match example {
+        Some(Value::String(text)) if !content_type.contains("json") => text,
+        Some(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+        None => String::new(),
+    };
+
+    let mut endpoint = Endpoint::new(method.to_uppercase(), path.to_string(), status, 0, body);
+    endpoint.content_type = content_type;
+    endpoint.schema = schema;
+    endpoint
+}
+
+/// The first 2xx response, then `default`, then whatever comes first.
+fn pick_response(operation: &Value) -> (u16, Value) {
+    let Some(responses) = operation.get("responses").and_then(Value::as_object) else {
+        return (200, Value::Null);
+    };
+    let mut codes: Vec<(&String, &Value)> = responses.iter().collect();
+    codes.sort_by(|a, b| a.0.cmp(b.0));
+
+    codes
+        .iter()
+        .find(|(code, _)| code.starts_with('2'))
+        .or_else(|| codes.iter().find(|(code, _)| code.as_str() == "default"))
+        .or_else(|| codes.first())
+        .map(|(code, response)| (code.parse().unwrap_or(200), (*response).clone()))
+        .unwrap_or((200, Value::Null))
+}
+
+/// `/users/{id}` -> `/users/*`
+fn wildcard_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "*" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Inlines local `#/...` references.
+fn resolve(value: &Value, doc: &Value, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return json!({});
+    }
+    match value {
+        Value::Object(map) => {
+            if let Some(target) = map.get("$ref").and_then(Value::as_str) {
+                return target
+                    .strip_prefix('#')
+                    .and_then(|pointer| doc.pointer(pointer))
+                    .map(|found| resolve(found, doc, depth + 1))
+                    .unwrap_or_else(|| json!({}));
+            }
+            Value::Object(
+                map.iter()
+                    .map(|(key, child)| (key.clone(), resolve(child, doc, depth + 1)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| resolve(item, doc, depth + 1)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Placeholder value shaped like `schema`.
+fn stub(schema: &Value, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    if let Some(value) = schema
+        .get("example")
+        .or_else(|| schema.get("default"))
+        .or_else(|| schema.get("enum").and_then(|values| values.get(0)))
+    {
+        return value.clone();
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = serde_json::Map::new();
+        for part in all {
+            if let Value::Object(fields) = stub(part, depth + 1) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+    if let Some(first) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(*key).and_then(|options| options.get(0)))
+    {
+        return stub(first, depth + 1);
+    }
+
+    let kind = match schema.get("type") {
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null"),
+        Some(kind) => kind.as_str(),
+        None if schema.get("properties").is_some() => Some("object"),
+        None => None,
+    };
+    match kind {
+        Some("object") => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|properties| {
+                    properties
+                        .iter()
+                        .map(|(name, property)| (name.clone(), stub(property, depth + 1)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        Some("array") => Value::Array(
+            schema
+                .get("items")
+                .map(|items| vec![stub(items, depth + 1)])
+                .unwrap_or_default(),
+        ),
+        Some("string") => Value::from(match schema.get("format").and_then(Value::as_str) {
+            Some("date-time") => "2024-01-01T00:00:00Z",
+            Some("date") => "2024-01-01",
+            Some("email") => "user@example.com",
+            Some("uuid") => "00000000-0000-0000-0000-000000000000",
+            Some("uri") | Some("url") => "https://example.com",
+            _ => "string",
+        }),
+        Some("integer") => Value::from(0),
+        Some("number") => Value::from(0.0),
+        Some("boolean") => Value::from(true),
+        _ => Value::Null,
+    }
+}
+
+/// Describes the enabled endpoints as an OpenAPI 3 document.
+///
+/// `*` / `**` wildcards become path parameters and endpoints sharing a method
+/// and path contribute one response per status code. Regex paths can't be
+/// expressed in OpenAPI and are left out.
+pub fn spec_from_endpoints(title: &str, endpoints: &[Endpoint]) -> Value {
+    let mut paths = serde_json::Map::new();
+    for endpoint in endpoints
+        .iter()
+        .filter(|e| e.enabled && e.match_type == MatchType::Exact && e.path.starts_with('/'))
+    {
+        let (path, parameters) = openapi_path(&endpoint.path);
+        let methods: Vec<String> = match endpoint.kind {
+            EndpointKind::Inbox => METHODS.iter().map(|m| m.to_string()).collect(),
+            EndpointKind::Resource => vec!["get".to_string(), "post".to_string()],
+            EndpointKind::WebSocket | EndpointKind::Static => vec!["get".to_string()],
+            EndpointKind::Mock => vec![endpoint.method.to_lowercase()],
+        };
+
+        let item = paths
+            .entry(path)
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("path items are objects");
+        for method in methods.into_iter().filter(|m| METHODS.contains(&m.as_str())) {
+            let operation = item.entry(method).or_insert_with(|| {
+                json!({
+                    "parameters": parameters.clone(),
+                    "responses": {},
+                })
+            });
+            if let Some(responses) = operation.get_mut("responses").and_then(Value::as_object_mut) {
+                responses
+                    .entry(endpoint.status.to_string())
+                    .or_insert_with(|| response_object(endpoint));
+            }
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": title, "version": "1.0.0" },
+        "paths": paths,
+    })
+}
+
+/// `/files/**` -> `/files/{path}`, `/users/*` -> `/users/{param1}`
+fn openapi_path(path: &str) -> (String, Vec<Value>) {
+    let mut parameters = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            let name = match segment {
+                "**" => "path".to_string(),
+                "*" => format!("param{}", parameters.len() + 1),
+                _ => return segment.to_string(),
+            };
+            parameters.push(json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            }));
+            format!("{{{}}}", name)
+        })
+        .collect();
+    (segments.join("/"), parameters)
+}
+
+fn response_object(endpoint: &Endpoint) -> Value {
+    let example = match serde_json::from_str::<Value>(&endpoint.response) {
+        Ok(value) if endpoint.content_type.contains("json") => value,
+        _ => Value::String(endpoint.response.clone()),
+    };
+    let mut media = json!({ "example": example });
+    if let Some(schema) = &endpoint.schema {
+        media["schema"] = schema.clone();
+    }
+    json!({
+        "description": format!("{} {}", endpoint.method, endpoint.path),
+        "content": { (endpoint.content_type.clone()): media },
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOpenApiParams {
+    title: Option<String>,
+    /// `yaml` or `json` (default).
+    format: Option<String>,
+}
+
+/// Renders the live endpoints as an OpenAPI 3 document in YAML or JSON.
+#[tauri::command]
+pub async fn export_openapi(
+    state: tauri::State<'_, AppState>,
+    params: ExportOpenApiParams,
+) -> Result<String, String> {
+    let title = params.title.unwrap_or_else(|| "Mock API".to_string());
+    let spec = spec_from_endpoints(&title, &state.endpoints.read().await);
+    match params.format.as_deref() {
+        Some("yaml") => serde_yaml::to_string(&spec).map_err(|e| format!("Failed to export OpenAPI: {}", e)),
+        _ => serde_json::to_string_pretty(&spec).map_err(|e| format!("Failed to export OpenAPI: {}", e)),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOpenApiParams {
+    spec: String,
+}
+
+/// Adds one endpoint per operation in the spec and returns the new endpoints.
+#[tauri::command]
+pub async fn import_openapi(
+    state: tauri::State<'_, AppState>,
+    params: ImportOpenApiParams,
+) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let imported = endpoints_from_spec(&params.spec)?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
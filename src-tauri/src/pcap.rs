@@ -0,0 +1,199 @@
+use axum::http::request::Parts;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::server::{MockBody, MockResponse};
+
+/// Raw IPv4 packets, no link-layer header.
+const LINKTYPE_RAW: u32 = 101;
+const MAX_SEGMENT: usize = 1460;
+const LOOPBACK: [u8; 4] = [127, 0, 0, 1];
+const FIRST_CLIENT_PORT: u16 = 49152;
+
+const TCP_FIN: u8 = 0x01;
+const TCP_SYN: u8 = 0x02;
+const TCP_PSH: u8 = 0x08;
+const TCP_ACK: u8 = 0x10;
+
+/// Writes mock traffic to a classic PCAP file, synthesizing one TCP
+/// connection per request/response exchange so Wireshark can follow it.
+pub struct PcapWriter {
+    out: BufWriter<File>,
+    server_port: u16,
+    next_client_port: u16,
+}
+
+struct Segment<'a> {
+    from_server: bool,
+    client_port: u16,
+    seq: u32,
+    ack: u32,
+    flags: u8,
+    payload: &'a [u8],
+}
+
+impl PcapWriter {
+    pub fn create(path: &str, server_port: u16) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create capture file {}: {}", path, e))?;
+        let mut out = BufWriter::new(file);
+
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&4u16.to_le_bytes());
+        header.extend_from_slice(&0i32.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&65535u32.to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        out.write_all(&header)
+            .and_then(|_| out.flush())
+            .map_err(|e| format!("Failed to write capture header: {}", e))?;
+
+        Ok(Self {
+            out,
+            server_port,
+            next_client_port: FIRST_CLIENT_PORT,
+        })
+    }
+
+    /// Writes one exchange as handshake, request, response and teardown.
+    pub fn write_exchange(&mut self, request: &[u8], response: &[u8]) -> std::io::Result<()> {
+        let client_port = self.next_client_port;
+        self.next_client_port = client_port.checked_add(1).unwrap_or(FIRST_CLIENT_PORT);
+
+        let mut client_seq: u32 = 1;
+        let mut server_seq: u32 = 1;
+
+        self.write_segment(Segment { from_server: false, client_port, seq: client_seq, ack: 0, flags: TCP_SYN, payload: &[] })?;
+        client_seq = client_seq.wrapping_add(1);
+        self.write_segment(Segment { from_server: true, client_port, seq: server_seq, ack: client_seq, flags: TCP_SYN | TCP_ACK, payload: &[] })?;
+        server_seq = server_seq.wrapping_add(1);
+        self.write_segment(Segment { from_server: false, client_port, seq: client_seq, ack: server_seq, flags: TCP_ACK, payload: &[] })?;
+
+        for chunk in request.chunks(MAX_SEGMENT) {
+            self.write_segment(Segment { from_server: false, client_port, seq: client_seq, ack: server_seq, flags: TCP_PSH | TCP_ACK, payload: chunk })?;
+            client_seq = client_seq.wrapping_add(chunk.len() as u32);
+        }
+        for chunk in response.chunks(MAX_SEGMENT) {
+            self.write_segment(Segment { from_server: true, client_port, seq: server_seq, ack: client_seq, flags: TCP_PSH | TCP_ACK, payload: chunk })?;
+            server_seq = server_seq.wrapping_add(chunk.len() as u32);
+        }
+
+        self.write_segment(Segment { from_server: true, client_port, seq: server_seq, ack: client_seq, flags: TCP_FIN | TCP_ACK, payload: &[] })?;
+        server_seq = server_seq.wrapping_add(1);
+        self.write_segment(Segment { from_server: false, client_port, seq: client_seq, ack: server_seq, flags: TCP_FIN | TCP_ACK, payload: &[] })?;
+        client_seq = client_seq.wrapping_add(1);
+        self.write_segment(Segment { from_server: true, client_port, seq: server_seq, ack: client_seq, flags: TCP_ACK, payload: &[] })?;
+
+        self.out.flush()
+    }
+
+    fn write_segment(&mut self, segment: Segment) -> std::io::Result<()> {
+        let (src_port, dst_port) = if segment.from_server {
+            (self.server_port, segment.client_port)
+        } else {
+            (segment.client_port, self.server_port)
+        };
+
+        let mut tcp = Vec::with_capacity(20 + segment.payload.len());
+        tcp.extend_from_slice(&src_port.to_be_bytes());
+        tcp.extend_from_slice(&dst_port.to_be_bytes());
+        tcp.extend_from_slice(&segment.seq.to_be_bytes());
+        tcp.extend_from_slice(&segment.ack.to_be_bytes());
+        tcp.push(5 << 4);
+        tcp.push(segment.flags);
+        tcp.extend_from_slice(&65535u16.to_be_bytes());
+        tcp.extend_from_slice(&[0, 0, 0, 0]);
+        tcp.extend_from_slice(segment.payload);
+
+        let mut pseudo = Vec::with_capacity(12 + tcp.len());
+        pseudo.extend_from_slice(&LOOPBACK);
+        pseudo.extend_from_slice(&LOOPBACK);
+        pseudo.extend_from_slice(&[0, 6]);
+        pseudo.extend_from_slice(&(tcp.len() as u16).to_be_bytes());
+        pseudo.extend_from_slice(&tcp);
+        let tcp_checksum = checksum(&pseudo);
+        tcp[16..18].copy_from_slice(&tcp_checksum.to_be_bytes());
+
+        let total_len = (20 + tcp.len()) as u16;
+        let mut ip = Vec::with_capacity(total_len as usize);
+        ip.extend_from_slice(&[0x45, 0]);
+        ip.extend_from_slice(&total_len.to_be_bytes());
+        ip.extend_from_slice(&[0, 0, 0x40, 0, 64, 6, 0, 0]);
+        ip.extend_from_slice(&LOOPBACK);
+        ip.extend_from_slice(&LOOPBACK);
+        let ip_checksum = checksum(&ip);
+        ip[10..12].copy_from_slice(&ip_checksum.to_be_bytes());
+        ip.extend_from_slice(&tcp);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut record = Vec::with_capacity(16);
+        record.extend_from_slice(&(now.as_secs() as u32).to_le_bytes());
+        record.extend_from_slice(&now.subsec_micros().to_le_bytes());
+        record.extend_from_slice(&(ip.len() as u32).to_le_bytes());
+        record.extend_from_slice(&(ip.len() as u32).to_le_bytes());
+
+        self.out.write_all(&record)?;
+        self.out.write_all(&ip)
+    }
+}
+
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = 0;
+    for chunk in data.chunks(2) {
+        let word = match chunk {
+            [hi, lo] => u16::from_be_bytes([*hi, *lo]),
+            [hi] => u16::from_be_bytes([*hi, 0]),
+            _ => 0,
+        };
+        sum += word as u32;
+    }
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Reconstructs the HTTP/1.1 wire form of a request for capture.
+pub fn serialize_request(parts: &Parts, body: &[u8]) -> Vec<u8> {
+    let target = parts.uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    let mut out = format!("{} {} HTTP/1.1\r\n", parts.method, target).into_bytes();
+    for (name, value) in &parts.headers {
+        out.extend_from_slice(name.as_str().as_bytes());
+        out.extend_from_slice(b": ");
+        out.extend_from_slice(value.as_bytes());
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b"\r\n");
+    out.extend_from_slice(body);
+    out
+}
+
+/// Reconstructs the HTTP/1.1 wire form of a mock response for capture.
+pub fn serialize_response(response: &MockResponse) -> Vec<u8> {
+    let mut out = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status.as_u16(),
+        response.status.canonical_reason().unwrap_or("")
+    )
+    .into_bytes();
+    for (name, value) in &response.headers {
+        out.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+    }
+    // File bodies are streamed straight to the client and not re-read for capture
+    let placeholder;
+    let body: &[u8] = match &response.body {
+        MockBody::Bytes(bytes) => &bytes[..],
+        MockBody::File(path) => {
+            placeholder = format!("[file body: {}]", path);
+            placeholder.as_bytes()
+        }
+        MockBody::Events(_) => b"[event stream]",
+        MockBody::Grpc(message) | MockBody::Reset(message) => &message[..],
+    };
+    out.extend_from_slice(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes());
+    out.extend_from_slice(body);
+    out
+}
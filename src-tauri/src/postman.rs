@@ -0,0 +1,234 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::endpoints::{AppState, Endpoint, EndpointKind, MatchType};
+
+const COLLECTION_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Response headers that describe the original transfer rather than the mock.
+const SKIPPED_HEADERS: [&str; 4] = ["content-length", "transfer-encoding", "date", "connection"];
+
+/// Converts a Postman v2.1 collection into endpoints, walking nested folders.
+///
+/// Each saved example response becomes an endpoint; when a request has several
+/// examples only the first is enabled. Requests without examples get an empty
+/// `200` response. `:param` and `{{var}}` path segments become `*` wildcards.
+pub fn endpoints_from_collection(collection: &str) -> Result<Vec<Endpoint>, String> {
+    let doc: Value =
+        serde_json::from_str(collection).map_err(|e| format!("Failed to parse Postman collection: {}", e))?;
+    let items = doc
+        .get("item")
+        .and_then(Value::as_array)
+        .ok_or("Postman collection has no items")?;
+
+    let mut endpoints = Vec::new();
+    collect_items(items, &mut endpoints);
+    Ok(endpoints)
+}
+
+fn collect_items(items: &[Value], out: &mut Vec<Endpoint>) {
+    for item in items {
+        if let Some(children) = item.get("item").and_then(Value::as_array) {
+            collect_items(children, out);
+            continue;
+        }
+        let Some(request) = item.get("request") else {
+            continue;
+        };
+
+        let method = match request {
+            Value::String(_) => "GET".to_string(),
+            _ => request
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or("GET")
+                .to_uppercase(),
+        };
+        let path = request_path(request.get("url").unwrap_or(request));
+
+        let examples = item.get("response").and_then(Value::as_array).cloned().unwrap_or_default();
+        if examples.is_empty() {
+            out.push(Endpoint::new(method, path, 200, 0, String::new()));
+            continue;
+        }
+        for (index, example) in examples.iter().enumerate() {
+            let mut endpoint = endpoint_from_example(&method, &path, example);
+            endpoint.enabled = index == 0;
+            out.push(endpoint);
+        }
+    }
+}
+
+fn endpoint_from_example(method: &str, path: &str, example: &Value) -> Endpoint {
+    let status = example
+        .get("code")
+        .and_then(Value::as_u64)
+        .and_then(|code| u16::try_from(code).ok())
+        .unwrap_or(200);
+    let body = example.get("body").and_then(Value::as_str).unwrap_or_default().to_string();
+    let mut endpoint = Endpoint::new(method.to_string(), path.to_string(), status, 0, body);
+
+    for header in example.get("header").and_then(Value::as_array).into_iter().flatten() {
+        let (Some(name), Some(value)) = (
+            header.get("key").and_then(Value::as_str),
+            header.get("value").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("content-type") {
+            endpoint.content_type = value.to_string();
+        } else if !SKIPPED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+            endpoint.headers.push((name.to_string(), value.to_string()));
+        }
+    }
+    endpoint
+}
+
+/// `url` is either a raw string or an object with a `path` array (or `raw`).
+fn request_path(url: &Value) -> String {
+    let segments: Vec<String> = match url.get("path").and_then(Value::as_array) {
+        Some(path) => path.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        None => {
+            let raw = url.as_str().or_else(|| url.get("raw").and_then(Value::as_str)).unwrap_or("/");
+            let raw = raw.split(['?', '#']).next().unwrap_or_default();
+            let without_host = match raw.find("://") {
+                Some(scheme_end) => &raw[scheme_end + 3..],
+                None => raw,
+            };
+            let path = if without_host.starts_with('/') {
+                without_host
+            } else {
+                without_host.find('/').map(|start| &without_host[start..]).unwrap_or("")
+            };
+            path.split('/').map(str::to_string).collect()
+        }
+    };
+
+    let path = segments
+        .iter()
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if segment.starts_with(':') || (segment.starts_with("{{") && segment.ends_with("}}")) {
+                "*"
+            } else {
+                segment.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("/{}", path)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPostmanParams {
+    collection: String,
+}
+
+/// Adds the collection's requests as endpoints and returns the new endpoints.
+#[tauri::command]
+pub async fn import_postman(
+    state: tauri::State<'_, AppState>,
+    params: ImportPostmanParams,
+) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let imported = endpoints_from_collection(&params.collection)?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
+
+/// Builds a v2.1 collection with one request per enabled endpoint and the mock
+/// response attached as its example. Requests use a `{{baseUrl}}` variable and
+/// wildcard segments become `:param` path variables; regex paths are left out.
+pub fn collection_from_endpoints(name: &str, base_url: &str, endpoints: &[Endpoint]) -> Value {
+    let items: Vec<Value> = endpoints
+        .iter()
+        .filter(|e| e.enabled && e.match_type == MatchType::Exact)
+        .map(|endpoint| {
+            let method = match endpoint.kind {
+                EndpointKind::Inbox => "POST".to_string(),
+                EndpointKind::Resource | EndpointKind::WebSocket | EndpointKind::Static => "GET".to_string(),
+                EndpointKind::Mock => endpoint.method.to_uppercase(),
+            };
+            let request = request_object(&method, &endpoint.path);
+
+            let mut headers = vec![json!({ "key": "Content-Type", "value": endpoint.content_type })];
+            headers.extend(
+                endpoint
+                    .headers
+                    .iter()
+                    .map(|(name, value)| json!({ "key": name, "value": value })),
+            );
+            let status = axum::http::StatusCode::from_u16(endpoint.status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or("");
+            let preview = if endpoint.content_type.contains("json") { "json" } else { "text" };
+
+            json!({
+                "name": format!("{} {}", method, endpoint.path),
+                "request": request,
+                "response": [{
+                    "name": "Mock response",
+                    "originalRequest": request,
+                    "status": status,
+                    "code": endpoint.status,
+                    "header": headers,
+                    "body": endpoint.response,
+                    "_postman_previewlanguage": preview,
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "info": {
+            "_postman_id": uuid::Uuid::new_v4().to_string(),
+            "name": name,
+            "schema": COLLECTION_SCHEMA,
+        },
+        "item": items,
+        "variable": [{ "key": "baseUrl", "value": base_url }],
+    })
+}
+
+fn request_object(method: &str, path: &str) -> Value {
+    let mut param = 0;
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment {
+            "**" => ":path".to_string(),
+            "*" => {
+                param += 1;
+                format!(":param{}", param)
+            }
+            _ => segment.to_string(),
+        })
+        .collect();
+
+    json!({
+        "method": method,
+        "header": [],
+        "url": {
+            "raw": format!("{{{{baseUrl}}}}/{}", segments.join("/")),
+            "host": ["{{baseUrl}}"],
+            "path": segments,
+        },
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPostmanParams {
+    name: Option<String>,
+}
+
+/// Renders the live endpoints as a Postman v2.1 collection JSON string.
+#[tauri::command]
+pub async fn export_postman(state: tauri::State<'_, AppState>, params: ExportPostmanParams) -> Result<String, String> {
+    let name = params.name.unwrap_or_else(|| "Mock API".to_string());
+    let base_url = crate::snippets::current_base_url(&state).await;
+    let collection = collection_from_endpoints(&name, &base_url, &state.endpoints.read().await);
+    serde_json::to_string_pretty(&collection).map_err(|e| format!("Failed to export Postman collection: {}", e))
+}
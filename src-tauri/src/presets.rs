@@ -0,0 +1,197 @@
+use serde::Serialize;
+
+use crate::endpoints::{AppState, Endpoint, EndpointKind};
+
+/// A bundle of endpoints for a commonly mocked third-party service.
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    endpoints: fn() -> Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetInfo {
+    pub name: String,
+    pub description: String,
+    pub endpoints: usize,
+}
+
+pub fn builtin() -> Vec<Preset> {
+    vec![
+        Preset {
+            name: "stripe-webhooks",
+            description: "Stripe webhook endpoint management, events and a receiver for webhook deliveries",
+            endpoints: stripe_webhooks,
+        },
+        Preset {
+            name: "github",
+            description: "GitHub REST API basics: the authenticated user, repositories and issues",
+            endpoints: github,
+        },
+        Preset {
+            name: "slack-webhooks",
+            description: "Slack incoming webhooks and chat.postMessage",
+            endpoints: slack_webhooks,
+        },
+        Preset {
+            name: "oauth",
+            description: "OAuth 2.0 token, revocation and userinfo endpoints",
+            endpoints: oauth,
+        },
+    ]
+}
+
+/// Fresh endpoints (new ids) for the preset, tagged with its name.
+pub fn endpoints_for(name: &str) -> Option<Vec<Endpoint>> {
+    let preset = builtin().into_iter().find(|preset| preset.name == name)?;
+    let mut endpoints = (preset.endpoints)();
+    for endpoint in &mut endpoints {
+        endpoint.tags.push(preset.name.to_string());
+    }
+    Some(endpoints)
+}
+
+fn endpoint(method: &str, path: &str, status: u16, response: serde_json::Value) -> Endpoint {
+    Endpoint::new(method.to_string(), path.to_string(), status, 0, response.to_string())
+}
+
+fn stripe_webhooks() -> Vec<Endpoint> {
+    let webhook_endpoint = serde_json::json!({
+        "id": "we_1Mock000000000000000000",
+        "object": "webhook_endpoint",
+        "url": "https://example.com/stripe/webhook",
+        "enabled_events": ["payment_intent.succeeded", "charge.failed"],
+        "status": "enabled",
+        "livemode": false,
+        "secret": "whsec_mock"
+    });
+    let mut receiver = endpoint("POST", "/stripe/webhook", 200, serde_json::json!({ "received": true }));
+    receiver.kind = EndpointKind::Inbox;
+
+    vec![
+        endpoint("POST", "/v1/webhook_endpoints", 200, webhook_endpoint.clone()),
+        endpoint(
+            "GET",
+            "/v1/webhook_endpoints",
+            200,
+            serde_json::json!({ "object": "list", "url": "/v1/webhook_endpoints", "has_more": false, "data": [webhook_endpoint] }),
+        ),
+        endpoint(
+            "GET",
+            "/v1/events/*",
+            200,
+            serde_json::json!({
+                "id": "evt_1Mock000000000000000000",
+                "object": "event",
+                "type": "payment_intent.succeeded",
+                "api_version": "2023-10-16",
+                "livemode": false,
+                "data": { "object": { "id": "pi_1Mock000000000000000000", "object": "payment_intent", "amount": 2000, "currency": "usd", "status": "succeeded" } }
+            }),
+        ),
+        receiver,
+    ]
+}
+
+fn github() -> Vec<Endpoint> {
+    let repo = serde_json::json!({
+        "id": 1296269,
+        "name": "hello-world",
+        "full_name": "octocat/hello-world",
+        "private": false,
+        "owner": { "login": "octocat", "id": 1 },
+        "default_branch": "main",
+        "html_url": "https://github.com/octocat/hello-world"
+    });
+    let issue = serde_json::json!({
+        "id": 1,
+        "number": 1347,
+        "title": "Found a bug",
+        "state": "open",
+        "user": { "login": "octocat", "id": 1 },
+        "labels": [],
+        "comments": 0
+    });
+
+    vec![
+        endpoint(
+            "GET",
+            "/user",
+            200,
+            serde_json::json!({ "login": "octocat", "id": 1, "name": "The Octocat", "type": "User", "public_repos": 8 }),
+        ),
+        endpoint("GET", "/user/repos", 200, serde_json::json!([repo.clone()])),
+        endpoint("GET", "/repos/*/*", 200, repo),
+        endpoint("GET", "/repos/*/*/issues", 200, serde_json::json!([issue.clone()])),
+        endpoint("POST", "/repos/*/*/issues", 201, issue),
+    ]
+}
+
+fn slack_webhooks() -> Vec<Endpoint> {
+    let mut incoming_webhook = Endpoint::new("POST".to_string(), "/services/**".to_string(), 200, 0, "ok".to_string());
+    incoming_webhook.content_type = "text/plain".to_string();
+    incoming_webhook.kind = EndpointKind::Inbox;
+
+    vec![
+        incoming_webhook,
+        endpoint(
+            "POST",
+            "/api/chat.postMessage",
+            200,
+            serde_json::json!({
+                "ok": true,
+                "channel": "C1234567890",
+                "ts": "1503435956.000247",
+                "message": { "type": "message", "text": "Hello world", "ts": "1503435956.000247" }
+            }),
+        ),
+    ]
+}
+
+fn oauth() -> Vec<Endpoint> {
+    let mut token = endpoint(
+        "POST",
+        "/oauth/token",
+        200,
+        serde_json::json!({
+            "access_token": "mock-access-token",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "refresh_token": "mock-refresh-token",
+            "scope": "read write"
+        }),
+    );
+    token.headers.push(("Cache-Control".to_string(), "no-store".to_string()));
+
+    vec![
+        token,
+        endpoint("POST", "/oauth/revoke", 200, serde_json::json!({})),
+        endpoint(
+            "GET",
+            "/userinfo",
+            200,
+            serde_json::json!({ "sub": "1234567890", "name": "Jane Doe", "email": "jane@example.com", "email_verified": true }),
+        ),
+    ]
+}
+
+#[tauri::command]
+pub async fn list_presets() -> Result<Vec<PresetInfo>, String> {
+    Ok(builtin()
+        .into_iter()
+        .map(|preset| PresetInfo {
+            name: preset.name.to_string(),
+            description: preset.description.to_string(),
+            endpoints: (preset.endpoints)().len(),
+        })
+        .collect())
+}
+
+/// Adds the preset's endpoints to the project and returns them.
+#[tauri::command]
+pub async fn import_preset(state: tauri::State<'_, AppState>, name: String) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let imported = endpoints_for(&name).ok_or_else(|| format!("Preset not found: {}", name))?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
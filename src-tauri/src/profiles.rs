@@ -0,0 +1,143 @@
+use axum::http::StatusCode;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::{AppState, Endpoint, ServerSettings};
+use crate::server::MockResponse;
+
+/// A named bundle of latency, failure and bandwidth settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Extra latency, drawn uniformly from `latency_min_ms..=latency_max_ms`.
+    #[serde(default, alias = "latencyMinMs")]
+    pub latency_min_ms: u64,
+    #[serde(default, alias = "latencyMaxMs")]
+    pub latency_max_ms: u64,
+    /// Fraction of requests (0.0 to 1.0) answered with `error_status` instead.
+    #[serde(default, alias = "errorRate")]
+    pub error_rate: f64,
+    #[serde(default = "default_error_status", alias = "errorStatus")]
+    pub error_status: u16,
+    #[serde(default, alias = "bytesPerSec")]
+    pub bytes_per_sec: Option<u64>,
+}
+
+fn default_error_status() -> u16 {
+    503
+}
+
+impl Profile {
+    fn new(name: &str, latency_ms: (u64, u64), error_rate: f64, error_status: u16, bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            name: name.to_string(),
+            latency_min_ms: latency_ms.0,
+            latency_max_ms: latency_ms.1,
+            error_rate,
+            error_status,
+            bytes_per_sec,
+        }
+    }
+
+    /// Extra delay for one request and whether it should fail.
+    pub fn roll(&self) -> (u64, bool) {
+        let mut rng = rand::thread_rng();
+        let delay = if self.latency_min_ms < self.latency_max_ms {
+            rng.gen_range(self.latency_min_ms..=self.latency_max_ms)
+        } else {
+            self.latency_min_ms
+        };
+        (delay, self.error_rate > 0.0 && rng.gen_bool(self.error_rate.min(1.0)))
+    }
+
+    pub fn failure_response(&self) -> MockResponse {
+        MockResponse::json(
+            StatusCode::from_u16(self.error_status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE),
+            serde_json::json!({ "error": format!("Simulated failure (profile '{}')", self.name) }).to_string(),
+        )
+    }
+}
+
+pub fn builtin() -> Vec<Profile> {
+    vec![
+        Profile::new("perfect", (0, 0), 0.0, 503, None),
+        Profile::new("flaky-3g", (300, 1500), 0.1, 503, Some(50_000)),
+        Profile::new("degraded-upstream", (800, 3000), 0.25, 502, None),
+    ]
+}
+
+/// Custom profiles shadow built-in ones with the same name.
+pub(crate) fn find(settings: &ServerSettings, name: &str) -> Option<Profile> {
+    settings
+        .profiles
+        .iter()
+        .cloned()
+        .chain(builtin())
+        .find(|profile| profile.name == name)
+}
+
+/// The endpoint's own profile, else the first of its tags with a profile, else the global one.
+pub fn resolve(settings: &ServerSettings, endpoint: &Endpoint) -> Option<Profile> {
+    endpoint
+        .profile
+        .as_ref()
+        .or_else(|| endpoint.tags.iter().find_map(|tag| settings.tag_profiles.get(tag)))
+        .or(settings.profile.as_ref())
+        .and_then(|name| find(settings, name))
+}
+
+#[tauri::command]
+pub async fn list_profiles(state: tauri::State<'_, AppState>) -> Result<Vec<Profile>, String> {
+    let settings = state.server_settings.read().await;
+    let mut profiles = settings.profiles.clone();
+    profiles.extend(builtin().into_iter().filter(|b| !settings.profiles.iter().any(|p| p.name == b.name)));
+    Ok(profiles)
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "scope", rename_all = "lowercase")]
+pub enum ProfileTarget {
+    Global,
+    Endpoint { id: String },
+    Tag { name: String },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyProfileParams {
+    /// `None` removes the profile from the target.
+    profile: Option<String>,
+    target: ProfileTarget,
+}
+
+/// Assigns a profile globally, to one endpoint or to every endpoint with a tag.
+#[tauri::command]
+pub async fn apply_profile(state: tauri::State<'_, AppState>, params: ApplyProfileParams) -> Result<(), String> {
+    if let Some(name) = &params.profile {
+        if find(&*state.server_settings.read().await, name).is_none() {
+            return Err(format!("Unknown profile: {}", name));
+        }
+    }
+
+    match params.target {
+        ProfileTarget::Global => state.server_settings.write().await.profile = params.profile,
+        ProfileTarget::Endpoint { id } => {
+            state.ensure_writable().await?;
+            let mut endpoints = state.endpoints.write().await;
+            let endpoint = endpoints
+                .iter_mut()
+                .find(|e| e.id == id)
+                .ok_or_else(|| format!("Endpoint not found: {}", id))?;
+            endpoint.ensure_unlocked()?;
+            endpoint.profile = params.profile;
+        }
+        ProfileTarget::Tag { name } => {
+            let mut settings = state.server_settings.write().await;
+            match params.profile {
+                Some(profile) => settings.tag_profiles.insert(name, profile),
+                None => settings.tag_profiles.remove(&name),
+            };
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use axum::http::{header, HeaderName, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::{IncomingRequest, MockBody, MockResponse};
+
+/// Headers that only describe a single hop and must not be forwarded.
+const HOP_BY_HOP: [HeaderName; 7] = [
+    header::CONNECTION,
+    header::HOST,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+    header::TE,
+    header::PROXY_AUTHORIZATION,
+];
+
+/// Forwards requests that match no endpoint to a real upstream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxySettings {
+    #[serde(alias = "upstreamUrl")]
+    pub upstream_url: String,
+    /// Save every proxied response as a new endpoint.
+    #[serde(default)]
+    pub record: bool,
+}
+
+pub async fn forward(
+    client: &reqwest::Client,
+    upstream_url: &str,
+    incoming: &IncomingRequest,
+) -> Result<MockResponse, String> {
+    let mut url = format!("{}{}", upstream_url.trim_end_matches('/'), incoming.path);
+    if !incoming.query.is_empty() {
+        url.push('?');
+        url.push_str(
+            &url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&incoming.query)
+                .finish(),
+        );
+    }
+
+    let mut request = client.request(incoming.method.clone(), &url);
+    for (name, value) in incoming.headers.iter().filter(|(name, _)| !HOP_BY_HOP.contains(*name)) {
+        if *name != header::ACCEPT_ENCODING {
+            request = request.header(name, value);
+        }
+    }
+    // Recorded bodies are stored as text, so ask for them uncompressed
+    let upstream = request
+        .header(header::ACCEPT_ENCODING, "identity")
+        .body(incoming.body.clone())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach upstream {}: {}", url, e))?;
+
+    let status = StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let headers = upstream
+        .headers()
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP.contains(*name))
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+        .collect();
+    let body = upstream
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read upstream response: {}", e))?;
+
+    Ok(MockResponse {
+        status,
+        headers,
+        body: MockBody::Bytes(body),
+        bytes_per_sec: None,
+        endpoint_id: None,
+        capture: true,
+        record_uploads: false,
+        suggestions: Vec::new(),
+    })
+}
+
+/// Turns a proxied exchange into an endpoint serving the same response.
+pub fn recorded_endpoint(incoming: &IncomingRequest, response: &MockResponse) -> Endpoint {
+    let body = match &response.body {
+        MockBody::Bytes(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        MockBody::File(_) | MockBody::Events(_) | MockBody::Grpc(_) | MockBody::Reset(_) => String::new(),
+    };
+    let mut endpoint = Endpoint::new(
+        incoming.method.to_string(),
+        incoming.path.clone(),
+        response.status.as_u16(),
+        0,
+        body,
+    );
+    for (name, value) in &response.headers {
+        if name.eq_ignore_ascii_case("content-type") {
+            endpoint.content_type = value.clone();
+        } else if !name.eq_ignore_ascii_case("date") {
+            endpoint.headers.push((name.clone(), value.clone()));
+        }
+    }
+    endpoint
+}
+
+/// Turns recording of proxied responses on or off; requires proxy mode to be configured.
+#[tauri::command]
+pub async fn set_proxy_recording(state: tauri::State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    let mut settings = state.server_settings.write().await;
+    let proxy = settings
+        .proxy
+        .as_mut()
+        .ok_or_else(|| "Proxy mode is not configured".to_string())?;
+    proxy.record = enabled;
+    Ok(())
+}
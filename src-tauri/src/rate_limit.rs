@@ -0,0 +1,158 @@
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Requests counted per client in the current window.
+pub type RateLimitCounters = Arc<Mutex<HashMap<String, Window>>>;
+
+/// Counters are swept for ended windows once this many clients are tracked.
+const PRUNE_AT: usize = 10_000;
+
+/// Requests allowed per client in a fixed window. As `rate_limit`, requests
+/// beyond the limit are answered with 429 and `Retry-After`; as
+/// `rate_limit_headers`, responses only carry `X-RateLimit-*` headers that
+/// count down, and nothing is rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Requests allowed per window.
+    pub limit: u32,
+    #[serde(default = "default_window_secs", alias = "windowSecs")]
+    pub window_secs: u64,
+    /// Header identifying the client, e.g. `X-Api-Key`; clients are told apart
+    /// by IP address when unset or absent from the request.
+    #[serde(default, alias = "clientHeader")]
+    pub client_header: Option<String>,
+}
+
+fn default_window_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    started: Instant,
+    length: Duration,
+    reset_at: SystemTime,
+    used: u32,
+}
+
+/// Who a request counts against: the client header's value, else the peer IP.
+pub fn client_key(incoming: &IncomingRequest, client_header: Option<&str>) -> String {
+    client_header
+        .and_then(|name| incoming.headers.get(name))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| format!("header:{}", value))
+        .or_else(|| incoming.remote_addr.map(|addr| format!("ip:{}", addr.ip())))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Counts the request against its client's window, returning the window as it
+/// stands afterwards; a new window starts once the previous one has ended.
+/// Clients whose window has ended are forgotten when many are tracked.
+pub fn count(counters: &RateLimitCounters, key: String, window_secs: u64) -> Window {
+    let length = Duration::from_secs(window_secs.max(1));
+    let mut counters = counters.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if counters.len() >= PRUNE_AT && !counters.contains_key(&key) {
+        counters.retain(|_, window| !window.has_ended());
+    }
+    let window = counters.entry(key).or_insert_with(|| Window::starting(length));
+    if window.started.elapsed() >= length {
+        *window = Window::starting(length);
+    }
+    window.used = window.used.saturating_add(1);
+    *window
+}
+
+impl Window {
+    fn starting(length: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            length,
+            reset_at: SystemTime::now() + length,
+            used: 0,
+        }
+    }
+
+    /// Whether the window is over, by the length it started with.
+    fn has_ended(&self) -> bool {
+        self.started.elapsed() >= self.length
+    }
+
+    pub fn used(&self) -> u32 {
+        self.used
+    }
+
+    /// Whole seconds until the window resets, at least 1.
+    pub fn retry_after_secs(&self) -> u64 {
+        let remaining = self.reset_at.duration_since(SystemTime::now()).unwrap_or_default();
+        remaining.as_secs_f64().ceil().max(1.0) as u64
+    }
+
+    /// Unix time in seconds at which the window resets.
+    pub fn reset_epoch(&self) -> u64 {
+        self.reset_at
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+pub fn annotate(config: &RateLimit, counters: &RateLimitCounters, incoming: &IncomingRequest, response: &mut MockResponse) {
+    let key = client_key(incoming, config.client_header.as_deref());
+    let window = count(counters, key, config.window_secs);
+    response.apply_headers(&[
+        ("X-RateLimit-Limit".to_string(), config.limit.to_string()),
+        ("X-RateLimit-Remaining".to_string(), config.limit.saturating_sub(window.used()).to_string()),
+        ("X-RateLimit-Reset".to_string(), window.reset_epoch().to_string()),
+    ]);
+}
+
+/// Counts the request against `scope` (e.g. one endpoint) and builds the 429
+/// once the client is over the limit.
+pub fn enforce(config: &RateLimit, counters: &RateLimitCounters, scope: &str, incoming: &IncomingRequest) -> Option<MockResponse> {
+    let key = format!("{}:{}", scope, client_key(incoming, config.client_header.as_deref()));
+    let window = count(counters, key, config.window_secs);
+    if window.used() <= config.limit {
+        return None;
+    }
+    let mut response = MockResponse::json(
+        StatusCode::TOO_MANY_REQUESTS,
+        serde_json::json!({ "error": "Too many requests" }).to_string(),
+    );
+    response.apply_headers(&[
+        ("Retry-After".to_string(), window.retry_after_secs().to_string()),
+        ("X-RateLimit-Limit".to_string(), config.limit.to_string()),
+        ("X-RateLimit-Remaining".to_string(), "0".to_string()),
+        ("X-RateLimit-Reset".to_string(), window.reset_epoch().to_string()),
+    ]);
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ended_windows_are_pruned_once_many_clients_are_tracked() {
+        let counters = RateLimitCounters::default();
+        {
+            let mut tracked = counters.lock().unwrap();
+            let ended = Window::starting(Duration::ZERO);
+            for client in 0..PRUNE_AT {
+                tracked.insert(format!("ip:{}", client), ended);
+            }
+            tracked.insert("live".to_string(), Window::starting(Duration::from_secs(60)));
+        }
+        assert_eq!(count(&counters, "live".to_string(), 60).used(), 1);
+        assert_eq!(counters.lock().unwrap().len(), PRUNE_AT + 1);
+
+        count(&counters, "new".to_string(), 60);
+        let tracked = counters.lock().unwrap();
+        assert_eq!(tracked.len(), 2);
+        assert!(tracked.contains_key("live") && tracked.contains_key("new"));
+    }
+}
@@ -0,0 +1,293 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::Emitter;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::{IncomingRequest, MockResponse};
+
+pub const FINISHED_EVENT: &str = "recording://finished";
+/// Exchanges beyond this many are not recorded.
+const MAX_RECORDED: usize = 1000;
+/// Sample paths listed per suggestion.
+const MAX_SAMPLES: usize = 3;
+
+/// Taken after the endpoints lock whenever both are held, as the server
+/// records inbox requests while holding the endpoints.
+pub type SharedRecording = Arc<RwLock<Option<RecordingSession>>>;
+
+/// Which traffic a session records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingSource {
+    /// Requests forwarded in proxy mode, with the upstream's responses.
+    Proxy,
+    /// Requests received by inbox endpoints; suggestions answer `200 {}`.
+    Inbox,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordingSession {
+    id: String,
+    source: RecordingSource,
+    ends_at: DateTime<Utc>,
+    /// One endpoint per recorded exchange, oldest first.
+    recorded: Vec<Endpoint>,
+}
+
+impl RecordingSession {
+    fn is_active(&self) -> bool {
+        Utc::now() < self.ends_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub source: RecordingSource,
+    pub ends_at: String,
+    pub active: bool,
+    pub recorded: usize,
+}
+
+/// An endpoint covering every recorded request to one path template.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointSuggestion {
+    /// Pass to `accept_recording_suggestions`.
+    pub id: String,
+    pub requests: usize,
+    pub sample_paths: Vec<String>,
+    pub endpoint: Endpoint,
+}
+
+/// Records the exchange if a session for `source` is running.
+pub async fn capture(
+    recording: &SharedRecording,
+    source: RecordingSource,
+    incoming: &IncomingRequest,
+    response: Option<&MockResponse>,
+) {
+    let mut session = recording.write().await;
+    let Some(session) = session.as_mut().filter(|s| s.source == source && s.is_active()) else {
+        return;
+    };
+    if session.recorded.len() >= MAX_RECORDED {
+        return;
+    }
+    let mut endpoint = match response {
+        Some(response) => crate::proxy::recorded_endpoint(incoming, response),
+        None => Endpoint::new(incoming.method.to_string(), incoming.path.clone(), 200, 0, "{}".to_string()),
+    };
+    endpoint.example_request = Some(incoming.example_request());
+    session.recorded.push(endpoint);
+}
+
+/// Whether a path segment looks like an identifier: a number, a UUID, a
+/// long hex string, or a long token mixing letters and digits.
+fn is_identifier(segment: &str) -> bool {
+    let digits = segment.chars().filter(char::is_ascii_digit).count();
+    let hex = segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    !segment.is_empty()
+        && (digits == segment.len()
+            || (hex && segment.len() >= 8 && digits > 0)
+            || (segment.len() >= 12 && digits > 0 && segment.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c))))
+}
+
+/// `/users/42/orders/9f1c0e2a` -> `/users/*/orders/*`
+fn path_template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if is_identifier(segment) { "*" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Recorded exchanges grouped by method and path template, skipping routes
+/// the project already has. The first exchange of each group supplies the
+/// response.
+fn suggestions(recorded: &[Endpoint], existing: &[Endpoint]) -> Vec<EndpointSuggestion> {
+    let mut suggestions: Vec<EndpointSuggestion> = Vec::new();
+    for exchange in recorded {
+        let template = path_template(&exchange.path);
+        if existing
+            .iter()
+            .any(|e| e.method.eq_ignore_ascii_case(&exchange.method) && (e.path == template || e.path == exchange.path))
+        {
+            continue;
+        }
+        match suggestions
+            .iter_mut()
+            .find(|s| s.endpoint.method.eq_ignore_ascii_case(&exchange.method) && s.endpoint.path == template)
+        {
+            Some(suggestion) => {
+                suggestion.requests += 1;
+                if suggestion.sample_paths.len() < MAX_SAMPLES && !suggestion.sample_paths.contains(&exchange.path) {
+                    suggestion.sample_paths.push(exchange.path.clone());
+                }
+            }
+            None => {
+                let mut endpoint = exchange.clone();
+                endpoint.path = template;
+                suggestions.push(EndpointSuggestion {
+                    id: endpoint.id.clone(),
+                    requests: 1,
+                    sample_paths: vec![exchange.path.clone()],
+                    endpoint,
+                });
+            }
+        }
+    }
+    suggestions
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRecordingParams {
+    minutes: u32,
+    source: RecordingSource,
+}
+
+/// Records proxied or inbox traffic for `minutes`, then emits
+/// `recording://finished` with the suggested endpoints.
+#[tauri::command]
+pub async fn start_recording(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    params: StartRecordingParams,
+) -> Result<RecordingStatus, String> {
+    if params.minutes == 0 {
+        return Err("Recording must last at least one minute".to_string());
+    }
+    if params.source == RecordingSource::Proxy && state.server_settings.read().await.proxy.is_none() {
+        return Err("Proxy mode is not configured".to_string());
+    }
+    let mut recording = state.recording.write().await;
+    if recording.as_ref().is_some_and(RecordingSession::is_active) {
+        return Err("A recording session is already running".to_string());
+    }
+    let session = RecordingSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: params.source,
+        ends_at: Utc::now() + Duration::minutes(i64::from(params.minutes)),
+        recorded: Vec::new(),
+    };
+    let status = status(&session);
+    let (id, shared, endpoints) = (session.id.clone(), state.recording.clone(), state.endpoints.clone());
+    *recording = Some(session);
+
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(u64::from(params.minutes) * 60)).await;
+        let endpoints = endpoints.read().await;
+        let recording = shared.read().await;
+        // Stopped early or replaced by a newer session
+        let Some(session) = recording.as_ref().filter(|s| s.id == id) else {
+            return;
+        };
+        let suggested = suggestions(&session.recorded, &endpoints);
+        if let Err(e) = app.emit(FINISHED_EVENT, &suggested) {
+            eprintln!("Failed to emit recording event: {}", e);
+        }
+    });
+    Ok(status)
+}
+
+fn status(session: &RecordingSession) -> RecordingStatus {
+    RecordingStatus {
+        source: session.source,
+        ends_at: session.ends_at.to_rfc3339(),
+        active: session.is_active(),
+        recorded: session.recorded.len(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_recording_status(state: tauri::State<'_, AppState>) -> Result<Option<RecordingStatus>, String> {
+    Ok(state.recording.read().await.as_ref().map(status))
+}
+
+/// Ends the session now and returns its suggestions.
+#[tauri::command]
+pub async fn stop_recording(state: tauri::State<'_, AppState>) -> Result<Vec<EndpointSuggestion>, String> {
+    let endpoints = state.endpoints.read().await;
+    let mut recording = state.recording.write().await;
+    let session = recording.as_mut().ok_or_else(|| "No recording session".to_string())?;
+    session.ends_at = session.ends_at.min(Utc::now());
+    // Keeps the timer from announcing a session that was stopped by hand
+    session.id = uuid::Uuid::new_v4().to_string();
+    Ok(suggestions(&session.recorded, &endpoints))
+}
+
+#[tauri::command]
+pub async fn get_recording_suggestions(state: tauri::State<'_, AppState>) -> Result<Vec<EndpointSuggestion>, String> {
+    let endpoints = state.endpoints.read().await;
+    let recording = state.recording.read().await;
+    let session = recording.as_ref().ok_or_else(|| "No recording session".to_string())?;
+    Ok(suggestions(&session.recorded, &endpoints))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptRecordingParams {
+    ids: Vec<String>,
+}
+
+/// Adds the chosen suggestions to the project and discards the session.
+#[tauri::command]
+pub async fn accept_recording_suggestions(
+    state: tauri::State<'_, AppState>,
+    params: AcceptRecordingParams,
+) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let mut endpoints = state.endpoints.write().await;
+    let mut recording = state.recording.write().await;
+    let session = recording.as_ref().ok_or_else(|| "No recording session".to_string())?;
+    if session.is_active() {
+        return Err("The recording session is still running".to_string());
+    }
+    let accepted: Vec<Endpoint> = suggestions(&session.recorded, &endpoints)
+        .into_iter()
+        .filter(|s| params.ids.contains(&s.id))
+        .map(|s| s.endpoint)
+        .collect();
+    endpoints.extend(accepted.iter().cloned());
+    *recording = None;
+    Ok(accepted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, path: &str) -> Endpoint {
+        Endpoint::new(method.to_string(), path.to_string(), 200, 0, "{}".to_string())
+    }
+
+    #[test]
+    fn templates_replace_identifier_segments() {
+        assert_eq!(path_template("/users/42/orders/9f1c0e2a"), "/users/*/orders/*");
+        assert_eq!(path_template("/users/0b6f2c9e-4a1d-4f3b-9c2e-7d5a8b1e6f00"), "/users/*");
+        assert_eq!(path_template("/sessions/Ab3dEf6hIj9k"), "/sessions/*");
+        assert_eq!(path_template("/api/v1/users/"), "/api/v1/users/");
+        assert_eq!(path_template("/colors/deadbeef"), "/colors/deadbeef");
+        assert_eq!(path_template("/"), "/");
+    }
+
+    #[test]
+    fn suggestions_group_by_template_and_skip_existing_routes() {
+        let recorded = [
+            exchange("GET", "/users/1"),
+            exchange("GET", "/users/2"),
+            exchange("DELETE", "/users/1"),
+            exchange("GET", "/health"),
+        ];
+        let existing = [exchange("GET", "/health")];
+
+        let suggested = suggestions(&recorded, &existing);
+        assert_eq!(suggested.len(), 2);
+        assert_eq!(suggested[0].endpoint.path, "/users/*");
+        assert_eq!(suggested[0].requests, 2);
+        assert_eq!(suggested[0].sample_paths, ["/users/1", "/users/2"]);
+        assert_eq!(suggested[1].endpoint.method, "DELETE");
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::dependencies::fixed_delay;
+use crate::docs::{html_escape, pretty_body};
+use crate::endpoints::{AppState, Endpoint};
+
+const UNTAGGED: &str = "Untagged";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReportParams {
+    title: Option<String>,
+    #[serde(default)]
+    format: ReportFormat,
+}
+
+/// Endpoints by tag, tags in alphabetical order with untagged ones last. An
+/// endpoint with several tags appears under each of them.
+fn groups(endpoints: &[Endpoint]) -> Vec<(&str, Vec<&Endpoint>)> {
+    let mut tagged: BTreeMap<&str, Vec<&Endpoint>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for endpoint in endpoints {
+        if endpoint.tags.is_empty() {
+            untagged.push(endpoint);
+        }
+        for tag in &endpoint.tags {
+            tagged.entry(tag.as_str()).or_default().push(endpoint);
+        }
+    }
+    let mut groups: Vec<_> = tagged.into_iter().collect();
+    if !untagged.is_empty() {
+        groups.push((UNTAGGED, untagged));
+    }
+    groups
+}
+
+/// One line per behavior worth knowing besides the body.
+fn facts(endpoints: &[Endpoint], endpoint: &Endpoint) -> Vec<String> {
+    let mut facts = vec![format!("Status {} ({})", endpoint.status, endpoint.content_type)];
+    let delay = fixed_delay(endpoints, endpoint);
+    if let Some(jitter) = &endpoint.delay_jitter {
+        facts.push(jitter.to_string());
+    } else if delay > endpoint.delay {
+        facts.push(format!("{}ms delay, {}ms of it from dependencies", delay, delay - endpoint.delay));
+    } else if delay > 0 {
+        facts.push(format!("{}ms delay", delay));
+    }
+    if let Some(profile) = &endpoint.profile {
+        facts.push(format!("Profile: {}", profile));
+    }
+    if let Some(rate) = endpoint.bytes_per_sec {
+        facts.push(format!("Throttled to {} bytes/s", rate));
+    }
+    if let Some(fault) = endpoint.fault {
+        let name = format!("{:?}", fault).to_lowercase();
+        facts.push(format!("Fault injected: {}", name));
+    }
+    if !endpoint.enabled {
+        facts.push("Disabled".to_string());
+    }
+    facts
+}
+
+pub fn render_markdown(title: &str, endpoints: &[Endpoint]) -> String {
+    let mut out = format!("# {}\n\n{} endpoints.\n", title, endpoints.len());
+    for (tag, members) in groups(endpoints) {
+        out.push_str(&format!("\n## {}\n", tag));
+        for endpoint in members {
+            out.push_str(&format!("\n### `{} {}`\n\n", endpoint.method.to_uppercase(), endpoint.path));
+            for fact in facts(endpoints, endpoint) {
+                out.push_str(&format!("- {}\n", fact));
+            }
+            let body = pretty_body(endpoint);
+            if !body.is_empty() {
+                // A longer fence than any run of backticks in the body
+                let fence = "`".repeat(3.max(longest_backtick_run(&body) + 1));
+                let language = if endpoint.content_type.contains("json") { "json" } else { "" };
+                out.push_str(&format!("\n{}{}\n{}\n{}\n", fence, language, body, fence));
+            }
+        }
+    }
+    out
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+pub fn render_html(title: &str, endpoints: &[Endpoint]) -> String {
+    let mut sections = String::new();
+    for (tag, members) in groups(endpoints) {
+        sections.push_str(&format!("<h2>{}</h2>\n", html_escape(tag)));
+        for endpoint in members {
+            let facts: Vec<String> = facts(endpoints, endpoint).iter().map(|fact| html_escape(fact)).collect();
+            sections.push_str(&format!(
+                "<section class=\"endpoint\">\n<h3><span class=\"method {method_class}\">{method}</span> <code>{path}</code></h3>\n\
+                 <p>{facts}</p>\n<pre>{body}</pre>\n</section>\n",
+                method_class = html_escape(&endpoint.method.to_lowercase()),
+                method = html_escape(&endpoint.method.to_uppercase()),
+                path = html_escape(&endpoint.path),
+                facts = facts.join(" &middot; "),
+                body = html_escape(&pretty_body(endpoint)),
+            ));
+        }
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n\
+         <body>\n<h1>{title}</h1>\n<p>{count} endpoints.</p>\n{sections}</body>\n</html>\n",
+        title = html_escape(title),
+        style = crate::docs::STYLE,
+        count = endpoints.len(),
+    )
+}
+
+/// Renders the project as a shareable report, grouped by tag, with each
+/// endpoint's status, delays and example response.
+#[tauri::command]
+pub async fn export_report(state: tauri::State<'_, AppState>, params: ExportReportParams) -> Result<String, String> {
+    let title = params.title.unwrap_or_else(|| "Mock API".to_string());
+    let endpoints = state.endpoints.read().await;
+    Ok(match params.format {
+        ReportFormat::Markdown => render_markdown(&title, &endpoints),
+        ReportFormat::Html => render_html(&title, &endpoints),
+    })
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::AppState;
+use crate::memory::{Area, MemoryBudget};
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Oldest entries are dropped once the log holds this many requests.
+const MAX_LOG_ENTRIES: usize = 500;
+/// Longer request bodies are truncated in the log.
+const MAX_LOGGED_BODY_BYTES: usize = 64 * 1024;
+
+pub type RequestLog = Arc<RwLock<VecDeque<RequestLogEntry>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestLogEntry {
+    pub id: String,
+    pub timestamp: String,
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// `None` when no endpoint matched.
+    pub endpoint_id: Option<String>,
+    pub status: u16,
+    /// Time from receiving the full request to producing the response, including delays.
+    pub latency_ms: u64,
+    /// Closest endpoints, for requests answered with the fallback 404.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// Files sent as `multipart/form-data`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uploads: Vec<crate::multipart::UploadedFile>,
+    /// Size of the body as received, when it was sent compressed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_size: Option<usize>,
+    /// e.g. `HTTP/1.1` or `HTTP/2.0`
+    #[serde(default)]
+    pub http_version: String,
+    /// Negotiated TLS version, cipher and ALPN; `None` for plain HTTP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<crate::tls_info::TlsInfo>,
+    /// When the entry was stored, for evicting the oldest data across stores.
+    #[serde(skip)]
+    pub(crate) accessed: u64,
+}
+
+impl RequestLogEntry {
+    pub fn new(incoming: &IncomingRequest, response: &MockResponse, latency_ms: u64) -> Self {
+        let body = &incoming.body[..incoming.body.len().min(MAX_LOGGED_BODY_BYTES)];
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method: incoming.method.to_string(),
+            path: incoming.path.clone(),
+            query: incoming.query.clone(),
+            headers: incoming.header_pairs(),
+            body: String::from_utf8_lossy(body).to_string(),
+            endpoint_id: response.endpoint_id.clone(),
+            status: response.status.as_u16(),
+            latency_ms,
+            suggestions: response.suggestions.clone(),
+            uploads: Vec::new(),
+            raw_size: None,
+            http_version: format!("{:?}", incoming.http_version),
+            tls: incoming.tls.clone(),
+            accessed: crate::memory::tick(),
+        }
+    }
+}
+
+pub async fn push(log: &RequestLog, budget: &MemoryBudget, entry: RequestLogEntry) {
+    {
+        let mut entries = log.write().await;
+        if entries.len() >= MAX_LOG_ENTRIES {
+            if let Some(dropped) = entries.pop_front() {
+                budget.removed(Area::RequestLog, crate::memory::log_entry_size(&dropped));
+            }
+        }
+        budget.added(Area::RequestLog, crate::memory::log_entry_size(&entry));
+        entries.push_back(entry);
+    }
+    budget.enforce().await;
+}
+
+/// Received requests, newest first.
+#[tauri::command]
+pub async fn get_request_log(state: tauri::State<'_, AppState>) -> Result<Vec<RequestLogEntry>, String> {
+    Ok(state.request_log.read().await.iter().rev().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn clear_request_log(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let mut entries = state.request_log.write().await;
+    let freed: usize = entries.iter().map(crate::memory::log_entry_size).sum();
+    entries.clear();
+    state.memory.removed(Area::RequestLog, freed);
+    Ok(())
+}
@@ -0,0 +1,385 @@
+use axum::http::{header, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::memory::{Area, MemoryBudget};
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Items per resource endpoint id, seeded from the endpoint on first use.
+pub type ResourceStore = Arc<RwLock<HashMap<String, Vec<Record>>>>;
+
+/// A stored item with its serialized size and when it was last read or written.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub value: Value,
+    pub(crate) size: usize,
+    pub(crate) accessed: u64,
+}
+
+impl Record {
+    pub fn new(value: Value) -> Self {
+        Self {
+            size: value.to_string().len(),
+            accessed: crate::memory::tick(),
+            value,
+        }
+    }
+}
+
+fn records_size(records: &[Record]) -> usize {
+    records.iter().map(|record| record.size).sum()
+}
+
+/// Settings of a `resource` endpoint; its `path` is the collection, e.g. `/users`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceConfig {
+    /// Field holding each item's id.
+    #[serde(default = "default_id_field", alias = "idField")]
+    pub id_field: String,
+    /// Items the collection starts with (and returns to on reset).
+    #[serde(default)]
+    pub seed: Vec<Value>,
+    /// `Location` of a created item; `{path}` is the collection path and `{id}`
+    /// the new id. Empty sends no `Location` header.
+    #[serde(default = "default_location")]
+    pub location: String,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            id_field: default_id_field(),
+            seed: Vec::new(),
+            location: default_location(),
+        }
+    }
+}
+
+fn default_id_field() -> String {
+    "id".to_string()
+}
+
+fn default_location() -> String {
+    "{path}/{id}".to_string()
+}
+
+/// `Some(None)` for the collection path, `Some(Some(id))` for an item path.
+pub fn item_id<'a>(endpoint: &Endpoint, path: &'a str) -> Option<Option<&'a str>> {
+    let base = endpoint.path.trim_end_matches('/');
+    let rest = path.strip_prefix(base)?;
+    match rest.trim_end_matches('/') {
+        "" => Some(None),
+        rest => match rest.strip_prefix('/') {
+            Some(id) if !id.contains('/') => Some(Some(id)),
+            _ => None,
+        },
+    }
+}
+
+/// GET/POST on the collection, GET/PUT/PATCH/DELETE on `<path>/<id>`, and HEAD
+/// wherever GET works. A POST without an id gets one generated and answers 201
+/// with the stored item and a `Location` header; one with an id already taken
+/// answers 409. Items carry an `ETag`; writes with a stale `If-Match` answer 412.
+pub async fn handle(
+    store: &ResourceStore,
+    budget: &MemoryBudget,
+    endpoint: &Endpoint,
+    incoming: &IncomingRequest,
+) -> MockResponse {
+    let config = endpoint.resource.clone().unwrap_or_default();
+    let Some(id) = item_id(endpoint, &incoming.path) else {
+        return not_found();
+    };
+
+    let response = {
+        let mut store = store.write().await;
+        let items = match store.entry(endpoint.id.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let seeded: Vec<Record> = config.seed.iter().cloned().map(Record::new).collect();
+                budget.added(Area::Resources, records_size(&seeded));
+                entry.insert(seeded)
+            }
+        };
+        let before = records_size(items);
+        let response = apply(items, &config, endpoint, id, incoming);
+        budget.resized(Area::Resources, before, records_size(items));
+        response
+    };
+    budget.enforce().await;
+    response
+}
+
+fn apply(
+    items: &mut Vec<Record>,
+    config: &ResourceConfig,
+    endpoint: &Endpoint,
+    id: Option<&str>,
+    incoming: &IncomingRequest,
+) -> MockResponse {
+    let position = id.and_then(|id| {
+        items
+            .iter()
+            .position(|item| id_of(&item.value, &config.id_field) == Some(id.to_string()))
+    });
+
+    let writes = matches!(incoming.method, Method::PUT | Method::PATCH | Method::DELETE);
+    if let (true, Some(_), Some(expected)) = (writes, id, incoming.headers.get(header::IF_MATCH)) {
+        let current = position.map(|index| etag(&items[index].value));
+        if !if_match(expected.to_str().unwrap_or_default(), current.as_deref()) {
+            return precondition_failed();
+        }
+    }
+
+    // HEAD answers like GET; the body is dropped when the response is sent
+    let method = match incoming.method {
+        Method::HEAD => Method::GET,
+        ref method => method.clone(),
+    };
+    match (&method, id, position) {
+        (&Method::GET, None, _) => {
+            let accessed = crate::memory::tick();
+            items.iter_mut().for_each(|item| item.accessed = accessed);
+            ok(StatusCode::OK, &Value::Array(items.iter().map(|item| item.value.clone()).collect()))
+        }
+        (&Method::POST, None, _) => {
+            let Some(mut item) = json_object(incoming) else {
+                return invalid_body();
+            };
+            match id_of(&item, &config.id_field) {
+                Some(id) if items.iter().any(|other| id_of(&other.value, &config.id_field) == Some(id.clone())) => {
+                    return conflict();
+                }
+                Some(_) => {}
+                None => item[config.id_field.as_str()] = next_id(items, &config.id_field),
+            }
+            items.push(Record::new(item.clone()));
+            let mut response = item_response(StatusCode::CREATED, &item);
+            if !config.location.is_empty() {
+                let id = id_of(&item, &config.id_field).unwrap_or_default();
+                let location = config
+                    .location
+                    .replace("{path}", endpoint.path.trim_end_matches('/'))
+                    .replace("{id}", &id);
+                response.headers.push(("Location".to_string(), location));
+            }
+            response
+        }
+        (_, None, _) => method_not_allowed(),
+        (_, Some(_), None) => not_found(),
+        (&Method::GET, Some(_), Some(index)) => {
+            items[index].accessed = crate::memory::tick();
+            item_response(StatusCode::OK, &items[index].value)
+        }
+        (&Method::PUT, Some(_), Some(index)) => {
+            let Some(mut item) = json_object(incoming) else {
+                return invalid_body();
+            };
+            item[config.id_field.as_str()] = items[index].value[config.id_field.as_str()].clone();
+            items[index] = Record::new(item);
+            item_response(StatusCode::OK, &items[index].value)
+        }
+        (&Method::PATCH, Some(_), Some(index)) => {
+            let Some(Value::Object(changes)) = json_object(incoming) else {
+                return invalid_body();
+            };
+            let mut item = std::mem::take(&mut items[index].value);
+            if let Value::Object(fields) = &mut item {
+                for (key, value) in changes.into_iter().filter(|(key, _)| *key != config.id_field) {
+                    fields.insert(key, value);
+                }
+            }
+            items[index] = Record::new(item);
+            item_response(StatusCode::OK, &items[index].value)
+        }
+        (&Method::DELETE, Some(_), Some(index)) => {
+            items.remove(index);
+            MockResponse::new(StatusCode::NO_CONTENT, "application/json", "")
+        }
+        _ => method_not_allowed(),
+    }
+}
+
+/// Ids are compared as strings so `/users/1` finds `{"id": 1}`.
+fn id_of(item: &Value, id_field: &str) -> Option<String> {
+    match item.get(id_field)? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// One past the largest numeric id, or a UUID when the ids aren't numbers.
+fn next_id(items: &[Record], id_field: &str) -> Value {
+    let numeric: Option<Vec<u64>> = items.iter().map(|item| item.value.get(id_field)?.as_u64()).collect();
+    match numeric {
+        Some(ids) => Value::from(ids.into_iter().max().unwrap_or(0) + 1),
+        None => Value::from(uuid::Uuid::new_v4().to_string()),
+    }
+}
+
+/// A strong validator derived from the item's content, so it changes with
+/// every write.
+fn etag(item: &Value) -> String {
+    let digest = Sha256::digest(item.to_string().as_bytes());
+    format!("\"{}\"", &hex::encode(digest)[..16])
+}
+
+/// Strong comparison, as RFC 9110 requires for `If-Match`; `*` matches any
+/// existing item.
+fn if_match(expected: &str, current: Option<&str>) -> bool {
+    let Some(current) = current else {
+        return false;
+    };
+    expected.trim() == "*" || expected.split(',').any(|tag| tag.trim() == current)
+}
+
+fn item_response(status: StatusCode, item: &Value) -> MockResponse {
+    let mut response = ok(status, item);
+    response.headers.push(("ETag".to_string(), etag(item)));
+    response
+}
+
+fn json_object(incoming: &IncomingRequest) -> Option<Value> {
+    incoming.json_body.clone().filter(Value::is_object)
+}
+
+fn ok(status: StatusCode, body: &Value) -> MockResponse {
+    MockResponse::json(status, body.to_string())
+}
+
+fn not_found() -> MockResponse {
+    MockResponse::json(StatusCode::NOT_FOUND, r#"{"error": "Resource not found"}"#)
+}
+
+fn invalid_body() -> MockResponse {
+    MockResponse::json(StatusCode::BAD_REQUEST, r#"{"error": "Request body must be a JSON object"}"#)
+}
+
+fn precondition_failed() -> MockResponse {
+    MockResponse::json(StatusCode::PRECONDITION_FAILED, r#"{"error": "If-Match does not match the current item"}"#)
+}
+
+fn conflict() -> MockResponse {
+    MockResponse::json(StatusCode::CONFLICT, r#"{"error": "An item with this id already exists"}"#)
+}
+
+fn method_not_allowed() -> MockResponse {
+    MockResponse::json(StatusCode::METHOD_NOT_ALLOWED, r#"{"error": "Method not allowed"}"#)
+}
+
+/// Replaces one resource endpoint's items.
+pub async fn seed(store: &ResourceStore, budget: &MemoryBudget, endpoint_id: &str, items: &[Value]) {
+    let records: Vec<Record> = items.iter().cloned().map(Record::new).collect();
+    budget.added(Area::Resources, records_size(&records));
+    if let Some(previous) = store.write().await.insert(endpoint_id.to_string(), records) {
+        budget.removed(Area::Resources, records_size(&previous));
+    }
+    budget.enforce().await;
+}
+
+/// Drops one resource endpoint's items, or every resource's, so they are
+/// seeded again on next use.
+pub async fn clear(store: &ResourceStore, budget: &MemoryBudget, endpoint_id: Option<&str>) {
+    let mut store = store.write().await;
+    let freed: usize = match endpoint_id {
+        Some(id) => store.remove(id).map(|records| records_size(&records)).unwrap_or(0),
+        None => store.drain().map(|(_, records)| records_size(&records)).sum(),
+    };
+    budget.removed(Area::Resources, freed);
+}
+
+/// Puts one resource endpoint's items, or every resource's, back to the seed.
+#[tauri::command]
+pub async fn reset_resources(state: tauri::State<'_, AppState>, endpoint_id: Option<String>) -> Result<(), String> {
+    clear(&state.resources, &state.memory, endpoint_id.as_deref()).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+    use axum::http::Request;
+
+    fn users() -> Endpoint {
+        let mut endpoint = Endpoint::new("GET".to_string(), "/users".to_string(), 200, 0, String::new());
+        endpoint.resource = Some(ResourceConfig {
+            seed: vec![serde_json::json!({ "id": 1, "name": "Ann" })],
+            ..ResourceConfig::default()
+        });
+        endpoint
+    }
+
+    fn request(method: &str, path: &str, headers: &[(&str, &str)], body: &str) -> IncomingRequest {
+        let mut builder = Request::builder().method(method).uri(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let (parts, ()) = builder.body(()).unwrap().into_parts();
+        IncomingRequest::from_parts(&parts, Bytes::from(body.to_string()))
+    }
+
+    fn header(response: &MockResponse, name: &str) -> Option<String> {
+        response
+            .headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone())
+    }
+
+    #[tokio::test]
+    async fn stale_if_match_answers_412() {
+        let (store, endpoint) = (ResourceStore::default(), users());
+        let budget = MemoryBudget::new(Default::default(), Default::default(), store.clone());
+        let read = handle(&store, &budget, &endpoint, &request("GET", "/users/1", &[], "")).await;
+        let tag = header(&read, "ETag").unwrap();
+
+        let update = request("PUT", "/users/1", &[("If-Match", &tag)], r#"{"name": "Bea"}"#);
+        let updated = handle(&store, &budget, &endpoint, &update).await;
+        assert_eq!(updated.status, StatusCode::OK);
+        assert_ne!(header(&updated, "ETag").unwrap(), tag);
+
+        let stale = request("PATCH", "/users/1", &[("If-Match", &tag)], r#"{"name": "Cy"}"#);
+        assert_eq!(handle(&store, &budget, &endpoint, &stale).await.status, StatusCode::PRECONDITION_FAILED);
+        let delete = request("DELETE", "/users/1", &[("If-Match", &tag)], "");
+        assert_eq!(handle(&store, &budget, &endpoint, &delete).await.status, StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn post_with_a_taken_id_answers_409() {
+        let (store, endpoint) = (ResourceStore::default(), users());
+        let budget = MemoryBudget::new(Default::default(), Default::default(), store.clone());
+        let taken = request("POST", "/users", &[], r#"{"id": 1, "name": "Bea"}"#);
+        assert_eq!(handle(&store, &budget, &endpoint, &taken).await.status, StatusCode::CONFLICT);
+        let free = request("POST", "/users", &[], r#"{"id": 2, "name": "Bea"}"#);
+        assert_eq!(handle(&store, &budget, &endpoint, &free).await.status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn head_answers_like_get() {
+        let (store, endpoint) = (ResourceStore::default(), users());
+        let budget = MemoryBudget::new(Default::default(), Default::default(), store.clone());
+        let item = handle(&store, &budget, &endpoint, &request("HEAD", "/users/1", &[], "")).await;
+        assert_eq!(item.status, StatusCode::OK);
+        assert!(header(&item, "ETag").is_some());
+        let collection = handle(&store, &budget, &endpoint, &request("HEAD", "/users", &[], "")).await;
+        assert_eq!(collection.status, StatusCode::OK);
+        let missing = handle(&store, &budget, &endpoint, &request("HEAD", "/users/9", &[], "")).await;
+        assert_eq!(missing.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn if_match_uses_strong_comparison() {
+        assert!(if_match("*", Some("\"a\"")));
+        assert!(if_match("\"b\", \"a\"", Some("\"a\"")));
+        assert!(!if_match("W/\"a\"", Some("\"a\"")));
+        assert!(!if_match("*", None));
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::AppState;
+
+/// A named way of running the project, e.g. "local HTTP" or "LAN HTTPS demo".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunConfig {
+    pub name: String,
+    pub port: u16,
+    #[serde(alias = "bindAddr")]
+    pub bind_addr: String,
+    #[serde(default, alias = "enableTls")]
+    pub enable_tls: bool,
+    /// Only endpoints carrying at least one of these tags are served; empty serves all.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// SLO profile applied project-wide when this configuration starts; none
+    /// clears the active profile.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Creates a run configuration or replaces the one with the same name.
+#[tauri::command]
+pub async fn save_run_config(state: tauri::State<'_, AppState>, config: RunConfig) -> Result<(), String> {
+    if config.name.trim().is_empty() {
+        return Err("Run configuration name must not be empty".to_string());
+    }
+    let mut settings = state.server_settings.write().await;
+    match settings.run_configs.iter_mut().find(|c| c.name == config.name) {
+        Some(existing) => *existing = config,
+        None => settings.run_configs.push(config),
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_run_config(state: tauri::State<'_, AppState>, name: String) -> Result<(), String> {
+    let mut settings = state.server_settings.write().await;
+    let index = settings
+        .run_configs
+        .iter()
+        .position(|c| c.name == name)
+        .ok_or_else(|| format!("Run configuration not found: {}", name))?;
+    settings.run_configs.remove(index);
+    Ok(())
+}
+
+/// (Re)starts the default server as the named run configuration describes.
+#[tauri::command]
+pub async fn start_server_with_config(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<String, String> {
+    let config = {
+        let mut settings = state.server_settings.write().await;
+        let config = settings
+            .run_configs
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+            .ok_or_else(|| format!("Run configuration not found: {}", name))?;
+        if let Some(profile) = &config.profile {
+            if crate::profiles::find(&settings, profile).is_none() {
+                return Err(format!("Unknown profile: {}", profile));
+            }
+        }
+        // A configuration without a profile runs without one, rather than
+        // inheriting whatever the previous run left active
+        settings.profile = config.profile.clone();
+        config
+    };
+    crate::endpoints::launch_server(app, &state, (&config).into()).await
+}
@@ -0,0 +1,423 @@
+use axum::http::{Method, StatusCode};
+use md5::{Digest, Md5};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::server::{IncomingRequest, MockResponse};
+use crate::soap::xml_escape;
+
+const XML_CONTENT_TYPE: &str = "application/xml";
+const S3_NS: &str = "http://s3.amazonaws.com/doc/2006-03-01/";
+const UPLOADS_DIR: &str = ".uploads";
+
+/// Path-style S3 API backed by a local directory (one sub-directory per bucket).
+///
+/// Signatures are never verified, so both signed requests and presigned URLs
+/// are accepted as-is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S3Settings {
+    pub root_dir: String,
+    /// URL prefix the API is served under; empty serves S3 at the root.
+    #[serde(default = "default_path_prefix")]
+    pub path_prefix: String,
+}
+
+fn default_path_prefix() -> String {
+    "/s3".to_string()
+}
+
+impl S3Settings {
+    /// The `/bucket/key` part of the path when the request targets the S3 API.
+    pub fn strip_prefix<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let rest = path.strip_prefix(self.path_prefix.trim_end_matches('/'))?;
+        (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+    }
+}
+
+struct S3Error {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl S3Error {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "InternalError", message)
+    }
+
+    fn into_response(self) -> MockResponse {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error><Code>{}</Code><Message>{}</Message></Error>",
+            self.code,
+            xml_escape(&self.message)
+        );
+        MockResponse::new(self.status, XML_CONTENT_TYPE, body)
+    }
+}
+
+impl From<std::io::Error> for S3Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::internal(e.to_string())
+    }
+}
+
+pub async fn handle(settings: &S3Settings, api_path: &str, incoming: &IncomingRequest) -> MockResponse {
+    match route(settings, api_path, incoming).await {
+        Ok(response) => response,
+        Err(error) => error.into_response(),
+    }
+}
+
+async fn route(settings: &S3Settings, api_path: &str, incoming: &IncomingRequest) -> Result<MockResponse, S3Error> {
+    let decoded = percent_decode_str(api_path.trim_start_matches('/'))
+        .decode_utf8_lossy()
+        .to_string();
+    let (bucket, key) = decoded.split_once('/').unwrap_or((decoded.as_str(), ""));
+    let root = Path::new(&settings.root_dir);
+
+    if bucket.is_empty() {
+        return match incoming.method {
+            Method::GET => list_buckets(root).await,
+            _ => Err(method_not_allowed()),
+        };
+    }
+
+    validate_bucket(bucket)?;
+    let bucket_dir = root.join(bucket);
+
+    if key.is_empty() {
+        return match incoming.method {
+            Method::PUT => {
+                tokio::fs::create_dir_all(&bucket_dir).await?;
+                Ok(empty(StatusCode::OK))
+            }
+            Method::DELETE => delete_bucket(&bucket_dir).await,
+            Method::HEAD => {
+                ensure_bucket(&bucket_dir)?;
+                Ok(empty(StatusCode::OK))
+            }
+            Method::GET => list_objects(bucket, &bucket_dir, incoming).await,
+            _ => Err(method_not_allowed()),
+        };
+    }
+
+    validate_key(key)?;
+    let object_path = bucket_dir.join(key);
+
+    match (&incoming.method, query_param(incoming, "uploadId")) {
+        (&Method::POST, None) if incoming.query.iter().any(|(name, _)| name == "uploads") => {
+            ensure_bucket(&bucket_dir)?;
+            initiate_upload(root, bucket, key).await
+        }
+        (&Method::PUT, Some(upload_id)) => upload_part(root, upload_id, incoming).await,
+        (&Method::POST, Some(upload_id)) => complete_upload(root, bucket, key, upload_id, &object_path).await,
+        (&Method::DELETE, Some(upload_id)) => {
+            let _ = tokio::fs::remove_dir_all(upload_dir(root, upload_id)?).await;
+            Ok(empty(StatusCode::NO_CONTENT))
+        }
+        (&Method::PUT, None) => {
+            ensure_bucket(&bucket_dir)?;
+            write_object(&object_path, &incoming.body).await?;
+            let mut response = empty(StatusCode::OK);
+            response.headers.push(("ETag".to_string(), etag(&incoming.body)));
+            Ok(response)
+        }
+        (&Method::GET | &Method::HEAD, None) => {
+            if !object_path.is_file() {
+                return Err(S3Error::new(StatusCode::NOT_FOUND, "NoSuchKey", format!("No such key: {}", key)));
+            }
+            Ok(MockResponse::file(
+                StatusCode::OK,
+                "application/octet-stream",
+                object_path.to_string_lossy().to_string(),
+            ))
+        }
+        (&Method::DELETE, None) => {
+            match tokio::fs::remove_file(&object_path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            Ok(empty(StatusCode::NO_CONTENT))
+        }
+        _ => Err(method_not_allowed()),
+    }
+}
+
+fn query_param<'a>(incoming: &'a IncomingRequest, name: &str) -> Option<&'a str> {
+    incoming
+        .query
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn empty(status: StatusCode) -> MockResponse {
+    MockResponse::new(status, XML_CONTENT_TYPE, "")
+}
+
+fn method_not_allowed() -> S3Error {
+    S3Error::new(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed", "The specified method is not allowed")
+}
+
+fn validate_bucket(bucket: &str) -> Result<(), S3Error> {
+    if bucket.starts_with('.') || bucket.contains(['\\', ':']) {
+        return Err(S3Error::new(StatusCode::BAD_REQUEST, "InvalidBucketName", format!("Invalid bucket name: {}", bucket)));
+    }
+    Ok(())
+}
+
+fn validate_key(key: &str) -> Result<(), S3Error> {
+    if key.starts_with('/') || key.contains('\\') || key.split('/').any(|segment| segment == ".." || segment == ".") {
+        return Err(S3Error::new(StatusCode::BAD_REQUEST, "InvalidArgument", format!("Invalid object key: {}", key)));
+    }
+    Ok(())
+}
+
+fn ensure_bucket(bucket_dir: &Path) -> Result<(), S3Error> {
+    if bucket_dir.is_dir() {
+        Ok(())
+    } else {
+        Err(S3Error::new(StatusCode::NOT_FOUND, "NoSuchBucket", "The specified bucket does not exist"))
+    }
+}
+
+fn etag(content: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(Md5::digest(content)))
+}
+
+fn timestamp(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time)
+        .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+        .to_string()
+}
+
+async fn write_object(path: &Path, content: &[u8]) -> Result<(), S3Error> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, content).await?;
+    Ok(())
+}
+
+async fn list_buckets(root: &Path) -> Result<MockResponse, S3Error> {
+    let mut buckets = String::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(root).await {
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().await?;
+            if name.starts_with('.') || !metadata.is_dir() {
+                continue;
+            }
+            let created = metadata.created().or_else(|_| metadata.modified()).unwrap_or(SystemTime::now());
+            buckets.push_str(&format!(
+                "<Bucket><Name>{}</Name><CreationDate>{}</CreationDate></Bucket>",
+                xml_escape(&name),
+                timestamp(created)
+            ));
+        }
+    }
+
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ListAllMyBucketsResult xmlns=\"{}\"><Owner><ID>fakeapi</ID><DisplayName>fakeapi</DisplayName></Owner><Buckets>{}</Buckets></ListAllMyBucketsResult>",
+        S3_NS, buckets
+    );
+    Ok(MockResponse::new(StatusCode::OK, XML_CONTENT_TYPE, body))
+}
+
+async fn delete_bucket(bucket_dir: &Path) -> Result<MockResponse, S3Error> {
+    ensure_bucket(bucket_dir)?;
+    let mut entries = tokio::fs::read_dir(bucket_dir).await?;
+    if entries.next_entry().await?.is_some() {
+        return Err(S3Error::new(StatusCode::CONFLICT, "BucketNotEmpty", "The bucket you tried to delete is not empty"));
+    }
+    tokio::fs::remove_dir(bucket_dir).await?;
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+/// ListObjectsV2 with `prefix`, `delimiter`, `max-keys` and continuation tokens.
+async fn list_objects(bucket: &str, bucket_dir: &Path, incoming: &IncomingRequest) -> Result<MockResponse, S3Error> {
+    ensure_bucket(bucket_dir)?;
+
+    let prefix = query_param(incoming, "prefix").unwrap_or("");
+    let delimiter = query_param(incoming, "delimiter").filter(|d| !d.is_empty());
+    let max_keys: usize = query_param(incoming, "max-keys")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1000);
+    let start_after = query_param(incoming, "continuation-token")
+        .or_else(|| query_param(incoming, "start-after"))
+        .unwrap_or("");
+
+    let dir = bucket_dir.to_path_buf();
+    let mut objects = tokio::task::spawn_blocking(move || walk_objects(&dir, &dir))
+        .await
+        .map_err(|e| S3Error::internal(e.to_string()))??;
+    objects.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut contents = String::new();
+    let mut common_prefixes: Vec<String> = Vec::new();
+    let mut key_count = 0;
+    let mut last_key = None;
+    let mut truncated = false;
+
+    for (key, size, modified) in objects
+        .into_iter()
+        .filter(|(key, _, _)| key.starts_with(prefix) && key.as_str() > start_after)
+    {
+        if key_count >= max_keys {
+            truncated = true;
+            break;
+        }
+
+        let common = delimiter.and_then(|d| {
+            key[prefix.len()..]
+                .find(d)
+                .map(|pos| key[..prefix.len() + pos + d.len()].to_string())
+        });
+        match common {
+            Some(common) if common_prefixes.contains(&common) => {}
+            Some(common) => {
+                common_prefixes.push(common);
+                key_count += 1;
+            }
+            None => {
+                contents.push_str(&format!(
+                    "<Contents><Key>{}</Key><LastModified>{}</LastModified><Size>{}</Size><StorageClass>STANDARD</StorageClass></Contents>",
+                    xml_escape(&key),
+                    timestamp(modified),
+                    size
+                ));
+                key_count += 1;
+            }
+        }
+        last_key = Some(key);
+    }
+
+    let next_token = match (truncated, last_key) {
+        (true, Some(key)) => format!("<NextContinuationToken>{}</NextContinuationToken>", xml_escape(&key)),
+        _ => String::new(),
+    };
+    let prefixes: String = common_prefixes
+        .iter()
+        .map(|p| format!("<CommonPrefixes><Prefix>{}</Prefix></CommonPrefixes>", xml_escape(p)))
+        .collect();
+
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ListBucketResult xmlns=\"{}\"><Name>{}</Name><Prefix>{}</Prefix><KeyCount>{}</KeyCount><MaxKeys>{}</MaxKeys><IsTruncated>{}</IsTruncated>{}{}{}</ListBucketResult>",
+        S3_NS,
+        xml_escape(bucket),
+        xml_escape(prefix),
+        key_count,
+        max_keys,
+        truncated,
+        next_token,
+        contents,
+        prefixes
+    );
+    Ok(MockResponse::new(StatusCode::OK, XML_CONTENT_TYPE, body))
+}
+
+/// Every file below `dir` as `(key, size, modified)`, keys relative to `base` with `/` separators.
+fn walk_objects(base: &Path, dir: &Path) -> std::io::Result<Vec<(String, u64, SystemTime)>> {
+    let mut objects = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let path = entry.path();
+        if metadata.is_dir() {
+            objects.extend(walk_objects(base, &path)?);
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            objects.push((key, metadata.len(), metadata.modified().unwrap_or(SystemTime::now())));
+        }
+    }
+    Ok(objects)
+}
+
+fn upload_dir(root: &Path, upload_id: &str) -> Result<PathBuf, S3Error> {
+    if uuid::Uuid::parse_str(upload_id).is_err() {
+        return Err(S3Error::new(StatusCode::NOT_FOUND, "NoSuchUpload", "The specified upload does not exist"));
+    }
+    Ok(root.join(UPLOADS_DIR).join(upload_id))
+}
+
+async fn initiate_upload(root: &Path, bucket: &str, key: &str) -> Result<MockResponse, S3Error> {
+    let upload_id = uuid::Uuid::new_v4().to_string();
+    tokio::fs::create_dir_all(upload_dir(root, &upload_id)?).await?;
+
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<InitiateMultipartUploadResult xmlns=\"{}\"><Bucket>{}</Bucket><Key>{}</Key><UploadId>{}</UploadId></InitiateMultipartUploadResult>",
+        S3_NS,
+        xml_escape(bucket),
+        xml_escape(key),
+        upload_id
+    );
+    Ok(MockResponse::new(StatusCode::OK, XML_CONTENT_TYPE, body))
+}
+
+async fn upload_part(root: &Path, upload_id: &str, incoming: &IncomingRequest) -> Result<MockResponse, S3Error> {
+    let dir = upload_dir(root, upload_id)?;
+    if !dir.is_dir() {
+        return Err(S3Error::new(StatusCode::NOT_FOUND, "NoSuchUpload", "The specified upload does not exist"));
+    }
+    let part_number: u32 = query_param(incoming, "partNumber")
+        .and_then(|n| n.parse().ok())
+        .filter(|n| (1..=10000).contains(n))
+        .ok_or_else(|| S3Error::new(StatusCode::BAD_REQUEST, "InvalidArgument", "partNumber must be between 1 and 10000"))?;
+
+    tokio::fs::write(dir.join(format!("{:05}", part_number)), &incoming.body).await?;
+    let mut response = empty(StatusCode::OK);
+    response.headers.push(("ETag".to_string(), etag(&incoming.body)));
+    Ok(response)
+}
+
+async fn complete_upload(
+    root: &Path,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    object_path: &Path,
+) -> Result<MockResponse, S3Error> {
+    let dir = upload_dir(root, upload_id)?;
+    if !dir.is_dir() {
+        return Err(S3Error::new(StatusCode::NOT_FOUND, "NoSuchUpload", "The specified upload does not exist"));
+    }
+
+    let mut parts = Vec::new();
+    let mut entries = tokio::fs::read_dir(&dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        parts.push(entry.path());
+    }
+    parts.sort();
+
+    let mut content = Vec::new();
+    for part in &parts {
+        content.extend_from_slice(&tokio::fs::read(part).await?);
+    }
+    write_object(object_path, &content).await?;
+    tokio::fs::remove_dir_all(&dir).await?;
+
+    let etag = format!("\"{}-{}\"", hex::encode(Md5::digest(&content)), parts.len());
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<CompleteMultipartUploadResult xmlns=\"{}\"><Bucket>{}</Bucket><Key>{}</Key><ETag>{}</ETag></CompleteMultipartUploadResult>",
+        S3_NS,
+        xml_escape(bucket),
+        xml_escape(key),
+        xml_escape(&etag)
+    );
+    Ok(MockResponse::new(StatusCode::OK, XML_CONTENT_TYPE, body))
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::AppState;
+
+/// Every scenario begins here and returns here when reset.
+pub const STARTED: &str = "Started";
+
+/// Current state per scenario name; scenarios not in the map are in `STARTED`.
+pub type ScenarioStates = Arc<RwLock<HashMap<String, String>>>;
+
+/// Ties an endpoint to a named scenario, WireMock style.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioRule {
+    pub name: String,
+    /// The endpoint only matches while the scenario is in this state.
+    #[serde(default, alias = "requiredState")]
+    pub required_state: Option<String>,
+    /// State the scenario moves to when the endpoint matches.
+    #[serde(default, alias = "newState")]
+    pub new_state: Option<String>,
+}
+
+pub async fn in_required_state(states: &ScenarioStates, rule: &ScenarioRule) -> bool {
+    let Some(required) = &rule.required_state else {
+        return true;
+    };
+    let states = states.read().await;
+    states.get(&rule.name).map(String::as_str).unwrap_or(STARTED) == required
+}
+
+pub async fn transition(states: &ScenarioStates, rule: &ScenarioRule) {
+    if let Some(new_state) = &rule.new_state {
+        states.write().await.insert(rule.name.clone(), new_state.clone());
+    }
+}
+
+/// State of every scenario referenced by an endpoint or moved since the last reset.
+#[tauri::command]
+pub async fn get_scenarios(state: tauri::State<'_, AppState>) -> Result<BTreeMap<String, String>, String> {
+    let mut scenarios: BTreeMap<String, String> = state
+        .endpoints
+        .read()
+        .await
+        .iter()
+        .filter_map(|endpoint| endpoint.scenario.as_ref())
+        .map(|rule| (rule.name.clone(), STARTED.to_string()))
+        .collect();
+    for (name, current) in state.scenarios.read().await.iter() {
+        scenarios.insert(name.clone(), current.clone());
+    }
+    Ok(scenarios)
+}
+
+/// Puts one scenario, or all of them when no name is given, back in `Started`.
+#[tauri::command]
+pub async fn reset_scenarios(state: tauri::State<'_, AppState>, name: Option<String>) -> Result<(), String> {
+    let mut states = state.scenarios.write().await;
+    match name {
+        Some(name) => {
+            states.remove(&name);
+        }
+        None => states.clear(),
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use axum::http::StatusCode;
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::{IncomingRequest, MockResponse};
+
+pub type ScriptStates = Arc<ScriptStore>;
+
+#[derive(Default)]
+pub struct ScriptStore {
+    /// Values scripts keep in `state` between calls, per endpoint id. Each
+    /// is locked for a whole run so concurrent calls don't lose updates.
+    states: Mutex<HashMap<String, Arc<Mutex<Map>>>>,
+    /// Compiled scripts with the source they were compiled from, per endpoint id.
+    compiled: Mutex<HashMap<String, (String, Arc<AST>)>>,
+}
+
+impl ScriptStore {
+    fn state(&self, id: &str) -> Result<Arc<Mutex<Map>>, String> {
+        let mut states = self.states.lock().map_err(|_| "Script state poisoned".to_string())?;
+        Ok(states.entry(id.to_string()).or_default().clone())
+    }
+
+    /// The compiled script, recompiling only when the source changed.
+    fn compile(&self, id: &str, source: &str) -> Result<Arc<AST>, String> {
+        if let Some((cached, ast)) = self.compiled.lock().map_err(|_| "Script cache poisoned".to_string())?.get(id) {
+            if cached == source {
+                return Ok(ast.clone());
+            }
+        }
+        let ast = Arc::new(engine().compile(source).map_err(|e| format!("Script error: {}", e))?);
+        if let Ok(mut compiled) = self.compiled.lock() {
+            compiled.insert(id.to_string(), (source.to_string(), ast.clone()));
+        }
+        Ok(ast)
+    }
+}
+
+/// Scripts have no file, network or process access; these limits stop
+/// runaway loops and allocations.
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut engine = Engine::new();
+        engine.set_max_operations(1_000_000);
+        engine.set_max_call_levels(64);
+        engine.set_max_expr_depths(64, 64);
+        engine.set_max_string_size(1024 * 1024);
+        engine.set_max_array_size(10_000);
+        engine.set_max_map_size(10_000);
+        engine
+    })
+}
+
+/// Runs the endpoint's script (its `response`) with `request` and `state` in
+/// scope.
+///
+/// The script's value is the response body, unless it is a map with any of
+/// `status`, `headers` and `body`, e.g. `#{ status: 201, headers: #{ "X-Id": "7" }, body: #{ id: 7 } }`.
+/// String bodies are sent as-is and anything else as JSON. Scripts run on the
+/// blocking pool, as they may take up to a million operations.
+pub async fn run(
+    states: &ScriptStates,
+    endpoint: &Endpoint,
+    incoming: &IncomingRequest,
+    status: StatusCode,
+) -> Result<MockResponse, String> {
+    let request = incoming.template_context()["request"].clone();
+    let (states, endpoint) = (states.clone(), endpoint.clone());
+    tokio::task::spawn_blocking(move || run_blocking(&states, &endpoint, request, status))
+        .await
+        .map_err(|e| format!("Script error: {}", e))?
+}
+
+fn run_blocking(
+    states: &ScriptStore,
+    endpoint: &Endpoint,
+    request: serde_json::Value,
+    status: StatusCode,
+) -> Result<MockResponse, String> {
+    let request = rhai::serde::to_dynamic(request).map_err(|e| format!("Script error: {}", e))?;
+    let ast = states.compile(&endpoint.id, &endpoint.response)?;
+    let shared = states.state(&endpoint.id)?;
+    let mut state = shared.lock().map_err(|_| "Script state poisoned".to_string())?;
+
+    let mut scope = Scope::new();
+    scope.push("request", request);
+    scope.push("state", state.clone());
+    let result = engine()
+        .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+        .map_err(|e| format!("Script error: {}", e))?;
+
+    if let Some(updated) = scope.get_value::<Map>("state") {
+        *state = updated;
+    }
+    drop(state);
+
+    let (status, headers, body) = match result.clone().try_cast::<Map>() {
+        Some(map) if ["status", "headers", "body"].iter().any(|key| map.contains_key(*key)) => {
+            let status = match map.get("status") {
+                Some(code) => code
+                    .as_int()
+                    .ok()
+                    .and_then(|code| u16::try_from(code).ok())
+                    .and_then(|code| StatusCode::from_u16(code).ok())
+                    .ok_or_else(|| "Script error: status must be an HTTP status code".to_string())?,
+                None => status,
+            };
+            let headers = match map.get("headers").and_then(|h| h.clone().try_cast::<Map>()) {
+                Some(headers) => headers
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                None => Vec::new(),
+            };
+            (status, headers, map.get("body").cloned().unwrap_or(Dynamic::UNIT))
+        }
+        _ => (status, Vec::new(), result),
+    };
+
+    let mut response = if body.is_string() {
+        MockResponse::new(status, &endpoint.content_type, body.to_string())
+    } else if body.is_unit() {
+        MockResponse::new(status, &endpoint.content_type, "")
+    } else {
+        let json: serde_json::Value =
+            rhai::serde::from_dynamic(&body).map_err(|e| format!("Script error: {}", e))?;
+        MockResponse::json(status, json.to_string())
+    };
+    response.apply_headers(&headers);
+    Ok(response)
+}
+
+/// Clears the `state` kept by one script endpoint, or by every script.
+#[tauri::command]
+pub async fn reset_script_state(state: tauri::State<'_, AppState>, endpoint_id: Option<String>) -> Result<(), String> {
+    let mut states = state.scripts.states.lock().map_err(|_| "Script state poisoned".to_string())?;
+    match endpoint_id {
+        Some(id) => {
+            states.remove(&id);
+        }
+        None => states.clear(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: &str = r#"if "n" in state { state.n += 1 } else { state.n = 1 }; state.n"#;
+
+    fn script(source: &str) -> Endpoint {
+        Endpoint::new("GET".to_string(), "/count".to_string(), 200, 0, source.to_string())
+    }
+
+    fn count(store: &ScriptStore, endpoint: &Endpoint) -> i64 {
+        store.state(&endpoint.id).unwrap().lock().unwrap().get("n").unwrap().as_int().unwrap()
+    }
+
+    #[test]
+    fn concurrent_runs_keep_every_state_update() {
+        let store = Arc::new(ScriptStore::default());
+        let endpoint = script(COUNTER);
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let (store, endpoint) = (store.clone(), endpoint.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        run_blocking(&store, &endpoint, serde_json::json!({}), StatusCode::OK).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(count(&store, &endpoint), 200);
+    }
+
+    #[test]
+    fn edited_script_is_recompiled() {
+        let store = ScriptStore::default();
+        let mut endpoint = script(r#""old""#);
+        let response = run_blocking(&store, &endpoint, serde_json::json!({}), StatusCode::OK).unwrap();
+        assert!(matches!(response.body, crate::server::MockBody::Bytes(ref b) if b.as_ref() == b"old"));
+
+        endpoint.response = r#""new""#.to_string();
+        let response = run_blocking(&store, &endpoint, serde_json::json!({}), StatusCode::OK).unwrap();
+        assert!(matches!(response.body, crate::server::MockBody::Bytes(ref b) if b.as_ref() == b"new"));
+    }
+}
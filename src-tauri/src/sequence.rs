@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, Endpoint};
+
+/// Calls served so far per endpoint id, shared by every running server.
+pub type SequenceCounters = Arc<RwLock<HashMap<String, usize>>>;
+
+/// One response in an endpoint's sequence; replaces the endpoint's own status,
+/// body and headers for that call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceStep {
+    pub status: u16,
+    #[serde(default)]
+    pub response: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl SequenceStep {
+    /// A copy of `endpoint` answering with this step instead of its own response.
+    pub fn apply(&self, endpoint: &Endpoint) -> Endpoint {
+        let mut applied = endpoint.clone();
+        applied.status = self.status;
+        applied.response = self.response.clone();
+        applied.headers.extend(self.headers.iter().cloned());
+        applied.response_source = crate::endpoints::ResponseSource::Inline;
+        applied
+    }
+}
+
+/// What happens once every step of a sequence has been served.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SequenceMode {
+    /// Start again from the first step.
+    #[default]
+    Loop,
+    /// Keep serving the last step until the sequence is reset.
+    Stick,
+}
+
+/// The endpoint as it should answer this call: a copy with the next step
+/// applied, or `None` when the endpoint has no sequence.
+pub async fn next(counters: &SequenceCounters, endpoint: &Endpoint) -> Option<Endpoint> {
+    if endpoint.sequence.is_empty() {
+        return None;
+    }
+
+    let call = {
+        let mut counters = counters.write().await;
+        let count = counters.entry(endpoint.id.clone()).or_insert(0);
+        let call = *count;
+        *count += 1;
+        call
+    };
+    let last = endpoint.sequence.len() - 1;
+    let step = match endpoint.sequence_mode {
+        SequenceMode::Loop => &endpoint.sequence[call % endpoint.sequence.len()],
+        SequenceMode::Stick => &endpoint.sequence[call.min(last)],
+    };
+
+    Some(step.apply(endpoint))
+}
+
+/// Rewinds one endpoint's sequence to its first step, or every sequence when no id is given.
+#[tauri::command]
+pub async fn reset_sequence(state: tauri::State<'_, AppState>, id: Option<String>) -> Result<(), String> {
+    let mut counters = state.sequences.write().await;
+    match id {
+        Some(id) => {
+            counters.remove(&id);
+        }
+        None => counters.clear(),
+    }
+    Ok(())
+}
@@ -1,28 +1,112 @@
 use axum::{
     Router,
-    http::{Method, StatusCode},
-    body::Body,
-    response::Response,
-    extract::State,
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Version, request::Parts},
+    body::{Body, Bytes},
+    response::{IntoResponse, Response},
+    extract::{ConnectInfo, FromRequestParts, State, ws::WebSocketUpgrade},
 };
-use std::sync::Arc;
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
 use tokio::sync::RwLock;
+use tokio_util::io::ReaderStream;
 use tower_http::cors::CorsLayer;
 
-use crate::endpoints::Endpoint;
+use crate::access::AccessTokens;
+use crate::concurrency::ConcurrencyLimits;
+use crate::connect_delay::DelayAcceptor;
+use crate::dependencies::{compose_body, total_delay};
+use crate::duplicates::DuplicateTracker;
+use crate::endpoints::{
+    AppState, BodyMatcher, Endpoint, EndpointKind, ExampleRequest, MatchType, ResponseSource, ServerSettings,
+    ValueMatchKind,
+};
+use crate::environments::SharedEnvironments;
+use crate::forward_proxy::{self, ForwardProxySettings};
+use crate::header_case::{ConnectionCasing, HeaderCaseAcceptor, HeaderCasing};
+use crate::idempotency::{IdempotencyCache, Lookup};
+use crate::inbox::Inbox;
+use crate::match_debug::{EndpointMatch, MatchReport};
+use crate::memory::SharedBudget;
+use crate::mutation::MutationRun;
+use crate::matching::{is_wildcard, json_path_lookup, PathMatching};
+use crate::pcap::PcapWriter;
+use crate::rate_limit::RateLimitCounters;
+use crate::recording::{RecordingSource, SharedRecording};
+use crate::request_log::{RequestLog, RequestLogEntry};
+use crate::resources::ResourceStore;
+use crate::s3::S3Settings;
+use crate::scenarios::ScenarioStates;
+use crate::scripting::ScriptStates;
+use crate::sequence::SequenceCounters;
+use crate::stats::{ServerStats, SharedStats};
+use crate::template::{self, TemplateCache};
+use crate::trace::Trace;
+use crate::websocket::WebSocketConfig;
+use crate::throttle::throttled;
+
+const MAX_BODY_BYTES: usize = 64 * 1024 * 1024;
+const DOCS_PATH: &str = "/__docs";
+/// `POST` a request description here to see which endpoint it would match.
+const MATCH_DEBUG_PATH: &str = "/__admin/match";
+/// Tauri event carrying a `RequestLogEntry` for every handled request.
+pub const REQUEST_EVENT: &str = "mock://request";
+/// Headers whose values are replaced by `REDACTED` in saved example requests.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+    "api-key",
+    crate::access::TOKEN_HEADER,
+];
+const REDACTED: &str = "[redacted]";
+/// Name of the server started without an explicit name.
+pub const DEFAULT_SERVER: &str = "default";
 
 pub struct ServerHandle {
     pub shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
     pub port: u16,
+    pub bind_addr: String,
     pub is_tls: bool,
+    /// Scheduled endpoint changes; cancelled when the handle is dropped.
+    pub timeline: Option<tokio::task::AbortHandle>,
+    /// Tag filter the server was started with.
+    pub tags: Vec<String>,
+    pub stats: Option<SharedStats>,
 }
 
 impl ServerHandle {
-    pub fn new(port: u16, is_tls: bool) -> Self {
+    pub fn new(port: u16, bind_addr: String, is_tls: bool) -> Self {
         Self {
             shutdown_tx: None,
             port,
+            bind_addr,
             is_tls,
+            timeline: None,
+            tags: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Base URL clients should use; wildcard binds are reached through localhost.
+    pub fn base_url(&self) -> String {
+        let protocol = if self.is_tls { "https" } else { "http" };
+        let host = match self.bind_addr.as_str() {
+            "0.0.0.0" | "::" => "localhost",
+            addr => addr,
+        };
+        format!("{}://{}:{}", protocol, host, self.port)
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        if let Some(timeline) = self.timeline.take() {
+            timeline.abort();
         }
     }
 }
@@ -30,36 +114,171 @@ impl ServerHandle {
 #[derive(Clone)]
 pub struct ServerState {
     pub app_state: Arc<RwLock<Vec<Endpoint>>>,
+    /// Compiled path and value regexes keyed by pattern; `None` marks an invalid pattern.
+    regex_cache: Arc<RwLock<HashMap<String, Option<Regex>>>>,
+    pcap: Option<Arc<Mutex<PcapWriter>>>,
+    mutation_runs: Arc<RwLock<HashMap<String, MutationRun>>>,
+    idempotency_cache: IdempotencyCache,
+    templates: Arc<RwLock<TemplateCache>>,
+    s3: Option<S3Settings>,
+    inbox: Inbox,
+    /// Live settings, so profiles applied while running take effect immediately.
+    settings: Arc<RwLock<ServerSettings>>,
+    duplicates: Arc<RwLock<DuplicateTracker>>,
+    request_log: RequestLog,
+    /// Receives a `mock://request` event per request when running inside the app.
+    app_handle: Option<tauri::AppHandle>,
+    access_tokens: AccessTokens,
+    sequences: SequenceCounters,
+    scenarios: ScenarioStates,
+    resources: ResourceStore,
+    environments: SharedEnvironments,
+    scripts: ScriptStates,
+    recording: SharedRecording,
+    memory: SharedBudget,
+    rate_limits: RateLimitCounters,
+    concurrency: ConcurrencyLimits,
+    http_client: reqwest::Client,
+    /// Endpoints pinned to another server are invisible to this one.
+    name: String,
+    /// Only endpoints with one of these tags are served; empty serves all.
+    tags: Vec<String>,
+    forward_proxy: Option<ForwardProxySettings>,
+    /// Where CONNECT tunnels for mocked hosts are sent.
+    local_addr: String,
+    stats: SharedStats,
+}
+
+impl ServerState {
+    pub fn new(app: &AppState, settings: &ServerSettings) -> Result<Self, String> {
+        let pcap = match &settings.pcap_path {
+            Some(path) if !path.is_empty() => {
+                Some(Arc::new(Mutex::new(PcapWriter::create(path, settings.port)?)))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            app_state: app.endpoints.clone(),
+            regex_cache: Arc::new(RwLock::new(HashMap::new())),
+            pcap,
+            mutation_runs: app.mutation_runs.clone(),
+            idempotency_cache: Arc::new(Mutex::new(HashMap::new())),
+            templates: app.templates.clone(),
+            s3: settings.s3.clone(),
+            inbox: app.inbox.clone(),
+            settings: app.server_settings.clone(),
+            duplicates: app.duplicates.clone(),
+            request_log: app.request_log.clone(),
+            app_handle: None,
+            access_tokens: app.access_tokens.clone(),
+            sequences: app.sequences.clone(),
+            scenarios: app.scenarios.clone(),
+            resources: app.resources.clone(),
+            environments: app.environments.clone(),
+            scripts: app.scripts.clone(),
+            memory: app.memory.clone(),
+            recording: app.recording.clone(),
+            rate_limits: Arc::new(Mutex::new(HashMap::new())),
+            concurrency: Arc::new(Mutex::new(HashMap::new())),
+            // Redirects go back to the client, which sees (and records) the 3xx
+            http_client: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap_or_default(),
+            name: DEFAULT_SERVER.to_string(),
+            tags: Vec::new(),
+            forward_proxy: settings.forward_proxy.clone(),
+            local_addr: match settings.bind_addr.as_str() {
+                "0.0.0.0" | "::" => format!("127.0.0.1:{}", settings.port),
+                addr if addr.contains(':') => format!("[{}]:{}", addr, settings.port),
+                addr => format!("{}:{}", addr, settings.port),
+            },
+            stats: ServerStats::new(),
+        })
+    }
+
+    /// Live counters, for the server's `ServerHandle`.
+    pub fn stats(&self) -> SharedStats {
+        self.stats.clone()
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    fn serves(&self, endpoint: &Endpoint) -> bool {
+        endpoint.enabled
+            && endpoint.server.as_ref().is_none_or(|server| *server == self.name)
+            && (self.tags.is_empty() || endpoint.tags.iter().any(|tag| self.tags.contains(tag)))
+    }
+
+    pub fn with_app_handle(mut self, app_handle: tauri::AppHandle) -> Self {
+        self.app_handle = Some(app_handle);
+        self
+    }
+
+    async fn regex_for(&self, pattern: &str) -> Option<Regex> {
+        if let Some(cached) = self.regex_cache.read().await.get(pattern) {
+            return cached.clone();
+        }
+
+        let compiled = match Regex::new(&format!("^(?:{})$", pattern)) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                eprintln!("Invalid regex '{}': {}", pattern, e);
+                None
+            }
+        };
+        self.regex_cache.write().await.insert(pattern.to_string(), compiled.clone());
+        compiled
+    }
 }
 
 pub async fn start_server(
     port: u16,
     bind_addr: String,
-    app_state: Arc<RwLock<Vec<Endpoint>>>,
+    server_state: ServerState,
 ) -> Result<tokio::sync::oneshot::Sender<()>, String> {
-    let server_state = ServerState {
-        app_state: app_state.clone(),
-    };
-
     let app = Router::new()
         .fallback(dynamic_handler)
-        .layer(CorsLayer::permissive())
-        .with_state(server_state);
+        .layer(CorsLayer::permissive());
+
+    let (title_case_headers, preserve_header_case, connect_delay_ms) = {
+        let settings = server_state.settings.read().await;
+        (settings.title_case_headers, settings.preserve_header_case, settings.connect_delay_ms)
+    };
+    let stats = server_state.stats();
+    let app = app.with_state(server_state);
 
     let addr = format!("{}:{}", bind_addr, port);
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
+    let listener = std::net::TcpListener::bind(&addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
         .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
 
+    // axum_server rather than axum::serve so the HTTP/1 writer can be configured
+    let acceptor = HeaderCaseAcceptor::new(DelayAcceptor::new(connect_delay_ms), preserve_header_case);
+    let mut server = axum_server::from_tcp(listener).acceptor(acceptor);
+    server.http_builder().http1().title_case_headers(title_case_headers);
+
+    let handle = stats.handle.clone();
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
 
+    let shutdown_handle = handle.clone();
     tokio::spawn(async move {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(async {
-                shutdown_rx.await.ok();
-            })
-            .await
-            .expect("Server error");
+        shutdown_rx.await.ok();
+        shutdown_handle.graceful_shutdown(None);
+    });
+    tokio::spawn(async move {
+        if let Err(e) = server.handle(handle).serve(app.into_make_service_with_connect_info::<SocketAddr>()).await {
+            stats.record_error(format!("Server error: {}", e));
+        }
     });
 
     Ok(shutdown_tx)
@@ -68,14 +287,15 @@ pub async fn start_server(
 pub async fn start_tls_server(
     port: u16,
     bind_addr: String,
-    app_state: Arc<RwLock<Vec<Endpoint>>>,
+    server_state: ServerState,
     cert_path: String,
     key_path: String,
 ) -> Result<tokio::sync::oneshot::Sender<()>, String> {
-    let server_state = ServerState {
-        app_state: app_state.clone(),
+    let (title_case_headers, preserve_header_case, connect_delay_ms) = {
+        let settings = server_state.settings.read().await;
+        (settings.title_case_headers, settings.preserve_header_case, settings.connect_delay_ms)
     };
-
+    let stats = server_state.stats();
     let app = Router::new()
         .fallback(dynamic_handler)
         .layer(CorsLayer::permissive())
@@ -91,13 +311,20 @@ pub async fn start_tls_server(
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
 
     tokio::spawn(async move {
-        let server = axum_server::bind_rustls(addr.parse().unwrap(), config)
-            .serve(app.into_make_service());
+        // The delay runs before the TLS handshake, so it counts as connection setup
+        let acceptor = axum_server::tls_rustls::RustlsAcceptor::new(config).acceptor(DelayAcceptor::new(connect_delay_ms));
+        let acceptor = crate::tls_info::TlsInfoAcceptor::new(acceptor);
+        let acceptor = HeaderCaseAcceptor::new(acceptor, preserve_header_case);
+        let mut server = axum_server::bind(addr.parse().unwrap())
+            .acceptor(acceptor)
+            .handle(stats.handle.clone());
+        server.http_builder().http1().title_case_headers(title_case_headers);
+        let server = server.serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
         tokio::select! {
             result = server => {
                 if let Err(e) = result {
-                    eprintln!("Server error: {}", e);
+                    stats.record_error(format!("Server error: {}", e));
                 }
             }
             _ = shutdown_rx => {
@@ -109,34 +336,1105 @@ pub async fn start_tls_server(
     Ok(shutdown_tx)
 }
 
+/// The parts of an incoming request that endpoint matching looks at.
+#[derive(Clone)]
+pub struct IncomingRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// `body` parsed as JSON, when it is valid JSON.
+    pub json_body: Option<serde_json::Value>,
+    /// Peer address of the connection, when known.
+    pub remote_addr: Option<SocketAddr>,
+    pub http_version: Version,
+    /// Handshake details, for requests received over TLS.
+    pub tls: Option<crate::tls_info::TlsInfo>,
+}
+
+impl IncomingRequest {
+    pub(crate) fn from_parts(parts: &Parts, body: Bytes) -> Self {
+        let query = parts
+            .uri
+            .query()
+            .map(|q| url::form_urlencoded::parse(q.as_bytes()).into_owned().collect())
+            .unwrap_or_default();
+
+        Self {
+            method: parts.method.clone(),
+            path: parts.uri.path().to_string(),
+            query,
+            headers: parts.headers.clone(),
+            json_body: serde_json::from_slice(&body).ok(),
+            body,
+            remote_addr: parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|info| info.0),
+            http_version: parts.version,
+            tls: parts.extensions.get::<crate::tls_info::TlsInfo>().cloned(),
+        }
+    }
+
+    /// Header names and values, with non-UTF-8 values converted lossily.
+    pub fn header_pairs(&self) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+            .collect()
+    }
+
+    /// The request as saved with the endpoint, with credentials masked since
+    /// examples end up in exported projects and docs.
+    pub fn example_request(&self) -> ExampleRequest {
+        let redact = |pairs: Vec<(String, String)>, sensitive: &[&str]| {
+            pairs
+                .into_iter()
+                .map(|(name, value)| match sensitive.iter().any(|s| s.eq_ignore_ascii_case(&name)) {
+                    true => (name, REDACTED.to_string()),
+                    false => (name, value),
+                })
+                .collect()
+        };
+        ExampleRequest {
+            method: self.method.to_string(),
+            path: self.path.clone(),
+            query: redact(self.query.clone(), &[crate::access::TOKEN_QUERY_PARAM]),
+            headers: redact(self.header_pairs(), SENSITIVE_HEADERS),
+            body: String::from_utf8_lossy(&self.body).to_string(),
+            captured_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Data exposed to response templates as `{{request.*}}` and `{{faker.*}}`.
+    pub fn template_context(&self) -> serde_json::Value {
+        let query: serde_json::Map<String, serde_json::Value> = self
+            .query
+            .iter()
+            .map(|(name, value)| (name.clone(), serde_json::Value::from(value.as_str())))
+            .collect();
+        let headers: serde_json::Map<String, serde_json::Value> = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.as_str().to_string(), serde_json::Value::from(v)))
+            })
+            .collect();
+        let body = self
+            .json_body
+            .clone()
+            .unwrap_or_else(|| serde_json::Value::from(String::from_utf8_lossy(&self.body).to_string()));
+
+        let faker = crate::faker::context();
+        serde_json::json!({
+            "request": {
+                "method": self.method.as_str(),
+                "path": self.path,
+                "query": query,
+                "headers": headers,
+                "body": body,
+            },
+            "faker": faker.clone(),
+            "fake": faker,
+        })
+    }
+}
+
+/// A response produced by the mock before it is sent.
+#[derive(Clone)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: MockBody,
+    /// Closest endpoints for an unmatched request, recorded in the request log.
+    pub suggestions: Vec<String>,
+    /// Throttles the body to this rate when set.
+    pub bytes_per_sec: Option<u64>,
+    /// The endpoint that produced this response, if any.
+    pub endpoint_id: Option<String>,
+    /// Whether the exchange is logged, emitted as an event and written to the PCAP file.
+    pub capture: bool,
+    /// Whether file parts of the request are recorded (and saved to the upload
+    /// directory): only for POSTs that reached an endpoint's response.
+    pub record_uploads: bool,
+}
+
+#[derive(Clone)]
+pub enum MockBody {
+    Bytes(Bytes),
+    /// Streamed from disk when the response is sent.
+    File(String),
+    /// Server-sent events, streamed when the response is sent.
+    Events(crate::sse::SseConfig),
+    /// One length-prefixed gRPC message, sent with `grpc-status` trailers.
+    Grpc(Bytes),
+    /// The start of a body, after which the connection is dropped.
+    Reset(Bytes),
+}
+
+impl MockResponse {
+    pub fn new(status: StatusCode, content_type: &str, body: impl Into<Bytes>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body: MockBody::Bytes(body.into()),
+            bytes_per_sec: None,
+            endpoint_id: None,
+            capture: true,
+            record_uploads: false,
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn json(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self::new(status, "application/json", body)
+    }
+
+    pub fn file(status: StatusCode, content_type: &str, path: String) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body: MockBody::File(path),
+            bytes_per_sec: None,
+            endpoint_id: None,
+            capture: true,
+            record_uploads: false,
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Appends configured headers, replacing any default header of the same name.
+    pub(crate) fn apply_headers(&mut self, headers: &[(String, String)]) {
+        self.headers
+            .retain(|(name, _)| !headers.iter().any(|(custom, _)| custom.eq_ignore_ascii_case(name)));
+        self.headers.extend(headers.iter().cloned());
+    }
+
+    async fn into_response(self) -> Response<Body> {
+        let mut builder = Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            // Templated, scripted and middleware headers can carry anything
+            if HeaderName::from_bytes(name.as_bytes()).is_err() || HeaderValue::from_str(value).is_err() {
+                return internal_error(format!("Response header '{}' is not a valid HTTP header", name.escape_default()));
+            }
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let cased: Vec<String> = self
+            .headers
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.bytes().any(|b| b.is_ascii_uppercase()))
+            .cloned()
+            .collect();
+        builder = builder.extension(HeaderCasing(cased));
+
+        let rate = self.bytes_per_sec.filter(|rate| *rate > 0);
+        let body = match self.body {
+            MockBody::Bytes(bytes) => match rate {
+                Some(rate) => {
+                    builder = builder.header("Content-Length", bytes.len());
+                    throttled(std::io::Cursor::new(bytes), rate)
+                }
+                None => Body::from(bytes),
+            },
+            MockBody::File(path) => match tokio::fs::File::open(&path).await {
+                Ok(file) => {
+                    if let Ok(metadata) = file.metadata().await {
+                        builder = builder.header("Content-Length", metadata.len());
+                    }
+                    match rate {
+                        Some(rate) => throttled(file, rate),
+                        None => Body::from_stream(ReaderStream::new(file)),
+                    }
+                }
+                Err(e) => return internal_error(format!("Failed to open response file {}: {}", path, e)),
+            },
+            MockBody::Events(config) => crate::sse::stream(config),
+            MockBody::Grpc(message) => crate::grpc::body(message),
+            MockBody::Reset(sent) => crate::fault::reset_body(sent),
+        };
+        builder
+            .body(body)
+            .unwrap_or_else(|e| internal_error(format!("Failed to build the response: {}", e)))
+    }
+}
+
+fn internal_error(message: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(serde_json::json!({ "error": message }).to_string()));
+    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    response
+        .headers_mut()
+        .insert(axum::http::header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
 async fn dynamic_handler(
     State(state): State<ServerState>,
     req: axum::extract::Request,
 ) -> Response<Body> {
-    let method = req.method().clone();
-    let path = req.uri().path().to_string();
+    let in_flight = state.stats.begin_request();
+    let casing = req
+        .extensions()
+        .get::<ConnectionCasing>()
+        .filter(|_| req.version() <= axum::http::Version::HTTP_11)
+        .cloned();
+    let mut response = serve(state, req).await;
+    if let Some(casing) = casing {
+        casing.prepare(&mut response);
+    }
+    in_flight.hold(response)
+}
+
+async fn serve(state: ServerState, req: axum::extract::Request) -> Response<Body> {
+
+    if let Some(forward_proxy) = &state.forward_proxy {
+        if req.method() == Method::CONNECT {
+            // Tunnels need a token as much as plain requests do
+            let (parts, body) = req.into_parts();
+            if let Err(denied) = check_access(&state, &IncomingRequest::from_parts(&parts, Bytes::new())).await {
+                return denied.into_response().await;
+            }
+            let req = axum::extract::Request::from_parts(parts, body);
+            return crate::forward_proxy::connect(forward_proxy, req, &state.local_addr);
+        }
+    }
+
+    if crate::websocket::is_upgrade(req.headers()) {
+        if let Some(config) = find_websocket(&state, req.uri().path()).await {
+            let (mut parts, _) = req.into_parts();
+            let incoming = IncomingRequest::from_parts(&parts, Bytes::new());
+            if let Err(denied) = check_access(&state, &incoming).await {
+                log_request(&state, RequestLogEntry::new(&incoming, &denied, 0)).await;
+                return denied.into_response().await;
+            }
+            let response = match WebSocketUpgrade::from_request_parts(&mut parts, &state).await {
+                Ok(upgrade) => upgrade.on_upgrade(move |socket| crate::websocket::serve(socket, config)),
+                Err(rejection) => rejection.into_response(),
+            };
+            let handshake = MockResponse::new(response.status(), "", Bytes::new());
+            log_request(&state, RequestLogEntry::new(&incoming, &handshake, 0)).await;
+            return response;
+        }
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return MockResponse::json(
+                StatusCode::BAD_REQUEST,
+                serde_json::json!({ "error": format!("Failed to read request body: {}", e) }).to_string(),
+            )
+            .into_response()
+            .await;
+        }
+    };
+    let (body, raw_size) = match crate::decompress::decode(&mut parts.headers, body, MAX_BODY_BYTES) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            return MockResponse::json(StatusCode::BAD_REQUEST, serde_json::json!({ "error": e }).to_string())
+                .into_response()
+                .await;
+        }
+    };
+    let incoming = IncomingRequest::from_parts(&parts, body);
+
+    let upstream = match state.forward_proxy.as_ref().map(|proxy| forward_proxy::route(proxy, &parts.uri)) {
+        Some(forward_proxy::Route::Rejected) => {
+            return forward_proxy::rejected(parts.uri.host().unwrap_or_default());
+        }
+        Some(forward_proxy::Route::Upstream(origin)) => Some(origin),
+        _ => None,
+    };
+
+    let window_ms = state.settings.read().await.duplicate_window_ms;
+    if window_ms > 0 {
+        state.duplicates.write().await.record(&incoming, Duration::from_millis(window_ms));
+    }
+
+    let started = std::time::Instant::now();
+    let response = match check_access(&state, &incoming).await {
+        Ok(remember) => {
+            let middleware = state.settings.read().await.middleware.clone();
+            let mut response = match crate::middleware::apply_request(&middleware, &incoming) {
+                Err(rejected) => rejected,
+                Ok(routed) => match &upstream {
+                    Some(origin) => crate::proxy::forward(&state.http_client, origin, &routed)
+                        .await
+                        .unwrap_or_else(|e| {
+                            MockResponse::json(StatusCode::BAD_GATEWAY, serde_json::json!({ "error": e }).to_string())
+                        }),
+                    None => respond(&state, &routed).await,
+                },
+            };
+            crate::middleware::apply_response(&middleware, &incoming, &mut response);
+            if let Some(rate_limit) = &state.settings.read().await.rate_limit_headers {
+                crate::rate_limit::annotate(rate_limit, &state.rate_limits, &incoming, &mut response);
+            }
+            if let Some(token) = remember {
+                response.headers.push((
+                    "Set-Cookie".to_string(),
+                    format!("{}={}; Path=/; HttpOnly; SameSite=Lax", crate::access::TOKEN_COOKIE, token),
+                ));
+            }
+            response
+        }
+        Err(denied) => denied,
+    };
+    if !response.capture {
+        return response.into_response().await;
+    }
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let mut entry = RequestLogEntry::new(&incoming, &response, latency_ms);
+    entry.raw_size = raw_size;
+    if response.record_uploads {
+        let upload_dir = state.settings.read().await.upload_dir.clone();
+        entry.uploads = crate::multipart::capture(upload_dir.as_deref(), &incoming).await;
+    }
+    log_request(&state, entry).await;
+
+    if let Some(pcap) = &state.pcap {
+        let request_bytes = crate::pcap::serialize_request(&parts, &incoming.body);
+        let response_bytes = crate::pcap::serialize_response(&response);
+        if let Ok(mut writer) = pcap.lock() {
+            if let Err(e) = writer.write_exchange(&request_bytes, &response_bytes) {
+                state.stats.record_error(format!("Failed to write capture: {}", e));
+            }
+        }
+    }
+
+    response.into_response().await
+}
+
+/// Adds the entry to the request log and announces it to the UI.
+async fn log_request(state: &ServerState, entry: RequestLogEntry) {
+    if let Some(app_handle) = &state.app_handle {
+        if let Err(e) = app_handle.emit(REQUEST_EVENT, &entry) {
+            state.stats.record_error(format!("Failed to emit request event: {}", e));
+        }
+    }
+    crate::request_log::push(&state.request_log, &state.memory, entry).await;
+}
+
+/// Enforces access tokens when required, returning the token to remember in
+/// a cookie when it was passed as `?access_token=`.
+async fn check_access(state: &ServerState, incoming: &IncomingRequest) -> Result<Option<String>, MockResponse> {
+    if !state.settings.read().await.require_access_token {
+        return Ok(None);
+    }
+    match crate::access::presented_token(incoming) {
+        Some(token) if crate::access::is_valid(&state.access_tokens, &token).await => {
+            let from_query = incoming.query.iter().any(|(name, _)| name == crate::access::TOKEN_QUERY_PARAM);
+            Ok(from_query.then_some(token))
+        }
+        _ => Err(MockResponse::json(
+            StatusCode::UNAUTHORIZED,
+            r#"{"error": "A valid access token is required"}"#,
+        )),
+    }
+}
+
+/// Answers the request, adding `X-FakeAPI-Trace` when the client sends
+/// `X-FakeAPI-Debug: 1`.
+async fn respond(state: &ServerState, incoming: &IncomingRequest) -> MockResponse {
+    let mut trace = Trace::new(incoming);
+    if trace.enabled {
+        // Explained up front, as answering may move scenarios on
+        let paths = state.settings.read().await.path_matching;
+        trace.rules = explain(state, incoming)
+            .await
+            .endpoints
+            .into_iter()
+            .filter(|e| paths.matches(&e.path, &incoming.path))
+            .filter_map(|e| Some(format!("{}: {}", e.id, e.rejected?)))
+            .collect();
+    }
+    let mut response = resolve(state, incoming, &mut trace).await;
+    trace.endpoint_id = response.endpoint_id.clone();
+    trace.attach(&mut response);
+    response
+}
+
+async fn resolve(state: &ServerState, incoming: &IncomingRequest, trace: &mut Trace) -> MockResponse {
+    if let Some(s3) = &state.s3 {
+        if let Some(api_path) = s3.strip_prefix(&incoming.path) {
+            return crate::s3::handle(s3, api_path, incoming).await;
+        }
+    }
+
+    if incoming.method == Method::POST && incoming.path == MATCH_DEBUG_PATH {
+        return crate::match_debug::respond(state, incoming).await;
+    }
 
+    if let Some(provider) = &state.settings.read().await.oauth {
+        if let Some(response) = crate::oauth::route(provider, incoming) {
+            return response;
+        }
+    }
+
+    if incoming.method == Method::GET && incoming.path.starts_with(DOCS_PATH) {
+        if let Some(page) = docs_response(state, incoming).await {
+            return page;
+        }
+    }
+
+    if let Some(limit) = &state.settings.read().await.rate_limit {
+        if let Some(limited) = crate::rate_limit::enforce(limit, &state.rate_limits, "global", incoming) {
+            return limited;
+        }
+    }
+
+    // Resolve everything under the read lock, then release it before sleeping
+    let (response, delay, capture, admission) = {
+        let endpoints = state.app_state.read().await;
+
+        if let Some(wsdl) = find_wsdl(state, &endpoints, incoming).await {
+            return MockResponse::new(StatusCode::OK, crate::soap::SOAP_CONTENT_TYPE, wsdl);
+        }
+
+        let Some(endpoint) = find_endpoint(state, &endpoints, incoming).await else {
+            drop(endpoints);
+            return unmatched(state, incoming).await;
+        };
+
+        if let Some(limit) = &endpoint.rate_limit {
+            let scope = format!("endpoint:{}", endpoint.id);
+            if let Some(mut limited) = crate::rate_limit::enforce(limit, &state.rate_limits, &scope, incoming) {
+                limited.endpoint_id = Some(endpoint.id.clone());
+                return limited;
+            }
+        }
+        let denied = {
+            let settings = state.settings.read().await;
+            let api_key = match &endpoint.api_key_auth {
+                Some(auth) => crate::api_key::authorize(auth, incoming).err(),
+                None => settings.api_key_auth.as_ref().and_then(|auth| crate::api_key::authorize(auth, incoming).err()),
+            };
+            api_key.or_else(|| match &endpoint.bearer_auth {
+                Some(auth) => crate::bearer::authorize(auth, incoming).err(),
+                None => settings.bearer_auth.as_ref().and_then(|auth| crate::bearer::authorize(auth, incoming).err()),
+            })
+        };
+        if let Some(mut denied) = denied {
+            denied.endpoint_id = Some(endpoint.id.clone());
+            return denied;
+        }
+        let admission = match &endpoint.concurrency {
+            Some(limit) => match crate::concurrency::admit(&state.concurrency, &endpoint.id, limit) {
+                crate::concurrency::Admission::Rejected(mut rejected) => {
+                    rejected.endpoint_id = Some(endpoint.id.clone());
+                    return rejected;
+                }
+                admitted => Some(admitted),
+            },
+            None => None,
+        };
+
+        if endpoint.kind == EndpointKind::Inbox {
+            crate::inbox::record(&state.inbox, &state.memory, endpoint, incoming).await;
+            crate::recording::capture(&state.recording, RecordingSource::Inbox, incoming, None).await;
+        }
+        // Replays leave scenarios, sequences and scripts where they are
+        let mut idempotency_claim = match idempotency_key(endpoint, incoming) {
+            Some(key) => match crate::idempotency::lookup(&state.idempotency_cache, key) {
+                Lookup::Replay(mut replay) => {
+                    replay.headers.push(("Idempotent-Replayed".to_string(), "true".to_string()));
+                    trace.step(|| "Replayed the response cached for this Idempotency-Key".to_string());
+                    return replay;
+                }
+                Lookup::Conflict(mut conflict) => {
+                    conflict.endpoint_id = Some(endpoint.id.clone());
+                    return conflict;
+                }
+                Lookup::Claimed(claim) => Some(claim),
+            },
+            None => None,
+        };
+        if let Some(scenario) = &endpoint.scenario {
+            crate::scenarios::transition(&state.scenarios, scenario).await;
+            if let Some(new_state) = &scenario.new_state {
+                trace.step(|| format!("Scenario '{}' moved to '{}'", scenario.name, new_state));
+            }
+        }
+
+        let chosen = match crate::sequence::next(&state.sequences, endpoint).await {
+            Some(step) => {
+                trace.step(|| format!("Sequence step answered with {}", step.status));
+                Some(step)
+            }
+            None => crate::variants::pick(endpoint).inspect(|variant| {
+                trace.step(|| format!("Variant answered with {}", variant.status));
+            }),
+        };
+        let endpoint = &chosen.unwrap_or_else(|| endpoint.clone());
+        let mut composed = endpoint.compose_dependencies.then(|| compose_body(&endpoints, endpoint));
+        let mut delay = total_delay(&endpoints, endpoint);
+        // Scripts, resources and templates run without holding up edits
+        drop(endpoints);
+
+        let templated = (endpoint.response_source == ResponseSource::Inline && template::is_template(&endpoint.response))
+            || endpoint.headers.iter().any(|(_, value)| template::is_template(value));
+        let context = if templated {
+            template_context(state, incoming).await
+        } else {
+            serde_json::Value::Null
+        };
+        if templated {
+            let sources = endpoint.headers.iter().map(|(_, value)| value.as_str());
+            trace.record_variables(std::iter::once(endpoint.response.as_str()).chain(sources), &context);
+        }
+
+        let status = StatusCode::from_u16(endpoint.status).unwrap_or(StatusCode::OK);
+        let mut response = if endpoint.kind == EndpointKind::Resource {
+            crate::resources::handle(&state.resources, &state.memory, endpoint, incoming).await
+        } else if let (EndpointKind::Static, Some(config)) = (endpoint.kind, &endpoint.static_files) {
+            crate::static_files::handle(config, &endpoint.path, incoming).await
+        } else {
+            match (endpoint.response_source, &endpoint.file_path) {
+                (ResponseSource::File, Some(path)) => {
+                    let strict = if endpoint.content_type.contains("json") {
+                        crate::lenient_json::strict_file(path).await
+                    } else {
+                        None
+                    };
+                    match strict {
+                        Some(body) => MockResponse::new(status, &endpoint.content_type, body),
+                        None => MockResponse::file(status, &endpoint.content_type, path.clone()),
+                    }
+                }
+                (ResponseSource::Events, _) => {
+                    let mut response = MockResponse::new(status, crate::sse::EVENT_STREAM_CONTENT_TYPE, "");
+                    response.body = MockBody::Events(endpoint.sse.clone().unwrap_or_default());
+                    response.headers.push(("Cache-Control".to_string(), "no-cache".to_string()));
+                    response
+                }
+                (ResponseSource::Script, _) => match crate::scripting::run(&state.scripts, endpoint, incoming, status).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        return MockResponse::json(
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            serde_json::json!({ "error": e }).to_string(),
+                        );
+                    }
+                },
+                _ => {
+                    let body = match next_mutation(state, &endpoint.id).await {
+                        Some(mutated) => {
+                            trace.step(|| "Body replaced by the next mutation".to_string());
+                            mutated
+                        }
+                        None if endpoint.compose_dependencies => composed.take().unwrap_or_default(),
+                        None if template::is_template(&endpoint.response) => {
+                            match template::render(&state.templates, &endpoint.id, &endpoint.response, &context).await {
+                                Ok(rendered) => rendered,
+                                Err(e) => {
+                                    return MockResponse::json(
+                                        StatusCode::INTERNAL_SERVER_ERROR,
+                                        serde_json::json!({ "error": e }).to_string(),
+                                    );
+                                }
+                            }
+                        }
+                        None => endpoint.response.clone(),
+                    };
+                    if let Some(grpc) = &endpoint.grpc {
+                        crate::grpc::build_response(grpc, &endpoint.path, &body)
+                    } else if let Some(jsonrpc) = &endpoint.jsonrpc {
+                        crate::jsonrpc::build_response(jsonrpc, incoming, &body)
+                    } else if let Some(soap) = &endpoint.soap {
+                        crate::soap::build_response(soap, status, body)
+                    } else {
+                        MockResponse::new(status, &endpoint.content_type, body)
+                    }
+                }
+            }
+        };
+        if endpoint.download {
+            let filename = endpoint
+                .download_name
+                .clone()
+                .or_else(|| {
+                    let path = endpoint.file_path.as_ref()?;
+                    Some(std::path::Path::new(path).file_name()?.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| "download".to_string());
+            response
+                .headers
+                .push(("Content-Disposition".to_string(), content_disposition(&filename)));
+        }
+        match template::render_headers(&state.templates, &endpoint.id, &endpoint.headers, &context).await {
+            Ok(headers) => response.apply_headers(&headers),
+            Err(e) => {
+                return MockResponse::json(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    serde_json::json!({ "error": e }).to_string(),
+                );
+            }
+        }
+
+        if let Some(profile) = crate::profiles::resolve(&*state.settings.read().await, endpoint) {
+            let (extra_delay, failed) = profile.roll();
+            delay += extra_delay;
+            trace.step(|| format!("Profile '{}' added {} ms", profile.name, extra_delay));
+            if failed {
+                trace.step(|| format!("Profile '{}' simulated a failure", profile.name));
+                // Simulated failures are never replayed for the same Idempotency-Key
+                idempotency_claim = None;
+                response = profile.failure_response();
+            }
+            response.bytes_per_sec = profile.bytes_per_sec;
+        }
+        if endpoint.bytes_per_sec.is_some() {
+            response.bytes_per_sec = endpoint.bytes_per_sec;
+        }
+        if let Some(fault) = endpoint.fault {
+            // Injected faults are never replayed for the same Idempotency-Key either
+            idempotency_claim = None;
+            crate::fault::inject(fault, &mut response);
+            trace.step(|| format!("Injected the {:?} fault", fault));
+        }
+        if let Some(signature) = &endpoint.signature {
+            crate::signing::sign(signature, &mut response);
+        }
+        response.endpoint_id = Some(endpoint.id.clone());
+        response.capture = endpoint.log_requests;
+        response.record_uploads = incoming.method == Method::POST;
+
+        if let (Some(claim), MockBody::Bytes(_)) = (idempotency_claim, &response.body) {
+            claim.complete(&response);
+        }
+        (response, delay, endpoint.capture_example.then(|| endpoint.id.clone()), admission)
+    };
+
+    if let Some(id) = capture {
+        if let Some(endpoint) = state.app_state.write().await.iter_mut().find(|e| e.id == id) {
+            endpoint.example_request = Some(incoming.example_request());
+        }
+    }
+
+    // The slot is held through the delay, which stands in for the endpoint's work
+    let _slot = match admission {
+        Some(admission) => admission.wait().await,
+        None => None,
+    };
+    trace.delay_ms = delay;
+    if delay > 0 {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+
+    response
+}
+
+/// `attachment` with a plain `filename` for old clients and an RFC 5987
+/// `filename*` when the name isn't plain ASCII.
+fn content_disposition(filename: &str) -> String {
+    let plain: String = filename
+        .chars()
+        .map(|c| if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' { c } else { '_' })
+        .collect();
+    if plain == filename {
+        format!("attachment; filename=\"{}\"", plain)
+    } else {
+        let encoded = percent_encoding::utf8_percent_encode(filename, percent_encoding::NON_ALPHANUMERIC);
+        format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", plain, encoded)
+    }
+}
+
+/// The request context plus `state`, the current state of every scenario, and
+/// `env`, the active environment's variables.
+async fn template_context(state: &ServerState, incoming: &IncomingRequest) -> serde_json::Value {
+    let mut context = incoming.template_context();
+    let scenarios: serde_json::Map<String, serde_json::Value> = state
+        .scenarios
+        .read()
+        .await
+        .iter()
+        .map(|(name, current)| (name.clone(), serde_json::Value::from(current.as_str())))
+        .collect();
+    context["state"] = serde_json::Value::Object(scenarios);
+    context["env"] = state.environments.read().await.active_variables();
+    context
+}
+
+/// Proxies to the upstream when proxy mode is on, recording the response if
+/// asked to and the project isn't read-only; otherwise a 404.
+async fn unmatched(state: &ServerState, incoming: &IncomingRequest) -> MockResponse {
+    let (proxy, read_only) = {
+        let settings = state.settings.read().await;
+        (settings.proxy.clone(), settings.read_only)
+    };
+    let Some(mut proxy) = proxy else {
+        return not_found(state, incoming).await;
+    };
+    proxy.upstream_url = state.environments.read().await.substitute(&proxy.upstream_url);
+
+    match crate::proxy::forward(&state.http_client, &proxy.upstream_url, incoming).await {
+        Ok(response) => {
+            crate::recording::capture(&state.recording, RecordingSource::Proxy, incoming, Some(&response)).await;
+            if proxy.record && !read_only {
+                let mut endpoints = state.app_state.write().await;
+                let recorded = endpoints
+                    .iter()
+                    .any(|e| e.method.eq_ignore_ascii_case(incoming.method.as_str()) && e.path == incoming.path);
+                if !recorded {
+                    endpoints.push(crate::proxy::recorded_endpoint(incoming, &response));
+                }
+            }
+            response
+        }
+        Err(e) => MockResponse::json(StatusCode::BAD_GATEWAY, serde_json::json!({ "error": e }).to_string()),
+    }
+}
+
+/// The fallback 404, listing the closest endpoints when suggestions are on.
+async fn not_found(state: &ServerState, incoming: &IncomingRequest) -> MockResponse {
+    let (enabled, paths) = {
+        let settings = state.settings.read().await;
+        (settings.not_found_suggestions, settings.path_matching)
+    };
+    if !enabled {
+        return MockResponse::json(StatusCode::NOT_FOUND, r#"{"error": "Endpoint not found"}"#);
+    }
+
+    let suggestions = {
+        let endpoints = state.app_state.read().await;
+        crate::suggestions::closest(endpoints.iter().filter(|e| state.serves(e)), incoming, &paths)
+    };
+    let mut response = MockResponse::json(
+        StatusCode::NOT_FOUND,
+        serde_json::json!({ "error": "Endpoint not found", "suggestions": suggestions }).to_string(),
+    );
+    response.suggestions = suggestions.iter().map(ToString::to_string).collect();
+    response
+}
+
+/// `/__docs` lists the endpoints with try-it forms; `/__docs/openapi.json` is
+/// the same list as an OpenAPI document.
+async fn docs_response(state: &ServerState, incoming: &IncomingRequest) -> Option<MockResponse> {
+    let endpoints: Vec<Endpoint> = state
+        .app_state
+        .read()
+        .await
+        .iter()
+        .filter(|e| state.serves(e))
+        .cloned()
+        .collect();
+    match incoming.path.trim_end_matches('/') {
+        DOCS_PATH => Some(MockResponse::new(
+            StatusCode::OK,
+            "text/html; charset=utf-8",
+            crate::docs::render_page("Mock API", &endpoints, true),
+        )),
+        path if path == format!("{}/openapi.json", DOCS_PATH) => Some(MockResponse::json(
+            StatusCode::OK,
+            crate::openapi::spec_from_endpoints("Mock API", &endpoints).to_string(),
+        )),
+        _ => None,
+    }
+}
+
+/// The most specific websocket endpoint for `path`.
+async fn find_websocket(state: &ServerState, path: &str) -> Option<WebSocketConfig> {
+    let paths = state.settings.read().await.path_matching;
     let endpoints = state.app_state.read().await;
+    endpoints
+        .iter()
+        .filter(|e| e.kind == EndpointKind::WebSocket && state.serves(e) && paths.matches(&e.path, path))
+        .max_by_key(|e| !is_wildcard(&e.path))
+        .map(|e| e.websocket.clone().unwrap_or_default())
+}
 
+/// Serves `GET <path>?wsdl` from any SOAP endpoint on that path that has a WSDL.
+async fn find_wsdl(state: &ServerState, endpoints: &[Endpoint], incoming: &IncomingRequest) -> Option<String> {
+    if incoming.method != Method::GET || !incoming.query.iter().any(|(name, _)| name.eq_ignore_ascii_case("wsdl")) {
+        return None;
+    }
+
+    let paths = state.settings.read().await.path_matching;
+    for endpoint in endpoints.iter().filter(|e| state.serves(e)) {
+        let Some(wsdl) = endpoint.soap.as_ref().and_then(|soap| soap.wsdl.as_ref()) else {
+            continue;
+        };
+        let path_match = match endpoint.match_type {
+            MatchType::Exact => paths.matches(&endpoint.path, &incoming.path),
+            MatchType::Regex => regex_matches(state, &paths, &endpoint.path, &incoming.path).await,
+        };
+        if path_match {
+            return Some(wsdl.clone());
+        }
+    }
+    None
+}
+
+/// Cache key for POSTs to idempotent endpoints that carry an `Idempotency-Key` header.
+fn idempotency_key(endpoint: &Endpoint, incoming: &IncomingRequest) -> Option<(String, String)> {
+    if !endpoint.idempotent || incoming.method != Method::POST {
+        return None;
+    }
+    let key = incoming.headers.get("Idempotency-Key")?.to_str().ok()?;
+    Some((endpoint.id.clone(), key.to_string()))
+}
+
+/// Takes the next mutated body of an active mutation test, ending the run once exhausted.
+async fn next_mutation(state: &ServerState, endpoint_id: &str) -> Option<String> {
+    let mut runs = state.mutation_runs.write().await;
+    let run = runs.get_mut(endpoint_id)?;
+    let body = run.mutations.get(run.next).map(|m| m.body.to_string());
+    run.next += 1;
+    if run.next >= run.mutations.len() {
+        runs.remove(endpoint_id);
+    }
+    body
+}
+
+/// Regex endpoint paths honor the case option through `(?i)`, and the
+/// trailing-slash and decoding options by also trying the normalized path.
+async fn regex_matches(state: &ServerState, paths: &PathMatching, pattern: &str, path: &str) -> bool {
+    let regex = if paths.case_insensitive {
+        state.regex_for(&format!("(?i){}", pattern)).await
+    } else {
+        state.regex_for(pattern).await
+    };
+    let Some(re) = regex else {
+        return false;
+    };
+    let normalized = paths.normalize(path);
+    re.is_match(path) || (normalized != path && re.is_match(&normalized))
+}
+
+/// Picks the most specific endpoint: literal paths beat wildcard and regex
+/// patterns, then endpoints with more satisfied conditions win.
+async fn find_endpoint<'a>(
+    state: &ServerState,
+    endpoints: &'a [Endpoint],
+    incoming: &IncomingRequest,
+) -> Option<&'a Endpoint> {
+    let paths = state.settings.read().await.path_matching;
+    let mut matched: Option<(&Endpoint, ((u8, usize), bool))> = None;
+    for endpoint in endpoints {
+        if let Ok(score) = evaluate(state, &paths, endpoint, incoming).await {
+            let rank = (score, explicit_method(endpoint, incoming));
+            if matched.is_none_or(|(_, best)| rank > best) {
+                matched = Some((endpoint, rank));
+            }
+        }
+    }
+    matched.map(|(endpoint, _)| endpoint)
+}
+
+/// Every endpoint with the first rule it fails, and the one `find_endpoint` would pick.
+pub(crate) async fn explain(state: &ServerState, incoming: &IncomingRequest) -> MatchReport {
+    let endpoints = state.app_state.read().await;
+    let paths = state.settings.read().await.path_matching;
+    let mut best: Option<(String, ((u8, usize), bool))> = None;
+    let mut report = Vec::with_capacity(endpoints.len());
     for endpoint in endpoints.iter() {
-        if endpoint.path == path && method_matches(&method, &endpoint.method) {
-            return Response::builder()
-                .status(StatusCode::OK)
-                .header("Content-Type", "application/json")
-                .body(Body::from(endpoint.response.clone()))
-                .unwrap();
+        let result = evaluate(state, &paths, endpoint, incoming).await;
+        if let Ok(score) = result {
+            let rank = (score, explicit_method(endpoint, incoming));
+            if best.as_ref().is_none_or(|(_, top)| rank > *top) {
+                best = Some((endpoint.id.clone(), rank));
+            }
+        }
+        report.push(EndpointMatch {
+            id: endpoint.id.clone(),
+            method: endpoint.method.clone(),
+            path: endpoint.path.clone(),
+            rejected: result.err().map(|mismatch| mismatch.to_string()),
+        });
+    }
+    MatchReport {
+        matched: best.map(|(id, _)| id),
+        endpoints: report,
+    }
+}
+
+/// Whether the endpoint names the request's method rather than answering it
+/// as an automatic HEAD; breaks ties between equally specific matches.
+fn explicit_method(endpoint: &Endpoint, incoming: &IncomingRequest) -> bool {
+    endpoint.method.eq_ignore_ascii_case(incoming.method.as_str())
+}
+
+/// The first rule an endpoint fails for a request.
+#[derive(Debug)]
+enum Mismatch<'a> {
+    Disabled,
+    NotServed,
+    Method(&'a str),
+    WebSocket,
+    Path(&'a str),
+    Query(&'a str),
+    Header(&'a str),
+    Body(usize),
+    Soap(&'a str),
+    Graphql,
+    JsonRpc(&'a str),
+    Scenario(&'a str),
+}
+
+impl std::fmt::Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::Disabled => write!(f, "Endpoint is disabled"),
+            Mismatch::NotServed => write!(f, "Endpoint is pinned to another server or outside its tag filter"),
+            Mismatch::Method(method) => write!(f, "Method is not {}", method),
+            Mismatch::WebSocket => write!(f, "WebSocket endpoints only answer upgrade requests"),
+            Mismatch::Path(pattern) => write!(f, "Path does not match {}", pattern),
+            Mismatch::Query(name) => write!(f, "Query parameter '{}' does not match", name),
+            Mismatch::Header(name) => write!(f, "Header '{}' does not match", name),
+            Mismatch::Body(index) => write!(f, "Body matcher #{} does not match", index + 1),
+            Mismatch::Soap(operation) => write!(f, "SOAP operation is not {}", operation),
+            Mismatch::Graphql => write!(f, "GraphQL operation does not match"),
+            Mismatch::JsonRpc(method) => write!(f, "JSON-RPC method is not {}", method),
+            Mismatch::Scenario(name) => write!(f, "Scenario '{}' is not in the required state", name),
         }
     }
+}
 
-    Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::from(r#"{"error": "Endpoint not found"}"#))
-        .unwrap()
+async fn evaluate<'a>(
+    state: &ServerState,
+    paths: &PathMatching,
+    endpoint: &'a Endpoint,
+    incoming: &IncomingRequest,
+) -> Result<(u8, usize), Mismatch<'a>> {
+    if !endpoint.enabled {
+        return Err(Mismatch::Disabled);
+    }
+    if !state.serves(endpoint) {
+        return Err(Mismatch::NotServed);
+    }
+    match endpoint.kind {
+        EndpointKind::Mock if !method_matches(&incoming.method, &endpoint.method) => {
+            return Err(Mismatch::Method(&endpoint.method));
+        }
+        EndpointKind::WebSocket => return Err(Mismatch::WebSocket),
+        EndpointKind::Static if !matches!(incoming.method, Method::GET | Method::HEAD) => {
+            return Err(Mismatch::Method("GET"));
+        }
+        _ => {}
+    }
+    match_score(state, paths, endpoint, incoming).await
 }
 
+async fn match_score<'a>(
+    state: &ServerState,
+    paths: &PathMatching,
+    endpoint: &'a Endpoint,
+    incoming: &IncomingRequest,
+) -> Result<(u8, usize), Mismatch<'a>> {
+    let path = incoming.path.as_str();
+    let path_rank = match endpoint.match_type {
+        _ if endpoint.kind == EndpointKind::Resource => {
+            crate::resources::item_id(endpoint, path).map(|id| if id.is_none() { 2 } else { 1 })
+        }
+        _ if endpoint.kind == EndpointKind::Static => crate::static_files::relative(&endpoint.path, path).map(|_| 1),
+        MatchType::Exact if !is_wildcard(&endpoint.path) => {
+            (paths.normalize(&endpoint.path) == paths.normalize(path)).then_some(2)
+        }
+        MatchType::Exact => paths.matches(&endpoint.path, path).then_some(1),
+        MatchType::Regex => regex_matches(state, paths, &endpoint.path, path).await.then_some(1),
+    }
+    .ok_or(Mismatch::Path(&endpoint.path))?;
+
+    for matcher in &endpoint.match_query {
+        let values: Vec<&str> = incoming
+            .query
+            .iter()
+            .filter(|(name, _)| name == &matcher.name)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        if !value_matches(state, matcher.kind, &matcher.value, &values).await {
+            return Err(Mismatch::Query(&matcher.name));
+        }
+    }
+
+    for (name, expected) in &endpoint.match_headers {
+        let present = incoming
+            .headers
+            .get_all(name.as_str())
+            .iter()
+            .any(|v| v.to_str().is_ok_and(|v| v == expected));
+        if !present {
+            return Err(Mismatch::Header(name));
+        }
+    }
+
+    if let Some(index) = endpoint.match_body.iter().position(|matcher| !body_matches(matcher, incoming)) {
+        return Err(Mismatch::Body(index));
+    }
+
+    if let Some(soap) = &endpoint.soap {
+        let operations = crate::soap::request_operations(&incoming.headers, &incoming.body);
+        if !crate::soap::matches_operation(soap, &operations) {
+            return Err(Mismatch::Soap(&soap.operation));
+        }
+    }
+
+    if let Some(graphql) = &endpoint.graphql {
+        if !crate::graphql::matches(graphql, incoming) {
+            return Err(Mismatch::Graphql);
+        }
+    }
+
+    if let Some(jsonrpc) = &endpoint.jsonrpc {
+        if !crate::jsonrpc::matches(jsonrpc, incoming) {
+            return Err(Mismatch::JsonRpc(&jsonrpc.method));
+        }
+    }
+
+    if let Some(scenario) = &endpoint.scenario {
+        if !crate::scenarios::in_required_state(&state.scenarios, scenario).await {
+            return Err(Mismatch::Scenario(&scenario.name));
+        }
+    }
+
+    let conditions = endpoint.match_query.len()
+        + endpoint.match_headers.len()
+        + endpoint.match_body.len()
+        + usize::from(endpoint.soap.is_some())
+        + endpoint.graphql.as_ref().map_or(0, |g| {
+            usize::from(g.operation_name.is_some()) + usize::from(g.root_field.is_some())
+        })
+        + usize::from(endpoint.jsonrpc.is_some())
+        + usize::from(endpoint.scenario.as_ref().is_some_and(|s| s.required_state.is_some()));
+    Ok((path_rank, conditions))
+}
+
+async fn value_matches(state: &ServerState, kind: ValueMatchKind, expected: &str, values: &[&str]) -> bool {
+    match kind {
+        ValueMatchKind::Present => !values.is_empty(),
+        ValueMatchKind::Exact => values.contains(&expected),
+        ValueMatchKind::Regex => match state.regex_for(expected).await {
+            Some(re) => values.iter().any(|v| re.is_match(v)),
+            None => false,
+        },
+    }
+}
+
+fn body_matches(matcher: &BodyMatcher, incoming: &IncomingRequest) -> bool {
+    match matcher {
+        BodyMatcher::Equals { value } => incoming.json_body.as_ref() == Some(value),
+        BodyMatcher::JsonPathExists { path } => incoming
+            .json_body
+            .as_ref()
+            .is_some_and(|json| json_path_lookup(json, path).is_some()),
+        BodyMatcher::JsonPathEquals { path, value } => incoming
+            .json_body
+            .as_ref()
+            .and_then(|json| json_path_lookup(json, path))
+            == Some(value),
+        BodyMatcher::Contains { value } => String::from_utf8_lossy(&incoming.body).contains(value.as_str()),
+    }
+}
+
+/// GET endpoints also answer HEAD, without the body, unless a HEAD endpoint
+/// matches equally well.
 fn method_matches(req_method: &Method, endpoint_method: &str) -> bool {
     match endpoint_method.to_uppercase().as_str() {
-        "GET" => req_method == Method::GET,
+        "GET" => req_method == Method::GET || req_method == Method::HEAD,
+        "HEAD" => req_method == Method::HEAD,
         "POST" => req_method == Method::POST,
         "PUT" => req_method == Method::PUT,
         "DELETE" => req_method == Method::DELETE,
@@ -144,3 +1442,64 @@ fn method_matches(req_method: &Method, endpoint_method: &str) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn invalid_header_answers_500() {
+        let mut response = MockResponse::json(StatusCode::OK, "{}");
+        response.headers.push(("X-Echo".to_string(), "a\r\nInjected: 1".to_string()));
+        assert_eq!(response.into_response().await.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mut response = MockResponse::json(StatusCode::OK, "{}");
+        response.headers.push(("Bad Name".to_string(), "1".to_string()));
+        assert_eq!(response.into_response().await.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn valid_headers_are_sent() {
+        let mut response = MockResponse::json(StatusCode::CREATED, "{}");
+        response.headers.push(("X-Echo".to_string(), "hello".to_string()));
+        let response = response.into_response().await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["x-echo"], "hello");
+    }
+
+    #[tokio::test]
+    async fn custom_headers_replace_defaults() {
+        let mut response = MockResponse::json(StatusCode::OK, "<p></p>");
+        response.apply_headers(&[
+            ("content-type".to_string(), "text/html".to_string()),
+            ("X-Request-Id".to_string(), "abc".to_string()),
+        ]);
+        let response = response.into_response().await;
+        assert_eq!(response.headers().get_all("content-type").iter().count(), 1);
+        assert_eq!(response.headers()["content-type"], "text/html");
+        assert_eq!(response.headers()["x-request-id"], "abc");
+    }
+
+    #[test]
+    fn example_requests_mask_credentials() {
+        let (parts, ()) = axum::http::Request::builder()
+            .uri("/users?access_token=secret&page=2")
+            .header("Authorization", "Bearer secret")
+            .header("Cookie", "session=secret")
+            .header("X-Api-Key", "secret")
+            .header("Accept", "application/json")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let example = IncomingRequest::from_parts(&parts, Bytes::new()).example_request();
+        let value = |pairs: &[(String, String)], name: &str| {
+            pairs.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+        };
+        for name in ["authorization", "cookie", "x-api-key"] {
+            assert_eq!(value(&example.headers, name).as_deref(), Some(REDACTED));
+        }
+        assert_eq!(value(&example.headers, "accept").as_deref(), Some("application/json"));
+        assert_eq!(value(&example.query, "access_token").as_deref(), Some(REDACTED));
+        assert_eq!(value(&example.query, "page").as_deref(), Some("2"));
+    }
+}
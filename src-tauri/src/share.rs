@@ -0,0 +1,177 @@
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tower_http::cors::CorsLayer;
+
+use crate::access::AccessTokens;
+use crate::endpoints::{AppState, Endpoint, ServerSettings};
+use crate::server::{IncomingRequest, ServerHandle};
+
+/// Read-only view of the project for teammates who don't run the app.
+#[derive(Clone)]
+struct ShareState {
+    endpoints: Arc<RwLock<Vec<Endpoint>>>,
+    settings: Arc<RwLock<ServerSettings>>,
+    access_tokens: AccessTokens,
+}
+
+/// The contract of an endpoint, leaving out auth secrets, signing keys,
+/// captured requests and local file paths.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SharedEndpoint {
+    method: String,
+    path: String,
+    status: u16,
+    content_type: String,
+    body: String,
+}
+
+impl SharedEndpoint {
+    fn new(endpoint: &Endpoint) -> Self {
+        Self {
+            method: endpoint.method.clone(),
+            path: endpoint.path.clone(),
+            status: endpoint.status,
+            content_type: endpoint.content_type.clone(),
+            body: crate::docs::pretty_body(endpoint),
+        }
+    }
+}
+
+impl ShareState {
+    async fn project(&self) -> serde_json::Value {
+        let settings = self.settings.read().await;
+        let endpoints: Vec<SharedEndpoint> = self
+            .endpoints
+            .read()
+            .await
+            .iter()
+            .filter(|e| e.enabled)
+            .map(SharedEndpoint::new)
+            .collect();
+        serde_json::json!({
+            "server": {
+                "port": settings.port,
+                "bindAddr": settings.bind_addr,
+                "enableTls": settings.enable_tls,
+            },
+            "endpoints": endpoints,
+        })
+    }
+}
+
+/// Turns away requests without a valid access token while the mock server
+/// requires one, like the mock server itself.
+async fn require_access_token(State(state): State<ShareState>, request: Request, next: Next) -> Response {
+    if !state.settings.read().await.require_access_token {
+        return next.run(request).await;
+    }
+    let (parts, body) = request.into_parts();
+    let incoming = IncomingRequest::from_parts(&parts, Bytes::new());
+    let valid = match crate::access::presented_token(&incoming) {
+        Some(token) => crate::access::is_valid(&state.access_tokens, &token).await,
+        None => false,
+    };
+    if !valid {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::CONTENT_TYPE, "application/json")],
+            r#"{"error": "A valid access token is required"}"#,
+        )
+            .into_response();
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
+
+async fn docs_page(State(state): State<ShareState>) -> Html<String> {
+    Html(crate::docs::render_page("Mock API", &state.endpoints.read().await, false))
+}
+
+async fn project_json(State(state): State<ShareState>) -> impl IntoResponse {
+    axum::Json(state.project().await)
+}
+
+async fn project_yaml(State(state): State<ShareState>) -> impl IntoResponse {
+    let yaml = serde_yaml::to_string(&state.project().await).unwrap_or_default();
+    ([(header::CONTENT_TYPE, "application/yaml")], yaml)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartShareServerParams {
+    port: u16,
+    bind_addr: String,
+}
+
+/// Serves the endpoint contracts at `/project.json`, `/project.yaml` and a docs
+/// page at `/`, behind the access token when one is required.
+#[tauri::command]
+pub async fn start_share_server(
+    state: tauri::State<'_, AppState>,
+    params: StartShareServerParams,
+) -> Result<String, String> {
+    let mut handle = state.share_handle.write().await;
+    if let Some(mut existing) = handle.take() {
+        if let Some(tx) = existing.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    let share_state = ShareState {
+        endpoints: state.endpoints.clone(),
+        settings: state.server_settings.clone(),
+        access_tokens: state.access_tokens.clone(),
+    };
+    let app = Router::new()
+        .route("/", get(docs_page))
+        .route("/project.json", get(project_json))
+        .route("/project.yaml", get(project_yaml))
+        .layer(middleware::from_fn_with_state(share_state.clone(), require_access_token))
+        .layer(CorsLayer::permissive())
+        .with_state(share_state);
+
+    let addr = format!("{}:{}", params.bind_addr, params.port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            })
+            .await
+        {
+            eprintln!("Share server error: {}", e);
+        }
+    });
+
+    let mut share_handle = ServerHandle::new(params.port, params.bind_addr, false);
+    share_handle.shutdown_tx = Some(shutdown_tx);
+    let url = share_handle.base_url();
+    *handle = Some(share_handle);
+    Ok(url)
+}
+
+#[tauri::command]
+pub async fn stop_share_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let mut handle = state.share_handle.write().await;
+    match handle.take().and_then(|mut share_handle| share_handle.shutdown_tx.take()) {
+        Some(tx) => {
+            let _ = tx.send(());
+            Ok(())
+        }
+        None => Err("Share server is not running".to_string()),
+    }
+}
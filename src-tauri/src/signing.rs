@@ -0,0 +1,69 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::server::{MockBody, MockResponse};
+
+/// Integrity and signature headers computed over the response body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignatureConfig {
+    /// `Content-MD5: <base64 md5>`.
+    #[serde(default, alias = "contentMd5")]
+    pub content_md5: bool,
+    /// `Digest: SHA-256=<base64 sha256>`.
+    #[serde(default)]
+    pub digest: bool,
+    #[serde(default)]
+    pub hmac: Option<HmacSignature>,
+}
+
+/// An HMAC-SHA256 of the body in a custom header, e.g. GitHub's
+/// `X-Hub-Signature-256: sha256=<hex>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HmacSignature {
+    pub header: String,
+    pub secret: String,
+    /// Text placed before the signature, such as `sha256=`.
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub encoding: SignatureEncoding,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Adds the configured headers. File-backed bodies are streamed and left unsigned.
+pub fn sign(config: &SignatureConfig, response: &mut MockResponse) {
+    let MockBody::Bytes(body) = &response.body else {
+        return;
+    };
+    let base64 = base64::engine::general_purpose::STANDARD;
+
+    let mut headers = Vec::new();
+    if config.content_md5 {
+        headers.push(("Content-MD5".to_string(), base64.encode(Md5::digest(body))));
+    }
+    if config.digest {
+        headers.push(("Digest".to_string(), format!("SHA-256={}", base64.encode(Sha256::digest(body)))));
+    }
+    if let Some(hmac) = &config.hmac {
+        // HMAC accepts keys of any length, so this cannot fail
+        let mut mac = Hmac::<Sha256>::new_from_slice(hmac.secret.as_bytes()).expect("HMAC key");
+        mac.update(body);
+        let signature = mac.finalize().into_bytes();
+        let encoded = match hmac.encoding {
+            SignatureEncoding::Hex => hex::encode(signature),
+            SignatureEncoding::Base64 => base64.encode(signature),
+        };
+        headers.push((hmac.header.clone(), format!("{}{}", hmac.prefix, encoded)));
+    }
+    response.apply_headers(&headers);
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::ServerHandle;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateSnippetsParams {
+    id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeSnippet {
+    pub language: String,
+    pub code: String,
+}
+
+/// Generates client snippets for an endpoint against the running server
+/// (or the configured settings when the server is stopped).
+#[tauri::command]
+pub async fn generate_snippets(
+    state: tauri::State<'_, AppState>,
+    params: GenerateSnippetsParams,
+) -> Result<Vec<CodeSnippet>, String> {
+    let endpoint = state
+        .endpoints
+        .read()
+        .await
+        .iter()
+        .find(|e| e.id == params.id)
+        .cloned()
+        .ok_or_else(|| format!("Endpoint not found: {}", params.id))?;
+
+    let base_url = current_base_url(&state).await;
+    Ok(build_snippets(&endpoint, &base_url))
+}
+
+/// Base URL of the default server (or any running one), or of the configured one when stopped.
+pub async fn current_base_url(state: &AppState) -> String {
+    let servers = state.servers.read().await;
+    let running = servers
+        .get(crate::server::DEFAULT_SERVER)
+        .or_else(|| servers.values().next());
+    match running {
+        Some(handle) => handle.base_url(),
+        None => {
+            let settings = state.server_settings.read().await;
+            ServerHandle::new(settings.port, settings.bind_addr.clone(), settings.enable_tls).base_url()
+        }
+    }
+}
+
+fn build_snippets(endpoint: &Endpoint, base_url: &str) -> Vec<CodeSnippet> {
+    let url = format!("{}{}", base_url, endpoint.path);
+    let method = endpoint.method.to_uppercase();
+    let has_body = matches!(method.as_str(), "POST" | "PUT" | "PATCH");
+
+    let fetch = if has_body {
+        format!(
+            "const res = await fetch(\"{url}\", {{\n  method: \"{method}\",\n  headers: {{ \"Content-Type\": \"application/json\" }},\n  body: JSON.stringify({{}}),\n}});\nconst data = await res.json();"
+        )
+    } else {
+        format!("const res = await fetch(\"{url}\", {{ method: \"{method}\" }});\nconst data = await res.json();")
+    };
+
+    let axios = if has_body {
+        format!(
+            "const {{ data }} = await axios.request({{\n  url: \"{url}\",\n  method: \"{}\",\n  data: {{}},\n}});",
+            method.to_lowercase()
+        )
+    } else {
+        format!(
+            "const {{ data }} = await axios.request({{\n  url: \"{url}\",\n  method: \"{}\",\n}});",
+            method.to_lowercase()
+        )
+    };
+
+    let python = if has_body {
+        format!(
+            "import requests\n\nres = requests.request(\"{method}\", \"{url}\", json={{}})\nprint(res.status_code, res.text)"
+        )
+    } else {
+        format!("import requests\n\nres = requests.request(\"{method}\", \"{url}\")\nprint(res.status_code, res.text)")
+    };
+
+    let body_reader = if has_body { "strings.NewReader(\"{}\")" } else { "nil" };
+    let go = format!(
+        "req, err := http.NewRequest(\"{method}\", \"{url}\", {body_reader})\nif err != nil {{\n\tlog.Fatal(err)\n}}\n{}res, err := http.DefaultClient.Do(req)\nif err != nil {{\n\tlog.Fatal(err)\n}}\ndefer res.Body.Close()\nbody, _ := io.ReadAll(res.Body)\nfmt.Println(res.StatusCode, string(body))",
+        if has_body { "req.Header.Set(\"Content-Type\", \"application/json\")\n" } else { "" }
+    );
+
+    vec![
+        CodeSnippet { language: "fetch".to_string(), code: fetch },
+        CodeSnippet { language: "axios".to_string(), code: axios },
+        CodeSnippet { language: "python".to_string(), code: python },
+        CodeSnippet { language: "go".to_string(), code: go },
+    ]
+}
@@ -0,0 +1,276 @@
+use axum::http::{HeaderMap, StatusCode};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::endpoints::{AppState, Endpoint};
+use crate::server::MockResponse;
+
+pub const SOAP_CONTENT_TYPE: &str = "text/xml; charset=utf-8";
+const ENVELOPE_NS: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+
+/// SOAP 1.1 behavior for an endpoint: one endpoint per operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoapConfig {
+    /// Operation name matched against `SOAPAction` or the first element in the Body.
+    pub operation: String,
+    /// WSDL document served for `GET <path>?wsdl`.
+    #[serde(default)]
+    pub wsdl: Option<String>,
+    /// Respond with this SOAP Fault instead of the configured response.
+    #[serde(default)]
+    pub fault: Option<SoapFault>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoapFault {
+    /// e.g. `soap:Client` or `soap:Server`
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// The operation a SOAP request invokes, from `SOAPAction` or the Body's first element.
+pub fn request_operations(headers: &HeaderMap, body: &[u8]) -> Vec<String> {
+    let mut operations = Vec::new();
+
+    if let Some(action) = headers.get("SOAPAction").and_then(|v| v.to_str().ok()) {
+        let action = action.trim().trim_matches('"');
+        if !action.is_empty() {
+            operations.push(action.to_string());
+            if let Some(short) = action.rsplit(['/', '#']).next() {
+                operations.push(short.to_string());
+            }
+        }
+    }
+
+    if let Some(operation) = body_operation(&String::from_utf8_lossy(body)) {
+        operations.push(operation);
+    }
+    operations
+}
+
+fn body_operation(xml: &str) -> Option<String> {
+    let mut in_body = false;
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with(['?', '!', '/']) {
+            continue;
+        }
+        let end = rest.find(|c: char| c.is_whitespace() || c == '>' || c == '/')?;
+        let name = &rest[..end];
+        let local = name.rsplit(':').next().unwrap_or(name);
+
+        if in_body {
+            return Some(local.to_string());
+        }
+        if local == "Body" {
+            in_body = true;
+        }
+    }
+    None
+}
+
+pub fn matches_operation(config: &SoapConfig, operations: &[String]) -> bool {
+    operations.iter().any(|op| op == &config.operation)
+}
+
+/// Wraps the body in an envelope unless it already is one, or returns the configured fault.
+pub fn build_response(config: &SoapConfig, status: StatusCode, body: String) -> MockResponse {
+    match &config.fault {
+        Some(fault) => MockResponse::new(StatusCode::INTERNAL_SERVER_ERROR, SOAP_CONTENT_TYPE, fault_envelope(fault)),
+        None if body.contains("Envelope") => MockResponse::new(status, SOAP_CONTENT_TYPE, body),
+        None => MockResponse::new(status, SOAP_CONTENT_TYPE, envelope(&body)),
+    }
+}
+
+pub fn envelope(body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<soap:Envelope xmlns:soap=\"{}\">\n  <soap:Body>\n    {}\n  </soap:Body>\n</soap:Envelope>",
+        ENVELOPE_NS, body
+    )
+}
+
+fn fault_envelope(fault: &SoapFault) -> String {
+    let detail = fault
+        .detail
+        .as_ref()
+        .map(|d| format!("\n      <detail>{}</detail>", d))
+        .unwrap_or_default();
+    envelope(&format!(
+        "<soap:Fault>\n      <faultcode>{}</faultcode>\n      <faultstring>{}</faultstring>{}\n    </soap:Fault>",
+        xml_escape(&fault.code),
+        xml_escape(&fault.message),
+        detail
+    ))
+}
+
+pub fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A start (or self-closing) tag: its local name and attributes.
+struct Tag {
+    local: String,
+    closing: bool,
+    attrs: HashMap<String, String>,
+}
+
+fn tags(xml: &str) -> Vec<Tag> {
+    static ATTR: OnceLock<Regex> = OnceLock::new();
+    let attr = ATTR.get_or_init(|| Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+    let mut tags = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with(['?', '!']) {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let tag = tag.trim_start_matches('/');
+        let name_end = tag.find(|c: char| c.is_whitespace() || c == '/').unwrap_or(tag.len());
+        let name = &tag[..name_end];
+        let attrs = attr
+            .captures_iter(&tag[name_end..])
+            .map(|c| {
+                let value = c.get(2).or_else(|| c.get(3)).map_or("", |m| m.as_str());
+                (c[1].to_string(), value.to_string())
+            })
+            .collect();
+        tags.push(Tag {
+            local: local_name(name).to_string(),
+            closing,
+            attrs,
+        });
+    }
+    tags
+}
+
+/// `tns:GetUser` -> `GetUser`
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// One endpoint per operation of a WSDL 1.1 document, each answering with
+/// an empty output element and serving the WSDL at `<path>?wsdl`. The path
+/// comes from the service address unless one is given.
+pub fn endpoints_from_wsdl(wsdl: &str, path: Option<&str>) -> Result<Vec<Endpoint>, String> {
+    let mut namespace = String::new();
+    let mut address = None;
+    // message name -> element of its first part
+    let mut messages: HashMap<String, String> = HashMap::new();
+    // operations in portType order, with their output message
+    let mut operations: Vec<(String, Option<String>)> = Vec::new();
+    let mut current_message: Option<String> = None;
+    let mut current_operation: Option<String> = None;
+    let mut in_port_type = false;
+
+    for tag in tags(wsdl) {
+        if tag.closing {
+            match tag.local.as_str() {
+                "message" => current_message = None,
+                "portType" => in_port_type = false,
+                "operation" => current_operation = None,
+                _ => {}
+            }
+            continue;
+        }
+        let attr = |name: &str| tag.attrs.get(name).cloned();
+        match tag.local.as_str() {
+            "definitions" => namespace = attr("targetNamespace").unwrap_or_default(),
+            "message" => current_message = attr("name"),
+            "part" => {
+                if let Some(message) = &current_message {
+                    let element = attr("element").or_else(|| attr("name")).unwrap_or_default();
+                    messages.entry(message.clone()).or_insert_with(|| local_name(&element).to_string());
+                }
+            }
+            "portType" => in_port_type = true,
+            "operation" if in_port_type => {
+                current_operation = attr("name");
+                if let Some(name) = &current_operation {
+                    if !operations.iter().any(|(op, _)| op == name) {
+                        operations.push((name.clone(), None));
+                    }
+                }
+            }
+            "output" if in_port_type => {
+                let output = attr("message").map(|message| local_name(&message).to_string());
+                if let Some((_, slot)) = operations
+                    .iter_mut()
+                    .find(|(op, _)| Some(op) == current_operation.as_ref())
+                {
+                    *slot = output;
+                }
+            }
+            "address" => address = address.or_else(|| attr("location")),
+            _ => {}
+        }
+    }
+
+    if operations.is_empty() {
+        return Err("WSDL has no operations".to_string());
+    }
+    let path = match (path, &address) {
+        (Some(path), _) => path.to_string(),
+        (None, Some(location)) => url::Url::parse(location)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| "/".to_string()),
+        (None, None) => "/".to_string(),
+    };
+
+    Ok(operations
+        .into_iter()
+        .map(|(operation, output)| {
+            let element = output
+                .and_then(|message| messages.get(&message).cloned())
+                .filter(|element| !element.is_empty())
+                .unwrap_or_else(|| format!("{}Response", operation));
+            let body = format!(
+                "<tns:{} xmlns:tns=\"{}\">\n    </tns:{}>",
+                element,
+                xml_escape(&namespace),
+                element
+            );
+            let mut endpoint = Endpoint::new("POST".to_string(), path.clone(), 200, 0, body);
+            endpoint.content_type = SOAP_CONTENT_TYPE.to_string();
+            endpoint.soap = Some(SoapConfig {
+                operation,
+                wsdl: Some(wsdl.to_string()),
+                fault: None,
+            });
+            endpoint.tags.push("soap".to_string());
+            endpoint
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWsdlParams {
+    contents: String,
+    /// Path the operations answer on; defaults to the service address path.
+    path: Option<String>,
+}
+
+/// Adds an endpoint per WSDL operation and returns the new endpoints.
+#[tauri::command]
+pub async fn import_wsdl(state: tauri::State<'_, AppState>, params: ImportWsdlParams) -> Result<Vec<Endpoint>, String> {
+    state.ensure_writable().await?;
+    let imported = endpoints_from_wsdl(&params.contents, params.path.as_deref())?;
+    state.endpoints.write().await.extend(imported.iter().cloned());
+    Ok(imported)
+}
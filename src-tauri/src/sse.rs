@@ -0,0 +1,87 @@
+use axum::body::Body;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+pub const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// Events streamed by an endpoint whose `response_source` is `events`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SseConfig {
+    #[serde(default)]
+    pub events: Vec<SseEvent>,
+    /// Start over after the last event instead of closing the stream.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+    /// Sends a `: heartbeat` comment whenever the stream has been idle this long.
+    #[serde(default, alias = "heartbeatMs")]
+    pub heartbeat_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SseEvent {
+    /// Wait before sending this event.
+    #[serde(default, alias = "delayMs")]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub event: Option<String>,
+    #[serde(default)]
+    pub id: Option<String>,
+    pub data: String,
+}
+
+impl SseEvent {
+    /// Wire format; multi-line data becomes several `data:` lines.
+    fn encode(&self) -> String {
+        let mut out = String::new();
+        if let Some(id) = &self.id {
+            out.push_str(&format!("id: {}\n", id));
+        }
+        if let Some(event) = &self.event {
+            out.push_str(&format!("event: {}\n", event));
+        }
+        for line in self.data.split('\n') {
+            out.push_str(&format!("data: {}\n", line));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Streams the configured events until they run out (or forever when
+/// looping) or the client disconnects.
+pub fn stream(config: SseConfig) -> Body {
+    let (mut writer, reader) = tokio::io::duplex(16 * 1024);
+    tokio::spawn(async move {
+        let heartbeat = config.heartbeat_ms.filter(|ms| *ms > 0).map(Duration::from_millis);
+        loop {
+            for event in &config.events {
+                let mut wait = Duration::from_millis(event.delay_ms);
+                while let Some(interval) = heartbeat.filter(|interval| *interval < wait) {
+                    tokio::time::sleep(interval).await;
+                    wait -= interval;
+                    if writer.write_all(b": heartbeat\n\n").await.is_err() {
+                        return;
+                    }
+                }
+                tokio::time::sleep(wait).await;
+                // The client hung up
+                if writer.write_all(event.encode().as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            if !config.repeat || config.events.is_empty() {
+                break;
+            }
+        }
+        // Keep a finished stream open on heartbeats so clients don't reconnect and replay
+        while let Some(interval) = heartbeat {
+            tokio::time::sleep(interval).await;
+            if writer.write_all(b": heartbeat\n\n").await.is_err() {
+                return;
+            }
+        }
+    });
+    Body::from_stream(ReaderStream::new(reader))
+}
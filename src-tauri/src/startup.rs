@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::endpoints::{AppState, EndpointKind};
+
+/// An action run, in order, before a server starts accepting requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum StartupAction {
+    /// Replaces a resource endpoint's items.
+    #[serde(rename_all = "camelCase")]
+    SeedResource { endpoint_id: String, items: Vec<Value> },
+    /// Puts every resource back to its configured seed.
+    ResetResources,
+    /// Sets a variable in the named environment, or the active one.
+    SetVariable {
+        #[serde(default)]
+        environment: Option<String>,
+        name: String,
+        value: String,
+    },
+    /// Puts a scenario in the given state.
+    SetScenario { name: String, state: String },
+}
+
+/// Runs the actions against the shared state, stopping at the first that
+/// can't be applied.
+pub async fn run(state: &AppState, actions: &[StartupAction]) -> Result<(), String> {
+    for action in actions {
+        match action {
+            StartupAction::SeedResource { endpoint_id, items } => {
+                let is_resource = state
+                    .endpoints
+                    .read()
+                    .await
+                    .iter()
+                    .any(|e| e.id == *endpoint_id && e.kind == EndpointKind::Resource);
+                if !is_resource {
+                    return Err(format!("Startup hook: {} is not a resource endpoint", endpoint_id));
+                }
+                crate::resources::seed(&state.resources, &state.memory, endpoint_id, items).await;
+            }
+            StartupAction::ResetResources => crate::resources::clear(&state.resources, &state.memory, None).await,
+            StartupAction::SetVariable { environment, name, value } => {
+                let mut environments = state.environments.write().await;
+                let target = match environment.as_deref().or(environments.active.as_deref()) {
+                    Some(target) => target.to_string(),
+                    None => return Err(format!("Startup hook: no environment to set {} in", name)),
+                };
+                environments.get_or_create(&target).variables.insert(name.clone(), value.clone());
+            }
+            StartupAction::SetScenario { name, state: scenario_state } => {
+                state.scenarios.write().await.insert(name.clone(), scenario_state.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::Endpoint;
+
+    fn parse(json: &str) -> Vec<StartupAction> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn primes_resources_variables_and_scenarios() {
+        let state = AppState::new();
+        let mut users = Endpoint::new("GET".to_string(), "/users".to_string(), 200, 0, String::new());
+        users.kind = EndpointKind::Resource;
+        let id = users.id.clone();
+        state.endpoints.write().await.push(users);
+
+        let actions = parse(&format!(
+            r#"[
+                {{"action": "seedResource", "endpointId": "{}", "items": [{{"id": 1}}]}},
+                {{"action": "setVariable", "environment": "local", "name": "TOKEN", "value": "abc"}},
+                {{"action": "setScenario", "name": "checkout", "state": "paid"}}
+            ]"#,
+            id
+        ));
+        run(&state, &actions).await.unwrap();
+
+        assert_eq!(state.resources.read().await[&id][0].value, serde_json::json!({ "id": 1 }));
+        assert_eq!(state.environments.read().await.get("local").unwrap().variables["TOKEN"], "abc");
+        assert_eq!(state.scenarios.read().await["checkout"], "paid");
+    }
+
+    #[tokio::test]
+    async fn rejects_actions_that_cannot_apply() {
+        let state = AppState::new();
+        let seed = parse(r#"[{"action": "seedResource", "endpointId": "missing", "items": []}]"#);
+        assert!(run(&state, &seed).await.is_err());
+
+        let variable = parse(r#"[{"action": "setVariable", "name": "TOKEN", "value": "abc"}]"#);
+        assert!(run(&state, &variable).await.is_err());
+    }
+}
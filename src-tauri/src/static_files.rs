@@ -0,0 +1,64 @@
+use axum::http::StatusCode;
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// A local folder served under the endpoint's path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticConfig {
+    pub dir: String,
+    /// File served for directory requests.
+    #[serde(default = "default_index")]
+    pub index: String,
+    /// Serve the index for paths with no file, for client-side routing.
+    #[serde(default, alias = "spaFallback")]
+    pub spa_fallback: bool,
+}
+
+fn default_index() -> String {
+    "index.html".to_string()
+}
+
+/// The part of `path` below `prefix`, if `path` is under it.
+pub fn relative<'a>(prefix: &str, path: &'a str) -> Option<&'a str> {
+    let prefix = prefix.trim_end_matches('/');
+    let rest = path.strip_prefix(prefix)?;
+    (rest.is_empty() || rest.starts_with('/')).then(|| rest.trim_start_matches('/'))
+}
+
+/// `relative` as a path inside the folder; `None` if it would escape it.
+fn resolve(dir: &Path, relative: &str) -> Option<PathBuf> {
+    let decoded = percent_decode_str(relative).decode_utf8_lossy();
+    let mut resolved = dir.to_path_buf();
+    for component in Path::new(decoded.as_ref()).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+pub async fn handle(config: &StaticConfig, prefix: &str, incoming: &IncomingRequest) -> MockResponse {
+    let not_found = || MockResponse::json(StatusCode::NOT_FOUND, r#"{"error": "File not found"}"#);
+    let dir = Path::new(&config.dir);
+    let Some(mut file) = relative(prefix, &incoming.path).and_then(|rest| resolve(dir, rest)) else {
+        return not_found();
+    };
+
+    if tokio::fs::metadata(&file).await.is_ok_and(|m| m.is_dir()) {
+        file.push(&config.index);
+    }
+    if !tokio::fs::metadata(&file).await.is_ok_and(|m| m.is_file()) {
+        if !config.spa_fallback {
+            return not_found();
+        }
+        file = dir.join(&config.index);
+    }
+
+    let content_type = mime_guess::from_path(&file).first_or_octet_stream();
+    MockResponse::file(StatusCode::OK, content_type.as_ref(), file.to_string_lossy().to_string())
+}
@@ -0,0 +1,124 @@
+use axum::body::{Body, Bytes};
+use axum::http::Response;
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use serde::Serialize;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// Live counters for one running server.
+pub struct ServerStats {
+    /// Shared with the listener; tracks open connections and drives shutdown.
+    pub handle: axum_server::Handle,
+    in_flight: AtomicUsize,
+    served: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    started_at: Instant,
+}
+
+pub type SharedStats = Arc<ServerStats>;
+
+/// A point-in-time copy of the counters for `get_server_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSnapshot {
+    pub active_connections: usize,
+    pub in_flight: usize,
+    /// Requests answered since the server started.
+    pub served: u64,
+    pub last_error: Option<String>,
+    pub uptime_secs: u64,
+}
+
+/// Counts a request as in flight until dropped.
+pub struct InFlight(SharedStats);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+        self.0.served.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl InFlight {
+    /// Moves the count into the response body, so the request stays in
+    /// flight while a slow, throttled or streamed body is still being sent.
+    pub fn hold(self, response: Response<Body>) -> Response<Body> {
+        response.map(|body| Body::new(CountedBody { body, _in_flight: self }))
+    }
+}
+
+struct CountedBody {
+    body: Body,
+    _in_flight: InFlight,
+}
+
+impl HttpBody for CountedBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        Pin::new(&mut self.body).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+impl ServerStats {
+    pub fn new() -> SharedStats {
+        Arc::new(Self {
+            handle: axum_server::Handle::new(),
+            in_flight: AtomicUsize::new(0),
+            served: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            started_at: Instant::now(),
+        })
+    }
+
+    pub fn begin_request(self: &Arc<Self>) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlight(self.clone())
+    }
+
+    /// Logs the error and keeps it as the most recent one.
+    pub fn record_error(&self, error: String) {
+        eprintln!("{}", error);
+        if let Ok(mut last) = self.last_error.lock() {
+            *last = Some(error);
+        }
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            active_connections: self.handle.connection_count(),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            served: self.served.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().ok().and_then(|last| last.clone()),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_stay_in_flight_until_the_body_is_sent() {
+        let stats = ServerStats::new();
+        let response = stats.begin_request().hold(Response::new(Body::from("hello")));
+        assert_eq!(stats.snapshot().in_flight, 1);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello");
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.in_flight, snapshot.served), (0, 1));
+    }
+}
@@ -0,0 +1,392 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::RwLock;
+
+use crate::endpoints::{AppState, Endpoint, ServerSettings};
+use crate::environments::{Environments, SharedEnvironments};
+use crate::request_log::{RequestLog, RequestLogEntry};
+
+const STORE_FILE: &str = "fakeapi.sqlite";
+/// Present in the app data dir while persistence is turned on, so the store
+/// is reopened on the next launch.
+const ENABLED_FILE: &str = "fakeapi.sqlite.enabled";
+/// How often endpoint, settings, environment and request log changes are written out.
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS endpoints (
+    id TEXT PRIMARY KEY,
+    position INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS request_log (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    endpoint_id TEXT,
+    latency_ms INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS request_log_timestamp ON request_log (timestamp);
+";
+
+/// SQLite database under the app data dir holding the endpoints, server
+/// settings, environments and full request history.
+#[derive(Clone)]
+pub struct Store {
+    conn: Arc<Mutex<Connection>>,
+}
+
+/// An open store plus the task keeping it in sync; dropping it stops syncing.
+pub struct StoreHandle {
+    pub store: Store,
+    sync: tokio::task::AbortHandle,
+}
+
+impl Drop for StoreHandle {
+    fn drop(&mut self) {
+        self.sync.abort();
+    }
+}
+
+impl Store {
+    pub fn open(path: &std::path::Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open store: {}", e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create store tables: {}", e))?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let mut conn = self.conn.lock().map_err(|_| "Store connection poisoned".to_string())?;
+        f(&mut conn).map_err(|e| format!("Store query failed: {}", e))
+    }
+
+    /// The saved endpoints, settings and environments, or `None` if nothing
+    /// was saved yet. Stores saved before environments were persisted have none.
+    pub fn load_project(&self) -> Result<Option<StoredProject>, String> {
+        let (rows, settings, environments) = self.with_conn(|conn| {
+            let setting = |key: &str| -> rusqlite::Result<Option<String>> {
+                conn.query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+                    .optional()
+            };
+            let (settings, environments) = (setting("server")?, setting("environments")?);
+            let mut statement = conn.prepare("SELECT data FROM endpoints ORDER BY position")?;
+            let rows = statement
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok((rows, settings, environments))
+        })?;
+
+        let Some(settings) = settings else {
+            return Ok(None);
+        };
+        let settings: ServerSettings =
+            serde_json::from_str(&settings).map_err(|e| format!("Invalid stored settings: {}", e))?;
+        let environments = environments
+            .map(|data| serde_json::from_str(&data).map_err(|e| format!("Invalid stored environments: {}", e)))
+            .transpose()?;
+        let endpoints = rows
+            .iter()
+            .map(|data| serde_json::from_str(data).map_err(|e| format!("Invalid stored endpoint: {}", e)))
+            .collect::<Result<Vec<Endpoint>, String>>()?;
+        Ok(Some(StoredProject {
+            endpoints,
+            settings,
+            environments,
+        }))
+    }
+
+    pub fn save_project(
+        &self,
+        endpoints: &[Endpoint],
+        settings: &ServerSettings,
+        environments: &Environments,
+    ) -> Result<(), String> {
+        let rows = endpoints
+            .iter()
+            .map(|endpoint| Ok((endpoint.id.clone(), serde_json::to_string(endpoint)?)))
+            .collect::<Result<Vec<(String, String)>, serde_json::Error>>()
+            .map_err(|e| e.to_string())?;
+        let settings = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+        let environments = serde_json::to_string(environments).map_err(|e| e.to_string())?;
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM endpoints", [])?;
+            for (position, (id, data)) in rows.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO endpoints (id, position, data) VALUES (?1, ?2, ?3)",
+                    params![id, position as i64, data],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES ('server', ?1)",
+                params![settings],
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES ('environments', ?1)",
+                params![environments],
+            )?;
+            tx.commit()
+        })
+    }
+
+    pub fn append_log(&self, entries: &[RequestLogEntry]) -> Result<(), String> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            for entry in entries {
+                tx.execute(
+                    "INSERT OR IGNORE INTO request_log (id, timestamp, method, path, status, endpoint_id, latency_ms, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        entry.id,
+                        entry.timestamp,
+                        entry.method,
+                        entry.path,
+                        entry.status,
+                        entry.endpoint_id,
+                        entry.latency_ms as i64,
+                        serde_json::to_string(entry).unwrap_or_default(),
+                    ],
+                )?;
+            }
+            tx.commit()
+        })
+    }
+
+    pub fn query_log(&self, query: &RequestHistoryQuery) -> Result<Vec<RequestLogEntry>, String> {
+        let rows = self.with_conn(|conn| {
+            let mut statement = conn.prepare(
+                "SELECT data FROM request_log
+                 WHERE (?1 IS NULL OR method = ?1)
+                   AND (?2 IS NULL OR path LIKE '%' || ?2 || '%')
+                   AND (?3 IS NULL OR status = ?3)
+                   AND (?4 IS NULL OR endpoint_id = ?4)
+                   AND (?5 IS NULL OR timestamp >= ?5)
+                 ORDER BY timestamp DESC
+                 LIMIT ?6",
+            )?;
+            let rows = statement
+                .query_map(
+                    params![
+                        query.method.as_ref().map(|m| m.to_uppercase()),
+                        query.path,
+                        query.status,
+                        query.endpoint_id,
+                        query.since,
+                        query.limit.unwrap_or(DEFAULT_QUERY_LIMIT) as i64,
+                    ],
+                    |row| row.get::<_, String>(0),
+                )?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok(rows)
+        })?;
+        Ok(rows.iter().filter_map(|data| serde_json::from_str(data).ok()).collect())
+    }
+}
+
+const DEFAULT_QUERY_LIMIT: u32 = 200;
+
+/// What `Store::load_project` reads back.
+pub struct StoredProject {
+    pub endpoints: Vec<Endpoint>,
+    pub settings: ServerSettings,
+    pub environments: Option<Environments>,
+}
+
+/// Writes the endpoints, settings and environments whenever they change and
+/// appends new request log entries.
+fn spawn_sync(
+    store: Store,
+    endpoints: Arc<RwLock<Vec<Endpoint>>>,
+    settings: Arc<RwLock<ServerSettings>>,
+    environments: SharedEnvironments,
+    log: RequestLog,
+) -> tokio::task::AbortHandle {
+    tokio::spawn(async move {
+        let mut saved_project = String::new();
+        let mut last_logged: Option<String> = None;
+        loop {
+            tokio::time::sleep(SYNC_INTERVAL).await;
+
+            let endpoints = endpoints.read().await.clone();
+            let settings = settings.read().await.clone();
+            let environments = environments.read().await.clone();
+            let project = serde_json::to_string(&(&endpoints, &settings, &environments)).unwrap_or_default();
+            let new_entries: Vec<RequestLogEntry> = {
+                let log = log.read().await;
+                let start = last_logged
+                    .as_ref()
+                    .and_then(|id| log.iter().position(|entry| &entry.id == id))
+                    .map_or(0, |index| index + 1);
+                log.iter().skip(start).cloned().collect()
+            };
+            if let Some(last) = new_entries.last() {
+                last_logged = Some(last.id.clone());
+            }
+
+            let changed = project != saved_project;
+            let store = store.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                if changed {
+                    store.save_project(&endpoints, &settings, &environments)?;
+                }
+                store.append_log(&new_entries)
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => saved_project = project,
+                Ok(Err(e)) => eprintln!("{}", e),
+                Err(e) => eprintln!("Store sync failed: {}", e),
+            }
+        }
+    })
+    .abort_handle()
+}
+
+/// Turns SQLite persistence on or off, remembering the choice for the next
+/// launch. When turned on, a previously saved project replaces the current
+/// endpoints, settings and environments (unless the store is empty, in which
+/// case the current project is saved into it); returns the number of
+/// endpoints loaded.
+#[tauri::command]
+pub async fn set_sqlite_store(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<usize, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to locate app data dir: {}", e))?;
+    if enabled {
+        return enable(&state, dir).await;
+    }
+    *state.store.write().await = None;
+    match tokio::fs::remove_file(dir.join(ENABLED_FILE)).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to turn off the store: {}", e)),
+        _ => Ok(0),
+    }
+}
+
+/// Reopens the store at startup when persistence was left on.
+pub fn restore(app: &tauri::AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        return;
+    };
+    if !dir.join(ENABLED_FILE).exists() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = enable(&app.state::<AppState>(), dir).await {
+            eprintln!("Failed to reopen the SQLite store: {}", e);
+        }
+    });
+}
+
+async fn enable(state: &AppState, dir: PathBuf) -> Result<usize, String> {
+    let mut handle = state.store.write().await;
+    if handle.is_some() {
+        return Ok(0);
+    }
+
+    let (store, project) = tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+        let store = Store::open(&dir.join(STORE_FILE))?;
+        let project = store.load_project()?;
+        std::fs::write(dir.join(ENABLED_FILE), "").map_err(|e| format!("Failed to turn on the store: {}", e))?;
+        Ok::<_, String>((store, project))
+    })
+    .await
+    .map_err(|e| format!("Failed to open store: {}", e))??;
+
+    let loaded = match project {
+        Some(project) => {
+            crate::template::sync_partials(&state.templates, &project.settings.partials).await?;
+            let count = project.endpoints.len();
+            *state.endpoints.write().await = project.endpoints;
+            *state.server_settings.write().await = project.settings;
+            if let Some(environments) = project.environments {
+                *state.environments.write().await = environments;
+            }
+            count
+        }
+        None => 0,
+    };
+
+    let sync = spawn_sync(
+        store.clone(),
+        state.endpoints.clone(),
+        state.server_settings.clone(),
+        state.environments.clone(),
+        state.request_log.clone(),
+    );
+    *handle = Some(StoreHandle { store, sync });
+    Ok(loaded)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestHistoryQuery {
+    method: Option<String>,
+    /// Substring of the request path.
+    path: Option<String>,
+    status: Option<u16>,
+    endpoint_id: Option<String>,
+    /// RFC 3339 timestamp; older requests are left out.
+    since: Option<String>,
+    limit: Option<u32>,
+}
+
+/// Searches the persisted request history, newest first.
+#[tauri::command]
+pub async fn query_request_history(
+    state: tauri::State<'_, AppState>,
+    params: RequestHistoryQuery,
+) -> Result<Vec<RequestLogEntry>, String> {
+    let store = match &*state.store.read().await {
+        Some(handle) => handle.store.clone(),
+        None => return Err("SQLite store is not enabled".to_string()),
+    };
+    tokio::task::spawn_blocking(move || store.query_log(&params))
+        .await
+        .map_err(|e| format!("Failed to query request history: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn saves_and_loads_endpoints_settings_and_environments() {
+        let store = Store::open(std::path::Path::new(":memory:")).unwrap();
+        assert!(store.load_project().unwrap().is_none());
+
+        let settings = AppState::new().server_settings.read().await.clone();
+        let endpoint = Endpoint::new("GET".to_string(), "/users".to_string(), 200, 0, String::new());
+        let mut environments = Environments::default();
+        environments.get_or_create("local").variables.insert("TOKEN".to_string(), "abc".to_string());
+        store.save_project(&[endpoint.clone()], &settings, &environments).unwrap();
+
+        let project = store.load_project().unwrap().unwrap();
+        assert_eq!(project.endpoints.len(), 1);
+        assert_eq!(project.endpoints[0].id, endpoint.id);
+        assert_eq!(project.settings.port, settings.port);
+        assert_eq!(project.environments.unwrap().get("local").unwrap().variables["TOKEN"], "abc");
+    }
+}
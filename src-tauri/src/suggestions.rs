@@ -0,0 +1,106 @@
+use serde::Serialize;
+
+use crate::endpoints::{Endpoint, EndpointKind};
+use crate::matching::PathMatching;
+use crate::server::IncomingRequest;
+
+/// How many endpoints a 404 suggests.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A configured endpoint close to an unmatched request.
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub method: String,
+    pub path: String,
+    pub hint: String,
+}
+
+impl std::fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ({})", self.method, self.path, self.hint)
+    }
+}
+
+/// Endpoints on the same path with another method first, then the paths
+/// with the smallest edit distance.
+pub fn closest<'a>(
+    endpoints: impl Iterator<Item = &'a Endpoint>,
+    incoming: &IncomingRequest,
+    paths: &PathMatching,
+) -> Vec<Suggestion> {
+    let requested = paths.normalize(&incoming.path);
+    let mut ranked: Vec<(usize, Suggestion)> = endpoints
+        .filter(|e| e.kind != EndpointKind::WebSocket)
+        .filter_map(|endpoint| {
+            if paths.matches(&endpoint.path, &incoming.path) {
+                let hint = if endpoint.method.eq_ignore_ascii_case(incoming.method.as_str()) {
+                    "path and method match, but a query, header or body condition doesn't".to_string()
+                } else {
+                    format!("configured for {}, not {}", endpoint.method.to_uppercase(), incoming.method)
+                };
+                return Some((0, suggestion(endpoint, hint)));
+            }
+            let distance = edit_distance(&paths.normalize(&endpoint.path), &requested);
+            let tolerance = (requested.chars().count() / 3).max(3);
+            (distance <= tolerance).then(|| (distance, suggestion(endpoint, format!("path differs by {} characters", distance))))
+        })
+        .collect();
+    ranked.sort_by_key(|(distance, _)| *distance);
+    ranked.into_iter().take(MAX_SUGGESTIONS).map(|(_, s)| s).collect()
+}
+
+fn suggestion(endpoint: &Endpoint, hint: String) -> Suggestion {
+    Suggestion {
+        method: endpoint.method.to_uppercase(),
+        path: endpoint.path.clone(),
+        hint,
+    }
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("/users", ""), 6);
+        assert_eq!(edit_distance("/users", "/user"), 1);
+        assert_eq!(edit_distance("/users", "/usres"), 2);
+        assert_eq!(edit_distance("/café", "/cafe"), 1);
+    }
+
+    #[test]
+    fn suggests_other_methods_before_near_paths() {
+        let endpoint = |method: &str, path: &str| Endpoint::new(method.to_string(), path.to_string(), 200, 0, String::new());
+        let endpoints = [endpoint("GET", "/users"), endpoint("POST", "/user"), endpoint("GET", "/orders/all")];
+        let (parts, ()) = axum::http::Request::builder()
+            .method("POST")
+            .uri("/users")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let incoming = IncomingRequest::from_parts(&parts, Bytes::new());
+
+        let suggested = closest(endpoints.iter(), &incoming, &PathMatching::default());
+        let paths: Vec<&str> = suggested.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, ["/users", "/user"]);
+        assert!(suggested[0].hint.contains("configured for GET"));
+    }
+}
@@ -0,0 +1,201 @@
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderErrorReason};
+use rand::Rng;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+use tokio::sync::RwLock;
+
+/// Handlebars templates for response bodies and header values, compiled once
+/// per distinct source and recompiled only when an endpoint's response changes.
+pub struct TemplateCache {
+    registry: Handlebars<'static>,
+    sources: HashMap<String, String>,
+    /// Partials currently registered, by name.
+    partials: HashMap<String, String>,
+}
+
+impl TemplateCache {
+    pub fn new() -> Self {
+        let mut registry = Handlebars::new();
+        // Responses are JSON/XML/text, not HTML
+        registry.register_escape_fn(handlebars::no_escape);
+        register_helpers(&mut registry);
+        Self {
+            registry,
+            sources: HashMap::new(),
+            partials: HashMap::new(),
+        }
+    }
+
+    fn is_current(&self, name: &str, source: &str) -> bool {
+        self.sources.get(name).is_some_and(|s| s == source)
+    }
+
+    fn compile(&mut self, name: &str, source: &str) -> Result<(), String> {
+        self.registry
+            .register_template_string(name, source)
+            .map_err(|e| format!("Invalid template: {}", e))?;
+        self.sources.insert(name.to_string(), source.to_string());
+        Ok(())
+    }
+
+    fn render_compiled(&self, name: &str, data: &Value) -> Result<String, String> {
+        self.registry
+            .render(name, data)
+            .map_err(|e| format!("Failed to render template: {}", e))
+    }
+}
+
+/// `{{uuid}}`, `{{randomInt 1 100}}`, `{{now "iso8601"}}` and `{{fake "email"}}`.
+fn register_helpers(registry: &mut Handlebars<'static>) {
+    registry.register_helper(
+        "uuid",
+        Box::new(
+            |_: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+                out.write(&uuid::Uuid::new_v4().to_string())?;
+                Ok(())
+            },
+        ),
+    );
+
+    registry.register_helper(
+        "randomInt",
+        Box::new(
+            |h: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+                let min = h.param(0).and_then(|p| p.value().as_i64()).unwrap_or(0);
+                let max = h.param(1).and_then(|p| p.value().as_i64()).unwrap_or(100);
+                let value = if min < max { rand::thread_rng().gen_range(min..=max) } else { min };
+                out.write(&value.to_string())?;
+                Ok(())
+            },
+        ),
+    );
+
+    registry.register_helper(
+        "now",
+        Box::new(
+            |h: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+                let now = chrono::Utc::now();
+                let formatted = match h.param(0).and_then(|p| p.value().as_str()).unwrap_or("iso8601") {
+                    "iso8601" => now.to_rfc3339(),
+                    "unix" => now.timestamp().to_string(),
+                    "unix_ms" => now.timestamp_millis().to_string(),
+                    pattern => {
+                        let mut formatted = String::new();
+                        write!(formatted, "{}", now.format(pattern))
+                            .map_err(|_| RenderErrorReason::Other(format!("Invalid date format: {}", pattern)))?;
+                        formatted
+                    }
+                };
+                out.write(&formatted)?;
+                Ok(())
+            },
+        ),
+    );
+
+    registry.register_helper(
+        "fake",
+        Box::new(
+            |h: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+                let kind = h.param(0).and_then(|p| p.value().as_str()).unwrap_or("word");
+                let value = crate::faker::fake(kind)
+                    .ok_or_else(|| RenderErrorReason::Other(format!("Unknown fake data kind: {}", kind)))?;
+                out.write(&value)?;
+                Ok(())
+            },
+        ),
+    );
+}
+
+pub fn is_template(source: &str) -> bool {
+    source.contains("{{")
+}
+
+/// Renders `source` registered under `name`, compiling it first if it changed.
+pub async fn render(cache: &RwLock<TemplateCache>, name: &str, source: &str, data: &Value) -> Result<String, String> {
+    {
+        let cache = cache.read().await;
+        if cache.is_current(name, source) {
+            return cache.render_compiled(name, data);
+        }
+    }
+
+    let mut cache = cache.write().await;
+    if !cache.is_current(name, source) {
+        cache.compile(name, source)?;
+    }
+    cache.render_compiled(name, data)
+}
+
+/// Registers `partials` for `{{> name}}`, dropping ones no longer defined.
+/// Compiled templates look partials up when rendering, so they pick up edits.
+/// Called when settings are saved; if any partial doesn't compile, nothing
+/// changes and the error names it.
+pub async fn sync_partials(cache: &RwLock<TemplateCache>, partials: &HashMap<String, String>) -> Result<(), String> {
+    if cache.read().await.partials == *partials {
+        return Ok(());
+    }
+    for (name, source) in partials {
+        handlebars::Template::compile(source).map_err(|e| format!("Invalid partial {}: {}", name, e))?;
+    }
+
+    let mut cache = cache.write().await;
+    let stale: Vec<String> = cache
+        .partials
+        .keys()
+        .filter(|name| !partials.contains_key(*name))
+        .cloned()
+        .collect();
+    for name in stale {
+        cache.registry.unregister_template(&name);
+    }
+    for (name, source) in partials {
+        cache
+            .registry
+            .register_partial(name, source)
+            .map_err(|e| format!("Invalid partial {}: {}", name, e))?;
+    }
+    cache.partials = partials.clone();
+    Ok(())
+}
+
+/// Header values with templates rendered; plain values are passed through.
+/// Each value is cached under `<name>#header<index>`.
+pub async fn render_headers(
+    cache: &RwLock<TemplateCache>,
+    name: &str,
+    headers: &[(String, String)],
+    data: &Value,
+) -> Result<Vec<(String, String)>, String> {
+    let mut rendered = Vec::with_capacity(headers.len());
+    for (index, (header, value)) in headers.iter().enumerate() {
+        let value = if is_template(value) {
+            render(cache, &format!("{}#header{}", name, index), value, data).await?
+        } else {
+            value.clone()
+        };
+        rendered.push((header.clone(), value));
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partials(source: &str) -> HashMap<String, String> {
+        HashMap::from([("user".to_string(), source.to_string())])
+    }
+
+    #[tokio::test]
+    async fn invalid_partials_leave_the_registered_ones() {
+        let cache = RwLock::new(TemplateCache::new());
+        sync_partials(&cache, &partials(r#"{"name": "{{name}}"}"#)).await.unwrap();
+        let error = sync_partials(&cache, &partials("{{#if}")).await.unwrap_err();
+        assert!(error.contains("user"));
+
+        let data = serde_json::json!({ "name": "Ann" });
+        let rendered = render(&cache, "endpoint", "{{> user}}", &data).await.unwrap();
+        assert_eq!(rendered, r#"{"name": "Ann"}"#);
+    }
+}
@@ -0,0 +1,30 @@
+use axum::body::Body;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+/// Streams `source` as a response body at roughly `bytes_per_sec`.
+pub fn throttled<R>(mut source: R, bytes_per_sec: u64) -> Body
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let (mut writer, reader) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let mut buf = vec![0; (bytes_per_sec / 10).clamp(1, 64 * 1024) as usize];
+        let start = tokio::time::Instant::now();
+        let mut sent: u64 = 0;
+        loop {
+            let read = match source.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            // The client hung up
+            if writer.write_all(&buf[..read]).await.is_err() {
+                break;
+            }
+            sent += read as u64;
+            tokio::time::sleep_until(start + Duration::from_secs_f64(sent as f64 / bytes_per_sec as f64)).await;
+        }
+    });
+    Body::from_stream(ReaderStream::new(reader))
+}
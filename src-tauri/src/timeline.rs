@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+
+use crate::endpoints::{apply_changes, Endpoint};
+
+/// A change applied to one endpoint `at_ms` after the server starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineStep {
+    #[serde(alias = "atMs")]
+    pub at_ms: u64,
+    #[serde(alias = "endpointId")]
+    pub endpoint_id: String,
+    /// Serialized `Endpoint` fields to overwrite, as accepted by `update_endpoint`.
+    pub changes: Map<String, Value>,
+}
+
+/// Runs the steps in time order against the live endpoints; abort the
+/// returned handle to cancel the rest of the timeline. Steps targeting a
+/// locked endpoint are skipped.
+pub fn spawn(endpoints: Arc<RwLock<Vec<Endpoint>>>, mut steps: Vec<TimelineStep>) -> Option<AbortHandle> {
+    if steps.is_empty() {
+        return None;
+    }
+    steps.sort_by_key(|step| step.at_ms);
+
+    let task = tokio::spawn(async move {
+        let start = tokio::time::Instant::now();
+        for step in steps {
+            tokio::time::sleep_until(start + Duration::from_millis(step.at_ms)).await;
+
+            let mut endpoints = endpoints.write().await;
+            let Some(endpoint) = endpoints.iter_mut().find(|e| e.id == step.endpoint_id) else {
+                eprintln!("Timeline step at {}ms: endpoint not found: {}", step.at_ms, step.endpoint_id);
+                continue;
+            };
+            if let Err(e) = endpoint.ensure_unlocked() {
+                eprintln!("Timeline step at {}ms skipped: {}", step.at_ms, e);
+                continue;
+            }
+            match apply_changes(endpoint, step.changes) {
+                Ok(updated) => *endpoint = updated,
+                Err(e) => eprintln!("Timeline step at {}ms failed: {}", step.at_ms, e),
+            }
+        }
+    });
+    Some(task.abort_handle())
+}
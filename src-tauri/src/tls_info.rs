@@ -0,0 +1,63 @@
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::RustlsAcceptor;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::server::TlsStream;
+use tower_http::add_extension::AddExtension;
+
+use crate::connect_delay::DelayAcceptor;
+
+/// What the client negotiated during the TLS handshake, attached to every
+/// request on the connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsInfo {
+    /// e.g. `TLSv1_3`
+    pub version: Option<String>,
+    /// e.g. `TLS13_AES_128_GCM_SHA256`
+    pub cipher: Option<String>,
+    /// ALPN protocol, e.g. `h2` or `http/1.1`; `None` if the client offered none.
+    pub alpn: Option<String>,
+}
+
+/// Performs the TLS handshake, then records its outcome as a request extension.
+#[derive(Clone)]
+pub struct TlsInfoAcceptor {
+    inner: RustlsAcceptor<DelayAcceptor>,
+}
+
+impl TlsInfoAcceptor {
+    pub fn new(inner: RustlsAcceptor<DelayAcceptor>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<I, S> Accept<I, S> for TlsInfoAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = TlsStream<I>;
+    type Service = AddExtension<S, TlsInfo>;
+    type Future = Pin<Box<dyn Future<Output = io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let acceptor = self.inner.clone();
+        Box::pin(async move {
+            let (stream, service) = acceptor.accept(stream, service).await?;
+            let (_, session) = stream.get_ref();
+            let info = TlsInfo {
+                version: session.protocol_version().map(|version| format!("{:?}", version)),
+                cipher: session
+                    .negotiated_cipher_suite()
+                    .map(|suite| format!("{:?}", suite.suite())),
+                alpn: session
+                    .alpn_protocol()
+                    .map(|protocol| String::from_utf8_lossy(protocol).to_string()),
+            };
+            Ok((stream, AddExtension::new(service, info)))
+        })
+    }
+}
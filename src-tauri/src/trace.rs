@@ -0,0 +1,95 @@
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+use crate::server::{IncomingRequest, MockResponse};
+
+/// Request header that turns tracing on, with `1` or `true`.
+pub const DEBUG_HEADER: &str = "x-fakeapi-debug";
+pub const TRACE_HEADER: &str = "X-FakeAPI-Trace";
+
+/// How the server arrived at a response, sent back as compact JSON in
+/// `X-FakeAPI-Trace`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trace {
+    #[serde(skip)]
+    pub enabled: bool,
+    pub endpoint_id: Option<String>,
+    /// Endpoints on the same path that didn't match, with the rule each failed.
+    pub rules: Vec<String>,
+    /// Decisions made after matching: sequence steps, variants, profiles, faults.
+    pub steps: Vec<String>,
+    pub delay_ms: u64,
+    /// Template variables the response refers to, with their values.
+    pub variables: Map<String, Value>,
+}
+
+impl Trace {
+    pub fn new(incoming: &IncomingRequest) -> Self {
+        let enabled = incoming
+            .headers
+            .get(DEBUG_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    pub fn step(&mut self, step: impl FnOnce() -> String) {
+        if self.enabled {
+            self.steps.push(step());
+        }
+    }
+
+    /// Records every path in `{{...}}` expressions of `sources` that resolves
+    /// against `context`, so helper names and literals are skipped.
+    pub fn record_variables<'a>(&mut self, sources: impl IntoIterator<Item = &'a str>, context: &Value) {
+        if !self.enabled {
+            return;
+        }
+        static EXPRESSION: OnceLock<Regex> = OnceLock::new();
+        let expression = EXPRESSION.get_or_init(|| Regex::new(r"\{\{\{?([^}]*)\}").unwrap());
+        for source in sources {
+            for captures in expression.captures_iter(source) {
+                for token in captures[1].split_whitespace() {
+                    let token = token.trim_start_matches(['#', '/', '(']).trim_end_matches(')');
+                    if self.variables.contains_key(token) {
+                        continue;
+                    }
+                    if let Some(value) = crate::matching::json_path_lookup(context, token) {
+                        self.variables.insert(token.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the trace header when tracing is on.
+    pub fn attach(&self, response: &mut MockResponse) {
+        if !self.enabled {
+            return;
+        }
+        let json = serde_json::to_string(self).unwrap_or_default();
+        response.headers.push((TRACE_HEADER.to_string(), ascii_escape(&json)));
+    }
+}
+
+/// Header values must be visible ASCII, so anything else becomes a JSON `\u` escape.
+fn ascii_escape(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() && !c.is_ascii_control() {
+            escaped.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
@@ -0,0 +1,22 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::endpoints::Endpoint;
+use crate::sequence::SequenceStep;
+
+/// A response picked at random in proportion to `weight`, e.g. 90 for a 200
+/// and 10 for a 503.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseVariant {
+    pub weight: u32,
+    #[serde(flatten)]
+    pub response: SequenceStep,
+}
+
+/// The endpoint with a randomly chosen variant applied, or `None` when it has
+/// no variants or all weights are zero.
+pub fn pick(endpoint: &Endpoint) -> Option<Endpoint> {
+    let index = WeightedIndex::new(endpoint.variants.iter().map(|variant| variant.weight)).ok()?;
+    let variant = &endpoint.variants[index.sample(&mut rand::thread_rng())];
+    Some(variant.response.apply(endpoint))
+}
@@ -0,0 +1,90 @@
+use axum::extract::ws::{Message, WebSocket};
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Behaviour of a `websocket` endpoint once a client has connected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// Sent as soon as the connection opens.
+    #[serde(default, alias = "onConnect")]
+    pub on_connect: Vec<String>,
+    /// Checked in order against each text message; the first match answers.
+    #[serde(default)]
+    pub replies: Vec<WebSocketReply>,
+    /// Send back messages no reply matched.
+    #[serde(default)]
+    pub echo: bool,
+    #[serde(default)]
+    pub push: Option<WebSocketPush>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketReply {
+    /// Substring the incoming message must contain; empty matches every message.
+    #[serde(default)]
+    pub contains: String,
+    pub response: String,
+}
+
+/// Messages pushed on a timer, cycling through `messages`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketPush {
+    #[serde(alias = "intervalMs")]
+    pub interval_ms: u64,
+    pub messages: Vec<String>,
+}
+
+pub fn is_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(axum::http::header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+pub async fn serve(mut socket: WebSocket, config: WebSocketConfig) {
+    for message in &config.on_connect {
+        if socket.send(Message::Text(message.clone().into())).await.is_err() {
+            return;
+        }
+    }
+
+    let push = config.push.as_ref().filter(|push| push.interval_ms > 0 && !push.messages.is_empty());
+    // Without pushes the interval's branch is disabled, so its period doesn't matter
+    let period = push.map_or(Duration::from_secs(3600), |push| Duration::from_millis(push.interval_ms));
+    let mut interval = tokio::time::interval(period);
+    // The first tick completes immediately; pushes start one interval after connecting
+    interval.tick().await;
+    let mut pushed = 0;
+
+    loop {
+        let outgoing = tokio::select! {
+            received = socket.recv() => match received {
+                Some(Ok(Message::Text(text))) => reply(&config, text.as_str()).map(|text| Message::Text(text.into())),
+                Some(Ok(Message::Binary(data))) => config.echo.then_some(Message::Binary(data)),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => None,
+            },
+            _ = interval.tick(), if push.is_some() => {
+                let messages = &push.expect("guarded by the select condition").messages;
+                let message = messages[pushed % messages.len()].clone();
+                pushed += 1;
+                Some(Message::Text(message.into()))
+            }
+        };
+        if let Some(message) = outgoing {
+            if socket.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn reply(config: &WebSocketConfig, text: &str) -> Option<String> {
+    config
+        .replies
+        .iter()
+        .find(|reply| text.contains(reply.contains.as_str()))
+        .map(|reply| reply.response.clone())
+        .or_else(|| config.echo.then(|| text.to_string()))
+}